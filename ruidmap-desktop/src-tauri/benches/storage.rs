@@ -0,0 +1,68 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ruidmap_desktop_lib::models::{RoadmapData, TaskStatus};
+use ruidmap_desktop_lib::seed::seed_demo_data;
+use ruidmap_desktop_lib::storage::Storage;
+use std::path::PathBuf;
+
+const SIZES: [u32; 3] = [100, 1_000, 10_000];
+
+fn seeded_storage(task_count: u32) -> Storage {
+    let path: PathBuf = std::env::temp_dir().join(format!("ruidmap-bench-{}.json", task_count));
+    let mut storage = Storage::new_with_path(path).expect("Failed to open benchmark data file");
+
+    let mut data = RoadmapData::default();
+    seed_demo_data(&mut data, task_count, 10);
+    storage.save_data(&data).expect("Failed to write benchmark data");
+
+    storage
+}
+
+fn bench_load_save(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load_save");
+    for size in SIZES {
+        let mut storage = seeded_storage(size);
+        let data = storage.load_data().unwrap();
+
+        group.bench_with_input(BenchmarkId::new("read_from_disk", size), &size, |b, _| {
+            b.iter(|| black_box(storage.read_from_disk().unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("load_data", size), &size, |b, _| {
+            b.iter(|| black_box(storage.load_data().unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("save_data", size), &size, |b, _| {
+            b.iter(|| storage.save_data(black_box(&data)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("queries");
+    for size in SIZES {
+        let storage = seeded_storage(size);
+
+        group.bench_with_input(BenchmarkId::new("get_task_by_id", size), &size, |b, &size| {
+            b.iter(|| black_box(storage.get_task_by_id(size / 2).unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("get_tasks_by_status", size), &size, |b, _| {
+            b.iter(|| black_box(storage.get_tasks_by_status(TaskStatus::InProgress).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn bench_mutations(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mutations");
+    group.sample_size(20);
+    for size in SIZES {
+        let mut storage = seeded_storage(size);
+
+        group.bench_with_input(BenchmarkId::new("update_task", size), &size, |b, &size| {
+            b.iter(|| storage.update_task(size / 2, None, None, Some(TaskStatus::InProgress), None).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_load_save, bench_queries, bench_mutations);
+criterion_main!(benches);
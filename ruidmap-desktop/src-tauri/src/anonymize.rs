@@ -0,0 +1,183 @@
+use crate::models::{Attachment, EscalationAction, RoadmapData};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+// Scrambled copy of the workspace for attaching to bug reports. Every word is replaced by a
+// random one of the same length and case, while punctuation, whitespace, ids, dates, numbers of
+// items and all settings that aren't free text are kept. The same word always becomes the same
+// replacement within one export, so #tags still match tags, "- [ ] item" lines still match
+// subtasks and @handles still match members. Secrets (tokens, webhook URLs) are removed.
+
+pub fn anonymize(data: &RoadmapData) -> RoadmapData {
+    let mut scrambler = Scrambler::new(&uuid::Uuid::new_v4().to_string());
+    let mut data = data.clone();
+
+    for task in &mut data.tasks {
+        task.title = scrambler.text(&task.title);
+        task.description = scrambler.text(&task.description);
+        scrambler.list(&mut task.tags);
+        for subtask in &mut task.subtasks {
+            subtask.title = scrambler.text(&subtask.title);
+        }
+        for comment in &mut task.comments {
+            comment.text = scrambler.text(&comment.text);
+            comment.author = scrambler.text(&comment.author);
+        }
+        scrambler.attachments(&mut task.attachments);
+        task.reviewer = task.reviewer.as_deref().map(|r| scrambler.text(r));
+        task.custom_fields = std::mem::take(&mut task.custom_fields).into_iter()
+            .map(|(name, value)| (scrambler.text(&name), scrambler.text(&value)))
+            .collect();
+        task.external_id = task.external_id.as_deref().map(|id| scrambler.text(id));
+    }
+
+    for project in &mut data.projects {
+        project.name = scrambler.text(&project.name);
+        project.description = project.description.as_deref().map(|d| scrambler.text(d));
+        scrambler.list(&mut project.settings.default_tags);
+        if let Some(template) = &mut project.settings.task_template {
+            template.title_prefix = template.title_prefix.as_deref().map(|p| scrambler.text(p));
+            template.default_description = template.default_description.as_deref().map(|d| scrambler.text(d));
+            scrambler.list(&mut template.default_tags);
+        }
+    }
+
+    for member in &mut data.members {
+        member.name = scrambler.text(&member.name);
+        member.handle = scrambler.text(&member.handle);
+    }
+    for notification in &mut data.notifications {
+        notification.author = scrambler.text(&notification.author);
+        notification.excerpt = scrambler.text(&notification.excerpt);
+    }
+
+    let settings = &mut data.settings;
+    settings.ical_feed.token = None;
+    settings.share_board.token = None;
+    settings.inbox.token = None;
+    settings.email_inbox.password = None;
+    settings.email_inbox.username = scrambler.text(&settings.email_inbox.username);
+    settings.email_inbox.host = scrambler.text(&settings.email_inbox.host);
+    settings.remote_backup.target = None;
+    settings.remote_backup.passphrase = None;
+    settings.webhooks.slack_url = None;
+    settings.webhooks.discord_url = None;
+    let templates = &mut settings.webhooks.templates;
+    for template in [&mut templates.task_completed, &mut templates.task_overdue, &mut templates.task_due_soon, &mut templates.project_completed] {
+        *template = scrambler.template(template);
+    }
+    for rule in &mut settings.escalation.rules {
+        match &mut rule.action {
+            EscalationAction::AddTag { tag } => *tag = scrambler.text(tag),
+            EscalationAction::Notify { message } => *message = scrambler.template(message),
+            EscalationAction::BumpPriority => {}
+        }
+    }
+    for holiday in &mut settings.working_days.holidays {
+        holiday.name = scrambler.text(&holiday.name);
+    }
+
+    data
+}
+
+struct Scrambler {
+    salt: String,
+    words: HashMap<String, String>, // Lowercased word -> lowercase replacement
+}
+
+impl Scrambler {
+    fn new(salt: &str) -> Self {
+        Scrambler { salt: salt.to_string(), words: HashMap::new() }
+    }
+
+    fn text(&mut self, text: &str) -> String {
+        self.scramble(text, false)
+    }
+
+    // Leaves {placeholders} intact so the template still renders
+    fn template(&mut self, text: &str) -> String {
+        self.scramble(text, true)
+    }
+
+    fn list(&mut self, items: &mut [String]) {
+        for item in items {
+            *item = self.text(item);
+        }
+    }
+
+    fn attachments(&mut self, attachments: &mut [Attachment]) {
+        for attachment in attachments {
+            attachment.filename = self.text(&attachment.filename);
+            attachment.file_path = self.text(&attachment.file_path);
+            attachment.url = attachment.url.as_deref().map(|u| self.text(u));
+        }
+    }
+
+    fn scramble(&mut self, text: &str, keep_placeholders: bool) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut word = String::new();
+        let mut in_placeholder = false;
+        for c in text.chars() {
+            if c.is_alphanumeric() && !in_placeholder {
+                word.push(c);
+                continue;
+            }
+            self.flush(&mut word, &mut result);
+            if keep_placeholders {
+                in_placeholder = match c {
+                    '{' => true,
+                    '}' => false,
+                    _ => in_placeholder,
+                };
+            }
+            result.push(c);
+        }
+        self.flush(&mut word, &mut result);
+        result
+    }
+
+    fn flush(&mut self, word: &mut String, result: &mut String) {
+        if word.is_empty() {
+            return;
+        }
+        let replacement = self.replacement(word);
+        // Carry the original's capitalization over
+        for (original, new) in word.chars().zip(replacement.chars()) {
+            if original.is_uppercase() {
+                result.extend(new.to_uppercase());
+            } else {
+                result.push(new);
+            }
+        }
+        word.clear();
+    }
+
+    fn replacement(&mut self, word: &str) -> String {
+        let key = word.to_lowercase();
+        if let Some(replacement) = self.words.get(&key) {
+            return replacement.clone();
+        }
+
+        // Digits stay digits and letters stay letters, so dates and versions in text keep their shape
+        let mut bytes = Vec::new();
+        let mut block = 0u32;
+        let replacement: String = word.chars().enumerate().map(|(i, c)| {
+            while bytes.len() <= i {
+                let mut hasher = Sha256::new();
+                hasher.update(self.salt.as_bytes());
+                hasher.update(key.as_bytes());
+                hasher.update(block.to_le_bytes());
+                bytes.extend_from_slice(&hasher.finalize());
+                block += 1;
+            }
+            if c.is_ascii_digit() {
+                (b'0' + bytes[i] % 10) as char
+            } else {
+                (b'a' + bytes[i] % 26) as char
+            }
+        }).collect();
+
+        self.words.insert(key, replacement.clone());
+        replacement
+    }
+}
@@ -0,0 +1,272 @@
+use crate::board_import::{self, BoardImportResult, ImportedItem, RemoteBoard};
+use crate::csv_import;
+use crate::mime;
+use crate::models::{RoadmapData, TaskStatus};
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+// Imports Azure DevOps work items, either from a CSV exported from a query ("Export to CSV" in the
+// query results) or by running a query through the REST API. Both are read by the field display
+// names ("Work Item Type", "Remaining Work", ...): the work item type becomes a tag, the state a
+// status, priority 1/2/3-4 high/medium/low and remaining work (hours) the estimate. Assignee, area,
+// iteration and any other column are kept as custom fields. Removed work items are left out.
+
+const API_VERSION: &str = "7.0";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// Most ids the work items batch endpoint takes per request
+const BATCH_SIZE: usize = 200;
+const DEFAULT_QUERY: &str = "SELECT [System.Id] FROM WorkItems WHERE [System.TeamProject] = @project AND [System.State] <> 'Removed' ORDER BY [System.Id]";
+
+// Reference name -> the display name used as CSV header
+const FIELDS: [(&str, &str); 17] = [
+    ("System.Id", "ID"),
+    ("System.WorkItemType", "Work Item Type"),
+    ("System.Title", "Title"),
+    ("System.State", "State"),
+    ("System.Reason", "Reason"),
+    ("System.AssignedTo", "Assigned To"),
+    ("System.Tags", "Tags"),
+    ("System.Description", "Description"),
+    ("System.AreaPath", "Area Path"),
+    ("System.IterationPath", "Iteration Path"),
+    ("System.TeamProject", "Team Project"),
+    ("System.CreatedDate", "Created Date"),
+    ("Microsoft.VSTS.Common.Priority", "Priority"),
+    ("Microsoft.VSTS.Scheduling.RemainingWork", "Remaining Work"),
+    ("Microsoft.VSTS.Scheduling.DueDate", "Due Date"),
+    ("Microsoft.VSTS.Scheduling.TargetDate", "Target Date"),
+    ("Microsoft.VSTS.TCM.ReproSteps", "Repro Steps"),
+];
+
+// Read into their own task fields rather than kept as custom fields
+const MAPPED_FIELDS: [&str; 11] = [
+    "ID", "Work Item Type", "Title", "State", "Tags", "Description", "Created Date",
+    "Priority", "Remaining Work", "Due Date", "Target Date",
+];
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AzureDevopsSource {
+    Csv {
+        content: String,
+    },
+    Query {
+        organization: String, // Name, or the full URL of the organization or collection
+        project: String,
+        token: String, // Personal access token with Work Items (Read); not stored
+        #[serde(default)]
+        query_id: Option<String>, // A saved query; otherwise `wiql` runs
+        #[serde(default)]
+        wiql: Option<String>, // Defaults to every work item of the project that isn't removed
+    },
+}
+
+pub fn fetch(source: &AzureDevopsSource) -> Result<RemoteBoard> {
+    let (name, rows) = match source {
+        AzureDevopsSource::Csv { content } => read_csv(content)?,
+        AzureDevopsSource::Query { organization, project, token, query_id, wiql } => {
+            let rows = query(organization, project, token, query_id.as_deref(), wiql.as_deref())?;
+            (project.trim().to_string(), rows)
+        }
+    };
+
+    let mut board = RemoteBoard { name, ..Default::default() };
+    for (i, row) in rows.iter().enumerate() {
+        match to_item(row) {
+            Ok(Some(item)) => board.items.push(item),
+            Ok(None) => {}
+            Err(e) => board.skipped.push(match row.get("ID") {
+                Some(id) => format!("Work item {}: {}", id, e),
+                None => format!("Row {}: {}", i + 2, e),
+            }),
+        }
+    }
+    Ok(board)
+}
+
+pub fn apply(
+    data: &mut RoadmapData,
+    board: RemoteBoard,
+    project_id: Option<u32>,
+    status_map: &HashMap<String, TaskStatus>,
+) -> Result<BoardImportResult> {
+    board_import::apply(data, "Azure DevOps", board, project_id, status_map)
+}
+
+// The project's name (from the first row that has one) and every row by header
+fn read_csv(content: &str) -> Result<(String, Vec<HashMap<String, String>>)> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut reader = csv_import::reader(content, csv_import::sniff_delimiter(content));
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+    if !headers.iter().any(|h| h.eq_ignore_ascii_case("ID")) {
+        return Err(anyhow!("No ID column; export the query results from Azure DevOps with the ID field"));
+    }
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let row: HashMap<String, String> = headers.iter().zip(record.iter())
+            .filter(|(_, value)| !value.trim().is_empty())
+            .map(|(header, value)| (display_name(header), value.trim().to_string()))
+            .collect();
+        if !row.is_empty() {
+            rows.push(row);
+        }
+    }
+
+    let name = rows.iter()
+        .find_map(|row| row.get("Team Project").cloned()
+            .or_else(|| row.get("Area Path").and_then(|p| p.split('\\').next()).map(str::to_string)))
+        .unwrap_or_else(|| "Azure DevOps".to_string());
+    Ok((name, rows))
+}
+
+// Exports differ in header case ("Work item type"); known fields use the canonical name
+fn display_name(header: &str) -> String {
+    FIELDS.iter()
+        .map(|(_, name)| *name)
+        .find(|name| name.eq_ignore_ascii_case(header))
+        .unwrap_or(header)
+        .to_string()
+}
+
+fn query(organization: &str, project: &str, token: &str, query_id: Option<&str>, wiql: Option<&str>) -> Result<Vec<HashMap<String, String>>> {
+    if token.trim().is_empty() {
+        return Err(anyhow!("A personal access token is required"));
+    }
+    let organization = organization.trim().trim_end_matches('/');
+    let base = if organization.contains("://") {
+        format!("{}/{}", organization, encode(project.trim()))
+    } else {
+        format!("https://dev.azure.com/{}/{}", encode(organization), encode(project.trim()))
+    };
+    let auth = format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(format!(":{}", token.trim())));
+    let agent = ureq::AgentBuilder::new()
+        .timeout(REQUEST_TIMEOUT)
+        .redirects(0)
+        .build();
+    let send = |request: ureq::Request, body: Option<Value>| -> Result<Value> {
+        let request = request
+            .query("api-version", API_VERSION)
+            .set("Authorization", &auth);
+        let response = match body {
+            Some(body) => request.send_json(body),
+            None => request.call(),
+        }.map_err(|e| match e {
+            ureq::Error::Status(401, _) => anyhow!("Azure DevOps rejected the token"),
+            ureq::Error::Status(404, _) => anyhow!("Project or query not found"),
+            ureq::Error::Status(_, response) => match response.into_json::<Value>() {
+                Ok(body) => anyhow!("Azure DevOps: {}", body["message"].as_str().unwrap_or("request failed")),
+                Err(_) => anyhow!("Azure DevOps request failed"),
+            },
+            e => anyhow!("Azure DevOps request failed: {}", e),
+        })?;
+        // An invalid token gets the sign-in page (a 203, or a redirect to it) instead of an error
+        if matches!(response.status(), 203 | 300..=399) {
+            return Err(anyhow!("Azure DevOps rejected the token"));
+        }
+        Ok(response.into_json()?)
+    };
+
+    let result = match query_id.map(str::trim).filter(|id| !id.is_empty()) {
+        Some(id) => send(agent.get(&format!("{}/_apis/wit/wiql/{}", base, encode(id))), None)?,
+        None => {
+            let wiql = wiql.map(str::trim).filter(|q| !q.is_empty()).unwrap_or(DEFAULT_QUERY);
+            send(agent.post(&format!("{}/_apis/wit/wiql", base)), Some(json!({ "query": wiql })))?
+        }
+    };
+
+    // Flat queries list work items, tree and direct links queries list links between them
+    let mut ids: Vec<u64> = result["workItems"].as_array().into_iter().flatten()
+        .chain(result["workItemRelations"].as_array().into_iter().flatten().map(|r| &r["target"]))
+        .filter_map(|item| item["id"].as_u64())
+        .collect();
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+
+    let fields: Vec<&str> = FIELDS.iter().map(|(reference, _)| *reference).collect();
+    let mut rows = Vec::new();
+    for batch in ids.chunks(BATCH_SIZE) {
+        let response = send(
+            agent.post(&format!("{}/_apis/wit/workitemsbatch", base)),
+            Some(json!({ "ids": batch, "fields": fields, "errorPolicy": "omit" })),
+        )?;
+        for work_item in response["value"].as_array().into_iter().flatten() {
+            rows.push(row(&work_item["fields"]));
+        }
+    }
+    Ok(rows)
+}
+
+// REST fields as they'd appear in a CSV export
+fn row(fields: &Value) -> HashMap<String, String> {
+    FIELDS.iter().filter_map(|(reference, name)| {
+        let value = match &fields[*reference] {
+            Value::String(text) => text.clone(),
+            Value::Number(number) => number.to_string(),
+            // Identities, e.g. Assigned To
+            Value::Object(identity) => identity.get("displayName")?.as_str()?.to_string(),
+            _ => return None,
+        };
+        Some((name.to_string(), value)).filter(|(_, value)| !value.trim().is_empty())
+    }).collect()
+}
+
+// None for removed work items
+fn to_item(row: &HashMap<String, String>) -> Result<Option<ImportedItem>> {
+    let get = |name: &str| row.get(name).map(String::as_str);
+    let id = get("ID").ok_or_else(|| anyhow!("no ID"))?;
+    let state = get("State").unwrap_or_default();
+    if state.eq_ignore_ascii_case("Removed") {
+        return Ok(None);
+    }
+    // Tree query exports spread the title over "Title 1", "Title 2", ... by depth
+    let title = get("Title")
+        .or_else(|| (1..10).find_map(|depth| get(&format!("Title {}", depth))))
+        .ok_or_else(|| anyhow!("no title"))?;
+
+    let mut item = ImportedItem {
+        external_id: format!("azure-devops:{}", id),
+        title: title.to_string(),
+        description: mime::html_to_text(get("Description").or(get("Repro Steps")).unwrap_or_default()),
+        column: Some(state.to_string()).filter(|s| !s.is_empty()),
+        priority: get("Priority").and_then(|p| csv_import::parse_priority(p).ok()),
+        due_date: get("Due Date").or(get("Target Date")).map(csv_import::parse_due_date).transpose()?,
+        estimated_time: get("Remaining Work").map(|hours| {
+            hours.replace(',', ".").parse::<f64>()
+                .ok()
+                .filter(|h| h.is_finite() && *h >= 0.0)
+                .map(|h| (h * 60.0).round() as u32)
+                .ok_or_else(|| anyhow!("Remaining Work \"{}\" is not a number of hours", hours))
+        }).transpose()?,
+        created_at: get("Created Date").and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok()).map(|d| d.to_rfc3339()),
+        custom_fields: BTreeMap::new(),
+        ..Default::default()
+    };
+
+    if let Some(work_item_type) = get("Work Item Type") {
+        item.tags.push(work_item_type.to_lowercase().split_whitespace().collect::<Vec<_>>().join("-"));
+    }
+    item.tags.extend(get("Tags").unwrap_or_default().split(';').map(str::trim).filter(|t| !t.is_empty()).map(str::to_string));
+
+    for (name, value) in row {
+        let is_title = name == "Title" || name.strip_prefix("Title ").is_some_and(|depth| depth.parse::<u32>().is_ok());
+        if !MAPPED_FIELDS.contains(&name.as_str()) && !is_title && name != "Repro Steps" {
+            item.custom_fields.insert(name.clone(), value.clone());
+        }
+    }
+
+    Ok(Some(item))
+}
+
+// Percent-encodes a URL path segment
+fn encode(segment: &str) -> String {
+    segment.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
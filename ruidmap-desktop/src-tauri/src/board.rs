@@ -0,0 +1,151 @@
+use crate::models::{RoadmapData, Task, TaskPriority, TaskStatus};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+// Kanban board grouped server-side: columns by one property and optional swimlanes by another,
+// e.g. status x assignee. Every lane has one cell per column, in column order.
+
+const UNASSIGNED: &str = "unassigned";
+const UNTAGGED: &str = "untagged";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BoardGrouping {
+    Status,
+    Priority,
+    Assignee,
+    Tag, // A task with several tags shows up under each of them
+}
+
+#[derive(Debug, Serialize)]
+pub struct Board {
+    pub project_id: u32,
+    pub group_by: BoardGrouping,
+    pub lane_by: Option<BoardGrouping>,
+    pub columns: Vec<BoardGroup>,
+    pub lanes: Vec<BoardLane>, // A single lane with an empty key when lane_by is None
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BoardGroup {
+    pub key: String, // Status/priority as serialized, a member id, a tag, or "unassigned"/"untagged"
+    pub title: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BoardLane {
+    pub key: String,
+    pub title: String,
+    pub cells: Vec<Vec<Task>>,
+    pub task_count: usize,
+}
+
+pub fn board(data: &RoadmapData, project_id: u32, group_by: BoardGrouping, lane_by: Option<BoardGrouping>) -> Result<Board> {
+    if lane_by == Some(group_by) {
+        return Err(anyhow!("Columns and swimlanes must be grouped by different properties"));
+    }
+    let mut tasks: Vec<&Task> = data.tasks.iter().filter(|t| t.project_id == project_id).collect();
+    tasks.sort_by_key(|t| (priority_rank(&t.priority), t.due_date.clone().unwrap_or_else(|| "~".to_string()), t.id));
+
+    let columns = groups(data, &tasks, group_by);
+    let lane_groups = match lane_by {
+        Some(lane_by) => groups(data, &tasks, lane_by),
+        None => vec![BoardGroup { key: String::new(), title: String::new() }],
+    };
+
+    let mut lanes: Vec<BoardLane> = lane_groups.into_iter().map(|lane| BoardLane {
+        key: lane.key,
+        title: lane.title,
+        cells: vec![Vec::new(); columns.len()],
+        task_count: 0,
+    }).collect();
+
+    for task in tasks {
+        let task_lanes = match lane_by {
+            Some(lane_by) => keys(data, task, lane_by),
+            None => vec![String::new()],
+        };
+        for lane_key in &task_lanes {
+            let Some(lane) = lanes.iter_mut().find(|l| &l.key == lane_key) else {
+                continue;
+            };
+            let mut placed = false;
+            for column_key in keys(data, task, group_by) {
+                if let Some(i) = columns.iter().position(|c| c.key == column_key) {
+                    lane.cells[i].push(task.clone());
+                    placed = true;
+                }
+            }
+            if placed {
+                lane.task_count += 1;
+            }
+        }
+    }
+
+    // Fixed lanes (statuses, priorities) stay even when empty; members and tags only when used
+    if matches!(lane_by, Some(BoardGrouping::Assignee | BoardGrouping::Tag)) {
+        lanes.retain(|l| l.task_count > 0);
+    }
+
+    Ok(Board { project_id, group_by, lane_by, columns, lanes })
+}
+
+// Every possible group, in display order
+fn groups(data: &RoadmapData, tasks: &[&Task], grouping: BoardGrouping) -> Vec<BoardGroup> {
+    let group = |key: &str, title: &str| BoardGroup { key: key.to_string(), title: title.to_string() };
+    match grouping {
+        BoardGrouping::Status => vec![
+            group("todo", "To Do"),
+            group("in-progress", "In Progress"),
+            group("done", "Done"),
+        ],
+        BoardGrouping::Priority => vec![
+            group("high", "High"),
+            group("medium", "Medium"),
+            group("low", "Low"),
+        ],
+        BoardGrouping::Assignee => {
+            let mut members: Vec<_> = data.members.iter().collect();
+            members.sort_by_key(|m| m.name.to_lowercase());
+            let mut groups: Vec<BoardGroup> = members.into_iter()
+                .map(|m| group(&m.id.to_string(), &m.name))
+                .collect();
+            groups.push(group(UNASSIGNED, "Unassigned"));
+            groups
+        }
+        BoardGrouping::Tag => {
+            let mut tags: Vec<&String> = tasks.iter().flat_map(|t| t.tags.iter()).collect();
+            tags.sort_by_key(|t| t.to_lowercase());
+            tags.dedup();
+            let mut groups: Vec<BoardGroup> = tags.into_iter().map(|t| group(t, t)).collect();
+            groups.push(group(UNTAGGED, "No tag"));
+            groups
+        }
+    }
+}
+
+fn keys(data: &RoadmapData, task: &Task, grouping: BoardGrouping) -> Vec<String> {
+    match grouping {
+        BoardGrouping::Status => vec![match task.status {
+            TaskStatus::Todo => "todo",
+            TaskStatus::InProgress => "in-progress",
+            TaskStatus::Done => "done",
+        }.to_string()],
+        BoardGrouping::Priority => vec![task.priority.to_string()],
+        // An assignee who is no longer a member counts as unassigned
+        BoardGrouping::Assignee => vec![task.assignee
+            .filter(|id| data.members.iter().any(|m| m.id == *id))
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| UNASSIGNED.to_string())],
+        BoardGrouping::Tag if task.tags.is_empty() => vec![UNTAGGED.to_string()],
+        BoardGrouping::Tag => task.tags.clone(),
+    }
+}
+
+fn priority_rank(priority: &TaskPriority) -> u8 {
+    match priority {
+        TaskPriority::High => 0,
+        TaskPriority::Medium => 1,
+        TaskPriority::Low => 2,
+    }
+}
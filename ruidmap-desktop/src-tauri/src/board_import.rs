@@ -0,0 +1,164 @@
+use crate::models::{Project, RoadmapData, Task, TaskPriority, TaskStatus};
+use crate::palette;
+use crate::status_mapping;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+// Shared by the importers that read boards from other trackers over their APIs. Each importer
+// turns the remote items into `ImportedItem`s; `apply` adds them to a project and, on a repeat
+// import, updates the tasks created last time (matched by external_id) instead of duplicating them.
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportedItem {
+    pub external_id: String, // "<source>:<id>"
+    pub title: String,
+    pub description: String,
+    pub column: Option<String>, // Board column / workflow state, mapped to a status
+    pub closed: bool, // Closed upstream; used when the item isn't in a column
+    pub priority: Option<TaskPriority>,
+    pub due_date: Option<String>,
+    pub estimated_time: Option<u32>, // minutes
+    pub time_spent: Option<u32>, // minutes tracked upstream
+    pub tags: Vec<String>,
+    pub custom_fields: BTreeMap<String, String>,
+    pub created_at: Option<String>,
+}
+
+// Everything read from the remote tracker, fetched before the storage lock is taken
+#[derive(Debug, Default)]
+pub struct RemoteBoard {
+    pub name: String,
+    pub items: Vec<ImportedItem>,
+    pub skipped: Vec<String>, // Items that couldn't be read, with the reason
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct BoardImportResult {
+    pub project_id: u32,
+    pub project_created: bool,
+    pub imported_tasks: usize,
+    pub updated_tasks: usize,
+    pub skipped: Vec<String>, // Items that couldn't be imported, with the reason
+    pub unmapped_columns: Vec<String>, // Columns imported as To Do for lack of a mapping
+}
+
+// Adds the items to `project_id`, or to a project named after the board (created if needed).
+// Columns are mapped through `status_map`, then the project's status mapping, then by their usual
+// names ("Backlog", "In progress", "Done", ...).
+pub fn apply(
+    data: &mut RoadmapData,
+    source: &str,
+    board: RemoteBoard,
+    project_id: Option<u32>,
+    status_map: &HashMap<String, TaskStatus>,
+) -> Result<BoardImportResult> {
+    let board_name = board.name.as_str();
+    let mut project_created = false;
+    let project_id = match project_id {
+        Some(id) => {
+            data.projects.iter().find(|p| p.id == id).ok_or_else(|| anyhow!("Project with id {} not found", id))?;
+            id
+        }
+        None => match data.projects.iter().find(|p| p.name.eq_ignore_ascii_case(board_name.trim())) {
+            Some(existing) => existing.id,
+            None => {
+                let id = data.projects.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+                let color = palette::next_color(&data.projects);
+                data.projects.push(Project::new_with_details(
+                    id,
+                    board_name.trim().to_string(),
+                    format!("Imported from {}", source),
+                    Some(color),
+                    Some(palette::DEFAULT_ICON.to_string()),
+                ));
+                project_created = true;
+                id
+            }
+        },
+    };
+    let mut result = BoardImportResult { project_id, project_created, skipped: board.skipped, ..Default::default() };
+    let project_mapping = data.projects.iter()
+        .find(|p| p.id == project_id)
+        .map(|p| p.settings.status_mapping.clone())
+        .unwrap_or_default();
+
+    let mut next_task_id = data.tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    for item in board.items {
+        if item.title.trim().is_empty() {
+            result.skipped.push(format!("{}: no title", item.external_id));
+            continue;
+        }
+
+        let status = match item.column.as_deref() {
+            Some(column) => status_mapping::status(column, status_map.iter().chain(&project_mapping)).unwrap_or_else(|| {
+                if !result.unmapped_columns.iter().any(|c| c == column) {
+                    result.unmapped_columns.push(column.to_string());
+                }
+                TaskStatus::Todo
+            }),
+            None if item.closed => TaskStatus::Done,
+            None => TaskStatus::Todo,
+        };
+
+        let existing = data.tasks.iter_mut()
+            .find(|t| t.project_id == result.project_id && t.external_id.as_deref() == Some(item.external_id.as_str()));
+        let task = match existing {
+            Some(task) => {
+                result.updated_tasks += 1;
+                task
+            }
+            None => {
+                let mut task = Task::new(next_task_id, result.project_id, String::new(), String::new());
+                task.external_id = Some(item.external_id.clone());
+                if let Some(created_at) = &item.created_at {
+                    task.created_at = created_at.clone();
+                }
+                next_task_id += 1;
+                result.imported_tasks += 1;
+                data.tasks.push(task);
+                data.tasks.last_mut().ok_or_else(|| anyhow!("Failed to add task"))?
+            }
+        };
+
+        // Remote fields win; local comments, time tracking and the like are kept. Tasks are only
+        // touched where something changed, so a re-import doesn't make everything look fresh.
+        let title = item.title.trim().to_string();
+        if task.title != title || task.description != item.description {
+            task.update_content(title, item.description);
+        }
+        if task.status != status {
+            task.update_status(status);
+        }
+        let mut changed = false;
+        if let Some(priority) = item.priority.filter(|p| *p != task.priority) {
+            task.priority = priority;
+            changed = true;
+        }
+        if task.due_date != item.due_date {
+            task.due_date = item.due_date;
+            changed = true;
+        }
+        if item.estimated_time.is_some() && task.estimated_time != item.estimated_time {
+            task.estimated_time = item.estimated_time;
+            changed = true;
+        }
+        // Time tracked here since the last import is kept
+        if let Some(minutes) = item.time_spent.filter(|m| *m > task.time_spent) {
+            task.time_spent = minutes;
+            changed = true;
+        }
+        if task.custom_fields != item.custom_fields {
+            task.custom_fields = item.custom_fields;
+            changed = true;
+        }
+        if changed {
+            task.updated_at = chrono::Utc::now().to_rfc3339();
+        }
+        for tag in item.tags {
+            task.add_tag(tag);
+        }
+    }
+
+    Ok(result)
+}
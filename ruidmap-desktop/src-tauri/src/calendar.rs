@@ -0,0 +1,99 @@
+use crate::models::LocaleSettings;
+use anyhow::{anyhow, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+// Calendar periods and date display following the workspace locale settings
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Period {
+    Week,
+    Month,
+    Quarter, // Fiscal quarter
+    FiscalYear,
+}
+
+pub fn validate(locale: &LocaleSettings) -> Result<()> {
+    if !(1..=12).contains(&locale.fiscal_year_start_month) {
+        return Err(anyhow!("Fiscal year start month must be between 1 and 12"));
+    }
+    if locale.date_format.trim().is_empty()
+        || StrftimeItems::new(&locale.date_format).any(|item| matches!(item, Item::Error))
+    {
+        return Err(anyhow!("Invalid date format \"{}\"", locale.date_format));
+    }
+    Ok(())
+}
+
+// First and last day (inclusive) of the period containing `date`
+pub fn period_bounds(period: Period, date: NaiveDate, locale: &LocaleSettings) -> (NaiveDate, NaiveDate) {
+    match period {
+        Period::Week => {
+            let start = week_start(date, locale);
+            (start, start + Duration::days(6))
+        }
+        Period::Month => {
+            let start = month_start(date.year(), date.month());
+            (start, add_months(start, 1) - Duration::days(1))
+        }
+        Period::Quarter => {
+            let year_start = fiscal_year_start(date, locale);
+            let months_in = months_between(year_start, date);
+            let start = add_months(year_start, months_in / 3 * 3);
+            (start, add_months(start, 3) - Duration::days(1))
+        }
+        Period::FiscalYear => {
+            let start = fiscal_year_start(date, locale);
+            (start, add_months(start, 12) - Duration::days(1))
+        }
+    }
+}
+
+pub fn week_start(date: NaiveDate, locale: &LocaleSettings) -> NaiveDate {
+    let first = locale.week_start.to_chrono().num_days_from_monday();
+    let offset = (date.weekday().num_days_from_monday() + 7 - first) % 7;
+    date - Duration::days(offset as i64)
+}
+
+// A fiscal year is named after the calendar year it starts in
+pub fn fiscal_year_start(date: NaiveDate, locale: &LocaleSettings) -> NaiveDate {
+    let month = locale.fiscal_year_start_month.clamp(1, 12);
+    let year = if date.month() >= month { date.year() } else { date.year() - 1 };
+    month_start(year, month)
+}
+
+// Local calendar day of an RFC 3339 timestamp
+pub fn local_date(timestamp: &str) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|d| d.with_timezone(&Local).date_naive())
+}
+
+pub fn format_date(date: NaiveDate, locale: &LocaleSettings) -> String {
+    if validate(locale).is_err() {
+        return date.format("%Y-%m-%d").to_string();
+    }
+    date.format(&locale.date_format).to_string()
+}
+
+// Timestamps that can't be parsed are returned as they are
+pub fn format_timestamp(timestamp: &str, locale: &LocaleSettings) -> String {
+    local_date(timestamp)
+        .map(|d| format_date(d, locale))
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn month_start(year: i32, month: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, 1).expect("first of the month is always valid")
+}
+
+fn add_months(date: NaiveDate, months: u32) -> NaiveDate {
+    let total = date.month0() + months;
+    month_start(date.year() + (total / 12) as i32, total % 12 + 1)
+}
+
+fn months_between(start: NaiveDate, date: NaiveDate) -> u32 {
+    ((date.year() - start.year()) * 12 + date.month() as i32 - start.month() as i32).max(0) as u32
+}
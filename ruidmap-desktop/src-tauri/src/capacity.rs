@@ -0,0 +1,179 @@
+use crate::calendar;
+use crate::models::{CapacitySettings, RoadmapData, TaskStatus};
+use anyhow::{anyhow, Result};
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+// Soft capacity planning: the remaining estimate of every open task is spread evenly over
+// the days between today and its due date, then summed per week and compared to capacity.
+// Sizes are planned the same way against a capacity in points, for teams that don't estimate
+// in hours.
+
+const MAX_PLAN_WEEKS: i64 = 104;
+
+#[derive(Debug, Serialize)]
+pub struct CapacityPlan {
+    pub weeks: Vec<CapacityWeek>,
+    pub over_committed_weeks: usize,
+    pub unscheduled_minutes: u32, // Open, estimated tasks without a due date
+    pub unscheduled_points: u32, // Open, sized tasks without a due date
+    pub unestimated_tasks: usize, // Open tasks with a due date in range but neither estimate nor size
+}
+
+#[derive(Debug, Serialize)]
+pub struct CapacityWeek {
+    pub week_start: String,
+    pub week_end: String,
+    pub label: String,
+    pub capacity_minutes: u32,
+    pub planned_minutes: u32,
+    pub capacity_points: f64, // 0 when no weekly points are set
+    pub planned_points: f64,
+    pub over_committed: bool,
+    pub tasks: Vec<PlannedTask>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlannedTask {
+    pub task_id: u32,
+    pub title: String,
+    pub due_date: String,
+    pub minutes: u32, // Share of the remaining estimate falling into this week
+    pub points: f64, // Share of the size falling into this week
+}
+
+pub fn validate(settings: &CapacitySettings) -> Result<()> {
+    if !settings.weekly_hours.is_finite() || settings.weekly_hours < 0.0 {
+        return Err(anyhow!("Weekly capacity must be zero or more hours"));
+    }
+    if !settings.weekly_points.is_finite() || settings.weekly_points < 0.0 {
+        return Err(anyhow!("Weekly capacity must be zero or more points"));
+    }
+    for week in &settings.overrides {
+        NaiveDate::parse_from_str(&week.week_start, "%Y-%m-%d")
+            .map_err(|e| anyhow!("Invalid week {}: {}", week.week_start, e))?;
+        if !week.hours.is_finite() || week.hours < 0.0 {
+            return Err(anyhow!("Capacity for week {} must be zero or more hours", week.week_start));
+        }
+    }
+    Ok(())
+}
+
+pub fn plan(
+    data: &RoadmapData,
+    start: NaiveDate,
+    end: NaiveDate,
+    project_id: Option<u32>,
+    today: NaiveDate,
+) -> Result<CapacityPlan> {
+    if end < start {
+        return Err(anyhow!("The end of the range is before its start"));
+    }
+    let locale = &data.settings.locale;
+    let first_week = calendar::week_start(start, locale);
+    let last_week = calendar::week_start(end, locale);
+    if (last_week - first_week).num_weeks() >= MAX_PLAN_WEEKS {
+        return Err(anyhow!("Capacity plans are limited to {} weeks", MAX_PLAN_WEEKS));
+    }
+
+    let mut week_starts = Vec::new();
+    let mut week = first_week;
+    while week <= last_week {
+        week_starts.push(week);
+        week += Duration::weeks(1);
+    }
+
+    let capacity = &data.settings.capacity;
+    let mut weeks: Vec<CapacityWeek> = week_starts.iter().map(|&week| {
+        let hours = capacity.overrides.iter()
+            .find(|o| NaiveDate::parse_from_str(&o.week_start, "%Y-%m-%d").ok() == Some(week))
+            .map(|o| o.hours)
+            .unwrap_or(capacity.weekly_hours);
+        // Overrides are in hours; a week with fewer hours gets proportionally fewer points
+        let points = if capacity.weekly_hours > 0.0 {
+            capacity.weekly_points * hours / capacity.weekly_hours
+        } else {
+            capacity.weekly_points
+        };
+        CapacityWeek {
+            week_start: week.format("%Y-%m-%d").to_string(),
+            week_end: (week + Duration::days(6)).format("%Y-%m-%d").to_string(),
+            label: calendar::format_date(week, locale),
+            capacity_minutes: (hours * 60.0).round() as u32,
+            planned_minutes: 0,
+            capacity_points: points,
+            planned_points: 0.0,
+            over_committed: false,
+            tasks: Vec::new(),
+        }
+    }).collect();
+
+    let mut unscheduled_minutes = 0;
+    let mut unscheduled_points = 0;
+    let mut unestimated_tasks = 0;
+    let open_tasks = data.tasks.iter()
+        .filter(|t| t.status != TaskStatus::Done)
+        .filter(|t| project_id.is_none_or(|id| t.project_id == id));
+
+    for task in open_tasks {
+        let remaining = task.estimated_time.map(|e| e.saturating_sub(task.time_spent));
+        let Some(due) = task.due_date.as_deref().and_then(calendar::local_date) else {
+            unscheduled_minutes += remaining.unwrap_or(0);
+            unscheduled_points += task.size.unwrap_or(0);
+            continue;
+        };
+        let remaining = remaining.unwrap_or(0);
+        let points = task.size.unwrap_or(0);
+        if remaining == 0 && points == 0 {
+            if task.estimated_time.is_none() && task.size.is_none() && due >= start && due <= end {
+                unestimated_tasks += 1;
+            }
+            continue;
+        }
+
+        // Overdue work all lands on today
+        let work_end = due.max(today);
+        let days = (work_end - today).num_days() + 1;
+        let per_day = remaining as f64 / days as f64;
+        let points_per_day = points as f64 / days as f64;
+
+        for (week, &week_start) in weeks.iter_mut().zip(&week_starts) {
+            let overlap_start = today.max(week_start);
+            let overlap_end = work_end.min(week_start + Duration::days(6));
+            if overlap_end < overlap_start {
+                continue;
+            }
+            let overlap_days = ((overlap_end - overlap_start).num_days() + 1) as f64;
+            let minutes = (per_day * overlap_days).round() as u32;
+            let points = points_per_day * overlap_days;
+            if minutes == 0 && points == 0.0 {
+                continue;
+            }
+            week.planned_minutes += minutes;
+            week.planned_points += points;
+            week.tasks.push(PlannedTask {
+                task_id: task.id,
+                title: task.title.clone(),
+                due_date: due.format("%Y-%m-%d").to_string(),
+                minutes,
+                points,
+            });
+        }
+    }
+
+    for week in &mut weeks {
+        // Points only count once a points capacity is set; the tolerance absorbs rounding from
+        // spreading sizes over days
+        week.over_committed = week.planned_minutes > week.capacity_minutes
+            || (capacity.weekly_points > 0.0 && week.planned_points > week.capacity_points + 1e-6);
+        week.tasks.sort_by(|a, b| a.due_date.cmp(&b.due_date));
+    }
+
+    Ok(CapacityPlan {
+        over_committed_weeks: weeks.iter().filter(|w| w.over_committed).count(),
+        weeks,
+        unscheduled_minutes,
+        unscheduled_points,
+        unestimated_tasks,
+    })
+}
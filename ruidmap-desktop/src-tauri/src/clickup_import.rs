@@ -0,0 +1,151 @@
+use crate::board_import::{self, BoardImportResult, ImportedItem, RemoteBoard};
+use crate::csv_import;
+use crate::duration;
+use crate::models::{RoadmapData, TaskPriority, TaskStatus};
+use anyhow::{anyhow, Result};
+use chrono::{TimeZone, Utc};
+use std::collections::{BTreeMap, HashMap};
+
+// Imports a ClickUp CSV export. Every list becomes a project named "<Space> / <List>", statuses
+// are mapped like board columns (custom ones through `status_map`), and time estimated/tracked,
+// which ClickUp exports in milliseconds, become the estimate and the time spent. Assignees,
+// folders and custom fields are kept as custom fields.
+
+// Exported columns read into their own task fields, or left out as redundant
+const MAPPED_COLUMNS: [&str; 22] = [
+    "Task ID", "Task Name", "Task Content", "Status", "Priority", "Tags",
+    "Date Created", "Date Created Text", "Due Date", "Due Date Text",
+    "Time Estimated", "Time Estimated Text", "Time Logged", "Time Logged Text",
+    "Space Name", "List Name", "Date Updated", "Date Updated Text",
+    "Date Closed", "Date Closed Text", "Date Done", "Date Done Text",
+];
+const MILLIS_PER_MINUTE: f64 = 60_000.0;
+
+// One board per list, in the order the lists first appear
+pub fn read(content: &str) -> Result<Vec<RemoteBoard>> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut reader = csv_import::reader(content, csv_import::sniff_delimiter(content));
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+    if !headers.iter().any(|h| h == "Task ID") || !headers.iter().any(|h| h == "Task Name") {
+        return Err(anyhow!("Not a ClickUp export: the Task ID and Task Name columns are missing"));
+    }
+
+    let mut boards: Vec<RemoteBoard> = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record?;
+        let row: HashMap<&str, &str> = headers.iter().map(String::as_str)
+            .zip(record.iter().map(str::trim))
+            .filter(|(_, value)| !value.is_empty() && *value != "null")
+            .collect();
+        if row.is_empty() {
+            continue;
+        }
+
+        let name = match (row.get("Space Name"), row.get("List Name")) {
+            (Some(space), Some(list)) => format!("{} / {}", space, list),
+            (Some(name), None) | (None, Some(name)) => name.to_string(),
+            (None, None) => "ClickUp".to_string(),
+        };
+        let index = match boards.iter().position(|b| b.name == name) {
+            Some(index) => index,
+            None => {
+                boards.push(RemoteBoard { name, ..Default::default() });
+                boards.len() - 1
+            }
+        };
+
+        match to_item(&row) {
+            Ok(item) => boards[index].items.push(item),
+            Err(e) => boards[index].skipped.push(match row.get("Task ID") {
+                Some(id) => format!("Task {}: {}", id, e),
+                None => format!("Row {}: {}", i + 2, e),
+            }),
+        }
+    }
+    Ok(boards)
+}
+
+pub fn apply(
+    data: &mut RoadmapData,
+    boards: Vec<RemoteBoard>,
+    status_map: &HashMap<String, TaskStatus>,
+) -> Result<Vec<BoardImportResult>> {
+    boards.into_iter()
+        .map(|board| board_import::apply(data, "ClickUp", board, None, status_map))
+        .collect()
+}
+
+fn to_item(row: &HashMap<&str, &str>) -> Result<ImportedItem> {
+    let get = |name: &str| row.get(name).copied();
+    let id = get("Task ID").ok_or_else(|| anyhow!("no Task ID"))?;
+
+    let mut item = ImportedItem {
+        external_id: format!("clickup:{}", id),
+        title: get("Task Name").unwrap_or_default().to_string(),
+        description: get("Task Content").unwrap_or_default().to_string(),
+        column: get("Status").map(str::to_string),
+        priority: get("Priority").map(priority).transpose()?,
+        due_date: match get("Due Date") {
+            Some(due) => Some(timestamp(due).map_or_else(|| csv_import::parse_due_date(due), Ok)?),
+            None => None,
+        },
+        estimated_time: minutes(get("Time Estimated"), get("Time Estimated Text"))?,
+        time_spent: minutes(get("Time Logged"), get("Time Logged Text"))?,
+        tags: list(get("Tags").unwrap_or_default()),
+        created_at: get("Date Created").and_then(timestamp),
+        custom_fields: BTreeMap::new(),
+        ..Default::default()
+    };
+
+    for (column, value) in row {
+        if MAPPED_COLUMNS.contains(column) {
+            continue;
+        }
+        let value = match *column {
+            "Assignees" | "Watchers" => list(value).join(", "),
+            _ => value.to_string(),
+        };
+        if !value.is_empty() {
+            item.custom_fields.insert(column.to_string(), value);
+        }
+    }
+
+    Ok(item)
+}
+
+// "urgent"/"high"/"normal"/"low", or 1-4 in the same order
+fn priority(value: &str) -> Result<TaskPriority> {
+    match value {
+        "1" | "2" => Ok(TaskPriority::High),
+        "3" => Ok(TaskPriority::Medium),
+        "4" => Ok(TaskPriority::Low),
+        _ => csv_import::parse_priority(value),
+    }
+}
+
+// Unix milliseconds, as in the Date Created and Due Date columns
+fn timestamp(value: &str) -> Option<String> {
+    let millis: i64 = value.parse().ok()?;
+    Utc.timestamp_millis_opt(millis).single().map(|t| t.to_rfc3339())
+}
+
+// Milliseconds, or the text column ("2 hours 30 minutes") when that's all there is
+fn minutes(millis: Option<&str>, text: Option<&str>) -> Result<Option<u32>> {
+    if let Some(millis) = millis {
+        let millis: f64 = millis.parse().map_err(|_| anyhow!("\"{}\" is not a number of milliseconds", millis))?;
+        if millis.is_finite() && millis >= 0.0 {
+            return Ok(Some((millis / MILLIS_PER_MINUTE).round() as u32));
+        }
+    }
+    text.map(duration::parse_minutes).transpose()
+}
+
+// Lists are exported as "[a, b]"
+fn list(value: &str) -> Vec<String> {
+    value.trim_start_matches('[').trim_end_matches(']')
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -1,14 +1,67 @@
-use crate::models::{Task, TaskCreateRequest, TaskUpdateRequest, TaskStatus, Project, ProjectCreateRequest, ProjectUpdateRequest};
-use crate::storage::Storage;
-use std::sync::Mutex;
-use tauri::State;
+use crate::anonymize;
+use crate::board::{self, Board, BoardGrouping};
+use crate::calendar::{self, Period};
+use crate::capacity::{self, CapacityPlan};
+use crate::clickup_import;
+use crate::board_import::BoardImportResult;
+use crate::csv_import::{self, CsvImportResult, CsvMapping, CsvPreview};
+use crate::duration::{self, DurationInput};
+use crate::models::{RoadmapData, Task, TaskCreateRequest, TaskUpdateRequest, TaskStatus, ReviewState, TaskStaleness, Project, ProjectCreateRequest, ProjectUpdateRequest, TimeSession, WebhookSettings, EscalationSettings, DescriptionFormat, Member, Notification, LastSelection, OnboardingState, LocaleSettings, CapacitySettings, WorkingDaysSettings, WeeklyReviewSettings, EmailInboxSettings, RemoteBackupSettings};
+use crate::escalation::{self, EscalationOutcome};
+use crate::export;
+use crate::github_import::{self, GithubImportOptions};
+use crate::azure_devops_import::{self, AzureDevopsSource};
+use crate::schema;
+use crate::share::{ShareBoardInfo, ShareBoardServer, ShareBoardState};
+use crate::search::{self, SearchResults};
+use crate::secrets;
+use crate::seed::{self, SampleWorkspace, SeedResult};
+use crate::sizing;
+use crate::snippet;
+use crate::status_mapping;
+use crate::links;
+use crate::location::{self, DataLocation, RelocationResult};
+use crate::markdown::{self, DescriptionMetadata};
+use crate::opml::{self, OpmlImportResult};
+use crate::overview::{self, WorkspaceOverview};
+use crate::palette::{self, ColorPalette};
+use crate::mentions;
+use crate::migrations;
+use crate::ical::{generate_token, IcalFeedInfo, IcalFeedServer, IcalFeedState};
+use crate::inbox::{InboxInfo, InboxServer, InboxState};
+use crate::email_inbox::{self, EmailPollResult};
+use crate::remote_backup::{self, RemoteBackup};
+use crate::sync::{self, SyncResult};
+use crate::integrity;
+use crate::templates::{self, ExportScope, TemplateInfo};
+use crate::trash::{self, PendingDelete, PendingDeletes};
+use crate::persistence::PersistenceStatus;
+use crate::storage::{RestorePreview, RestoreSelection, Storage, StorageFormat};
+use crate::webhooks;
+use crate::weekly_review::{self, ReviewPacket};
+use crate::workdays::{self, DueDateSuggestion};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::RwLock;
+use tauri::{AppHandle, State};
 
-pub struct AppState(pub Mutex<Storage>);
+// Read-only commands share the lock; anything that saves takes it exclusively
+pub struct AppState(pub RwLock<Storage>);
 
+// Network calls block for up to their timeout, so they run on the blocking pool rather than on
+// one of the async runtime's few workers
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(work).await.map_err(|e| format!("Background task failed: {}", e))
+}
+
+// `fields` limits each returned task to the named properties, e.g. ["id", "title", "status"]
 #[tauri::command]
-pub async fn get_tasks(state: State<'_, AppState>) -> Result<Vec<Task>, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    storage.get_tasks().map_err(|e| format!("Failed to get tasks: {}", e))
+pub async fn get_tasks(
+    fields: Option<Vec<String>>,
+    state: State<'_, AppState>
+) -> Result<Vec<serde_json::Value>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    project_task_fields(storage.data().tasks.iter(), fields)
 }
 
 #[tauri::command]
@@ -16,7 +69,7 @@ pub async fn add_task(
     request: TaskCreateRequest,
     state: State<'_, AppState>
 ) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     storage.add_task(
         request.title,
         request.description,
@@ -29,67 +82,209 @@ pub async fn update_task(
     request: TaskUpdateRequest,
     state: State<'_, AppState>
 ) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    storage.update_task(
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let previous_status = storage.get_task_by_id(request.id).map(|t| t.status).ok();
+    
+    let task = storage.update_task(
         request.id,
         request.title,
         request.description,
         request.status,
         request.priority
-    ).map_err(|e| format!("Failed to update task: {}", e))
+    ).map_err(|e| format!("Failed to update task: {}", e))?;
+    
+    if let Some(previous_status) = previous_status {
+        webhooks::notify_status_change(&storage, &previous_status, &task);
+    }
+    
+    Ok(task)
+}
+
+#[tauri::command]
+pub async fn delete_task(
+    id: u32,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pending: State<'_, PendingDeletes>
+) -> Result<(), String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    storage.delete_task(id).map_err(|e| format!("Failed to delete task: {}", e))?;
+    pending.deleted(&app, &[id]);
+    Ok(())
+}
+
+// Deletes the task once the countdown runs out unless cancel_pending_delete is called first
+#[tauri::command]
+pub async fn delete_task_deferred(
+    id: u32,
+    seconds: u64,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pending: State<'_, PendingDeletes>
+) -> Result<PendingDelete, String> {
+    if seconds == 0 || seconds > trash::MAX_DELAY_SECONDS {
+        return Err(format!("The delay must be between 1 and {} seconds", trash::MAX_DELAY_SECONDS));
+    }
+    
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    storage.check_writable().map_err(|e| format!("Failed to schedule delete: {}", e))?;
+    let task = storage.task(id).ok_or_else(|| format!("Task with id {} not found", id))?;
+    pending.schedule(&app, task, seconds)
+        .map_err(|e| format!("Failed to schedule delete: {}", e))
+}
+
+#[tauri::command]
+pub async fn cancel_pending_delete(
+    id: u32,
+    app: AppHandle,
+    pending: State<'_, PendingDeletes>
+) -> Result<PendingDelete, String> {
+    pending.cancel(&app, id)
+        .map_err(|e| format!("Failed to cancel delete: {}", e))?
+        .ok_or_else(|| format!("Task {} is not pending deletion", id))
 }
 
+// Lets a reloaded window bring back the toasts that are still counting down
 #[tauri::command]
-pub async fn delete_task(id: u32, state: State<'_, AppState>) -> Result<(), String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    storage.delete_task(id).map_err(|e| format!("Failed to delete task: {}", e))
+pub async fn get_pending_deletes(pending: State<'_, PendingDeletes>) -> Result<Vec<PendingDelete>, String> {
+    Ok(pending.list())
 }
 
 #[tauri::command]
 pub async fn get_task_by_id(id: u32, state: State<'_, AppState>) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     storage.get_task_by_id(id).map_err(|e| format!("Failed to get task: {}", e))
 }
 
 #[tauri::command]
 pub async fn get_tasks_by_status(
     status: String,
+    fields: Option<Vec<String>>,
     state: State<'_, AppState>
-) -> Result<Vec<Task>, String> {
+) -> Result<Vec<serde_json::Value>, String> {
     let task_status: TaskStatus = status.as_str().into();
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    storage.get_tasks_by_status(task_status).map_err(|e| format!("Failed to get tasks by status: {}", e))
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let tasks = storage.data().tasks.iter().filter(|t| t.status == task_status);
+    project_task_fields(tasks, fields)
+}
+
+// Computed properties added to every task in a list, on top of the stored ones
+const COMPUTED_TASK_FIELDS: &[&str] = &["staleness"];
+
+fn task_summary(task: &Task, now: chrono::DateTime<chrono::Utc>) -> Result<serde_json::Value, String> {
+    let mut value = serde_json::to_value(task).map_err(|e| format!("Failed to serialize task: {}", e))?;
+    if let Some(object) = value.as_object_mut() {
+        let staleness = serde_json::to_value(task.staleness(now)).map_err(|e| format!("Failed to serialize task: {}", e))?;
+        object.insert("staleness".to_string(), staleness);
+    }
+    Ok(value)
+}
+
+fn project_task_fields<'a>(
+    tasks: impl Iterator<Item = &'a Task>,
+    fields: Option<Vec<String>>
+) -> Result<Vec<serde_json::Value>, String> {
+    let now = chrono::Utc::now();
+    let fields = match fields {
+        Some(fields) => fields,
+        None => return tasks.map(|t| task_summary(t, now)).collect(),
+    };
+    
+    // Every stored property is serialized, even when empty, so a blank task names them all
+    let blank = serde_json::to_value(Task::new(0, 0, String::new(), String::new()))
+        .map_err(|e| format!("Failed to serialize task: {}", e))?;
+    let known = blank.as_object();
+    let is_known = |field: &String| COMPUTED_TASK_FIELDS.contains(&field.as_str())
+        || known.map(|k| k.contains_key(field.as_str())).unwrap_or(false);
+    if let Some(unknown) = fields.iter().find(|f| !is_known(f)) {
+        return Err(format!("Unknown task field `{}`", unknown));
+    }
+    
+    tasks.map(|task| {
+        let mut value = task_summary(task, now)?;
+        if let Some(object) = value.as_object_mut() {
+            object.retain(|key, _| fields.contains(key));
+        }
+        Ok(value)
+    }).collect()
+}
+
+const DEFAULT_STALE_DAYS: u32 = 14;
+
+// Open tasks not updated for at least `threshold` days (default 14), most neglected first
+#[tauri::command]
+pub async fn get_stale_tasks(
+    threshold: Option<u32>,
+    project_id: Option<u32>,
+    fields: Option<Vec<String>>,
+    state: State<'_, AppState>
+) -> Result<Vec<serde_json::Value>, String> {
+    let threshold = threshold.unwrap_or(DEFAULT_STALE_DAYS) as i64;
+    let now = chrono::Utc::now();
+    
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut tasks: Vec<(&Task, TaskStaleness)> = storage.data().tasks.iter()
+        .filter(|t| t.status != TaskStatus::Done)
+        .filter(|t| project_id.is_none_or(|id| t.project_id == id))
+        .map(|t| (t, t.staleness(now)))
+        .filter(|(_, staleness)| staleness.days_since_update >= threshold)
+        .collect();
+    tasks.sort_by_key(|(t, staleness)| (std::cmp::Reverse(staleness.days_since_update), t.id));
+    
+    project_task_fields(tasks.into_iter().map(|(t, _)| t), fields)
 }
 
 #[tauri::command]
 pub async fn get_theme(state: State<'_, AppState>) -> Result<String, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     storage.get_theme().map_err(|e| format!("Failed to get theme: {}", e))
 }
 
 #[tauri::command]
 pub async fn set_theme(theme: String, state: State<'_, AppState>) -> Result<(), String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     storage.set_theme(theme).map_err(|e| format!("Failed to set theme: {}", e))
 }
 
 #[tauri::command]
 pub async fn backup_data(backup_path: String, state: State<'_, AppState>) -> Result<(), String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     storage.backup_data(backup_path.into()).map_err(|e| format!("Failed to backup data: {}", e))
 }
 
 #[tauri::command]
 pub async fn restore_data(backup_path: String, state: State<'_, AppState>) -> Result<(), String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     storage.restore_data(backup_path.into()).map_err(|e| format!("Failed to restore data: {}", e))
 }
 
+#[tauri::command]
+pub async fn preview_partial_restore(
+    backup_path: String,
+    selection: RestoreSelection,
+    state: State<'_, AppState>
+) -> Result<RestorePreview, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    storage.preview_partial_restore(backup_path.into(), &selection)
+        .map_err(|e| format!("Failed to preview restore: {}", e))
+}
+
+#[tauri::command]
+pub async fn restore_partial(
+    backup_path: String,
+    selection: RestoreSelection,
+    state: State<'_, AppState>
+) -> Result<RestorePreview, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    storage.restore_partial(backup_path.into(), &selection)
+        .map_err(|e| format!("Failed to restore data: {}", e))
+}
+
 // Additional utility commands for better UX
 
 #[tauri::command]
 pub async fn toggle_task_status(id: u32, state: State<'_, AppState>) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
     // Get current task
     let current_task = storage.get_task_by_id(id)
@@ -102,13 +297,18 @@ pub async fn toggle_task_status(id: u32, state: State<'_, AppState>) -> Result<T
         TaskStatus::Done => TaskStatus::Todo,
     };
     
-    storage.update_task(id, None, None, Some(new_status), None)
-        .map_err(|e| format!("Failed to toggle task status: {}", e))
+    let task = storage.update_task(id, None, None, Some(new_status), None)
+        .map_err(|e| format!("Failed to toggle task status: {}", e))?;
+    
+    webhooks::notify_status_change(&storage, &current_task.status, &task);
+    
+    Ok(task)
 }
 
 #[tauri::command]
 pub async fn get_task_stats(state: State<'_, AppState>) -> Result<TaskStats, String> {
-    let tasks = get_tasks(state).await?;
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let tasks = &storage.data().tasks;
     
     let todo_count = tasks.iter().filter(|t| t.status == TaskStatus::Todo).count();
     let in_progress_count = tasks.iter().filter(|t| t.status == TaskStatus::InProgress).count();
@@ -147,20 +347,10 @@ pub async fn add_task_tag(
     tag: String,
     state: State<'_, AppState>
 ) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
-    
-    let task = data.tasks.iter_mut()
-        .find(|t| t.id == task_id)
-        .ok_or_else(|| format!("Task with id {} not found", task_id))?;
-    
-    task.add_tag(tag);
-    let updated_task = task.clone();
-    
-    storage.save_data(&data).map_err(|e| format!("Failed to save: {}", e))?;
-    
-    Ok(updated_task)
+    storage.modify_task(task_id, |task| task.add_tag(tag))
+        .map_err(|e| format!("Failed to update task: {}", e))
 }
 
 #[tauri::command]
@@ -169,20 +359,73 @@ pub async fn remove_task_tag(
     tag: String,
     state: State<'_, AppState>
 ) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    
-    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    let task = data.tasks.iter_mut()
-        .find(|t| t.id == task_id)
-        .ok_or_else(|| format!("Task with id {} not found", task_id))?;
+    storage.modify_task(task_id, |task| task.remove_tag(&tag))
+        .map_err(|e| format!("Failed to update task: {}", e))
+}
+
+// Adds and removes tags on many tasks with a single save: either every change is written or,
+// if saving fails, none is. Unknown ids are reported per task and don't stop the others.
+#[tauri::command]
+pub async fn bulk_tag(
+    ids: Vec<u32>,
+    add_tags: Vec<String>,
+    remove_tags: Vec<String>,
+    state: State<'_, AppState>
+) -> Result<Vec<BulkTagResult>, String> {
+    let clean = |tags: Vec<String>| -> Vec<String> {
+        let mut cleaned: Vec<String> = Vec::new();
+        for tag in tags.into_iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+            if !cleaned.contains(&tag) {
+                cleaned.push(tag);
+            }
+        }
+        cleaned
+    };
+    let add_tags = clean(add_tags);
+    let remove_tags = clean(remove_tags);
+    if let Some(tag) = add_tags.iter().find(|t| remove_tags.contains(t)) {
+        return Err(format!("The tag \"{}\" can't be both added and removed", tag));
+    }
     
-    task.remove_tag(&tag);
-    let updated_task = task.clone();
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
     
-    storage.save_data(&data).map_err(|e| format!("Failed to save: {}", e))?;
+    let mut results: Vec<BulkTagResult> = Vec::new();
+    for id in ids {
+        if results.iter().any(|r| r.task_id == id) {
+            continue;
+        }
+        let Some(task) = data.tasks.iter_mut().find(|t| t.id == id) else {
+            results.push(BulkTagResult { task_id: id, changed: false, tags: Vec::new(), error: Some(format!("Task with id {} not found", id)) });
+            continue;
+        };
+        let before = task.tags.clone();
+        for tag in &add_tags {
+            task.add_tag(tag.clone());
+        }
+        for tag in &remove_tags {
+            // remove_tag always bumps updated_at, so only call it when there is something to remove
+            if task.tags.contains(tag) {
+                task.remove_tag(tag);
+            }
+        }
+        results.push(BulkTagResult { task_id: id, changed: task.tags != before, tags: task.tags.clone(), error: None });
+    }
     
-    Ok(updated_task)
+    if results.iter().any(|r| r.changed) {
+        storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    }
+    Ok(results)
+}
+
+#[derive(serde::Serialize)]
+pub struct BulkTagResult {
+    pub task_id: u32,
+    pub changed: bool,
+    pub tags: Vec<String>, // The task's tags afterwards
+    pub error: Option<String>,
 }
 
 #[tauri::command]
@@ -191,20 +434,33 @@ pub async fn set_task_due_date(
     due_date: Option<String>,
     state: State<'_, AppState>
 ) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    
-    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
-    
-    let task = data.tasks.iter_mut()
-        .find(|t| t.id == task_id)
-        .ok_or_else(|| format!("Task with id {} not found", task_id))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    task.set_due_date(due_date);
-    let updated_task = task.clone();
+    let task = storage.task(task_id).ok_or_else(|| format!("Task with id {} not found", task_id))?;
+    let default_reminders = storage.project(task.project_id)
+        .map(|p| p.settings.notifications.reminder_offsets.clone())
+        .unwrap_or_default();
     
-    storage.save_data(&data).map_err(|e| format!("Failed to save: {}", e))?;
+    storage.modify_task(task_id, |task| {
+        // Tasks getting a due date start with the project's reminders unless they have their own
+        if task.due_date.is_none() && due_date.is_some() && task.reminders.is_empty() {
+            task.set_reminders(default_reminders);
+        }
+        task.set_due_date(due_date);
+    }).map_err(|e| format!("Failed to update task: {}", e))
+}
+
+// `minutes_before` replaces the task's reminders; an empty list turns them off
+#[tauri::command]
+pub async fn set_task_reminders(
+    task_id: u32,
+    minutes_before: Vec<u32>,
+    state: State<'_, AppState>
+) -> Result<Task, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    Ok(updated_task)
+    storage.modify_task(task_id, |task| task.set_reminders(minutes_before))
+        .map_err(|e| format!("Failed to update task: {}", e))
 }
 
 #[tauri::command]
@@ -213,22 +469,13 @@ pub async fn add_task_subtask(
     subtask_title: String,
     state: State<'_, AppState>
 ) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    
-    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
-    
-    let task = data.tasks.iter_mut()
-        .find(|t| t.id == task_id)
-        .ok_or_else(|| format!("Task with id {} not found", task_id))?;
-    
-    // Generate new subtask ID
-    let subtask_id = task.subtasks.iter().map(|s| s.id).max().unwrap_or(0) + 1;
-    task.add_subtask(subtask_id, subtask_title);
-    let updated_task = task.clone();
-    
-    storage.save_data(&data).map_err(|e| format!("Failed to save: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    Ok(updated_task)
+    storage.modify_task(task_id, |task| {
+        // Generate new subtask ID
+        let subtask_id = task.subtasks.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+        task.add_subtask(subtask_id, subtask_title);
+    }).map_err(|e| format!("Failed to update task: {}", e))
 }
 
 #[tauri::command]
@@ -237,20 +484,10 @@ pub async fn toggle_task_subtask(
     subtask_id: u32,
     state: State<'_, AppState>
 ) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
-    
-    let task = data.tasks.iter_mut()
-        .find(|t| t.id == task_id)
-        .ok_or_else(|| format!("Task with id {} not found", task_id))?;
-    
-    task.toggle_subtask(subtask_id);
-    let updated_task = task.clone();
-    
-    storage.save_data(&data).map_err(|e| format!("Failed to save: {}", e))?;
-    
-    Ok(updated_task)
+    storage.modify_task(task_id, |task| task.toggle_subtask(subtask_id))
+        .map_err(|e| format!("Failed to update task: {}", e))
 }
 
 #[tauri::command]
@@ -260,7 +497,7 @@ pub async fn add_task_comment(
     author: String,
     state: State<'_, AppState>
 ) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
     let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
     
@@ -273,231 +510,636 @@ pub async fn add_task_comment(
     task.add_comment(comment_id, comment_text, author);
     let updated_task = task.clone();
     
+    if let Some(comment) = updated_task.comments.last() {
+        mentions::notify_mentions(&mut data, task_id, comment);
+    }
+    
     storage.save_data(&data).map_err(|e| format!("Failed to save: {}", e))?;
     
     Ok(updated_task)
 }
 
+// Review workflow: each step is recorded as a structured comment on the task
+
 #[tauri::command]
-pub async fn add_task_time(
+pub async fn request_review(
     task_id: u32,
-    minutes: u32,
+    requested_by: String,
+    reviewer: Option<String>,
     state: State<'_, AppState>
 ) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
+    let reviewer = reviewer.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
     let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
     
     let task = data.tasks.iter_mut()
         .find(|t| t.id == task_id)
         .ok_or_else(|| format!("Task with id {} not found", task_id))?;
+    if task.review_state == ReviewState::Requested {
+        return Err(format!("Review for task {} has already been requested", task_id));
+    }
     
-    task.add_time(minutes);
+    task.request_review(requested_by, reviewer);
     let updated_task = task.clone();
     
+    // Lets "@handle" reviewers see the request in their notifications
+    if let Some(comment) = updated_task.comments.last() {
+        mentions::notify_mentions(&mut data, task_id, comment);
+    }
+    
     storage.save_data(&data).map_err(|e| format!("Failed to save: {}", e))?;
     
     Ok(updated_task)
 }
 
 #[tauri::command]
-pub async fn set_task_estimated_time(
+pub async fn approve_task(
     task_id: u32,
-    estimated_minutes: Option<u32>,
+    reviewer: String,
+    note: Option<String>,
     state: State<'_, AppState>
 ) -> Result<Task, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    
-    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
-    
-    let task = data.tasks.iter_mut()
-        .find(|t| t.id == task_id)
-        .ok_or_else(|| format!("Task with id {} not found", task_id))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    task.set_estimated_time(estimated_minutes);
-    let updated_task = task.clone();
+    let previous_status = check_review_requested(&storage, task_id)?;
+    let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    let task = storage.modify_task(task_id, |task| task.approve(reviewer, note))
+        .map_err(|e| format!("Failed to approve task: {}", e))?;
     
-    storage.save_data(&data).map_err(|e| format!("Failed to save: {}", e))?;
+    webhooks::notify_status_change(&storage, &previous_status, &task);
     
-    Ok(updated_task)
+    Ok(task)
 }
 
 #[tauri::command]
-pub async fn get_tasks_by_tag(
-    tag: String,
+pub async fn reject_task(
+    task_id: u32,
+    reviewer: String,
+    reason: String,
     state: State<'_, AppState>
-) -> Result<Vec<Task>, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    let tasks = storage.get_tasks().map_err(|e| format!("Failed to get tasks: {}", e))?;
+) -> Result<Task, String> {
+    let reason = reason.trim().to_string();
+    if reason.is_empty() {
+        return Err("A reason is required to reject a task".to_string());
+    }
     
-    let filtered_tasks: Vec<Task> = tasks.into_iter()
-        .filter(|t| t.tags.contains(&tag))
-        .collect();
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let previous_status = check_review_requested(&storage, task_id)?;
+    let task = storage.modify_task(task_id, |task| task.reject(reviewer, reason))
+        .map_err(|e| format!("Failed to reject task: {}", e))?;
     
-    Ok(filtered_tasks)
+    webhooks::notify_status_change(&storage, &previous_status, &task);
+    
+    Ok(task)
+}
+
+// Returns the task's current status so callers can report the transition
+fn check_review_requested(storage: &Storage, task_id: u32) -> Result<TaskStatus, String> {
+    let task = storage.task(task_id).ok_or_else(|| format!("Task with id {} not found", task_id))?;
+    if task.review_state != ReviewState::Requested {
+        return Err(format!("Task {} has no pending review", task_id));
+    }
+    Ok(task.status.clone())
 }
 
+// Without a title the page title is fetched, falling back to the URL itself
 #[tauri::command]
-pub async fn get_tasks_by_due_date(
-    due_date: String,
+pub async fn add_task_link_attachment(
+    task_id: u32,
+    url: String,
+    title: Option<String>,
     state: State<'_, AppState>
-) -> Result<Vec<Task>, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    let tasks = storage.get_tasks().map_err(|e| format!("Failed to get tasks: {}", e))?;
+) -> Result<Task, String> {
+    let url = links::validate_url(&url).map_err(|e| format!("Failed to add link: {}", e))?;
     
-    let filtered_tasks: Vec<Task> = tasks.into_iter()
-        .filter(|t| t.due_date == Some(due_date.clone()))
-        .collect();
+    {
+        let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        storage.task(task_id).ok_or_else(|| format!("Task with id {} not found", task_id))?;
+    }
+    
+    // Fetched before taking the write lock so a slow site doesn't block other commands
+    let title = match title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+        Some(title) => title,
+        None => {
+            let link = url.clone();
+            blocking(move || links::fetch_title(&link)).await?.unwrap_or_else(|| links::fallback_title(&url))
+        }
+    };
     
-    Ok(filtered_tasks)
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    storage.modify_task(task_id, |task| {
+        task.add_link_attachment(url, title);
+    }).map_err(|e| format!("Failed to add link: {}", e))
 }
 
 #[tauri::command]
-pub async fn get_overdue_tasks(state: State<'_, AppState>) -> Result<Vec<Task>, String> {
-    use chrono::{DateTime, Utc};
-    
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    let tasks = storage.get_tasks().map_err(|e| format!("Failed to get tasks: {}", e))?;
-    
-    let now = Utc::now();
-    let filtered_tasks: Vec<Task> = tasks.into_iter()
-        .filter(|t| {
-            if let Some(due_date_str) = &t.due_date {
-                if let Ok(due_date) = DateTime::parse_from_rfc3339(due_date_str) {
-                    return due_date.with_timezone(&Utc) < now && t.status != TaskStatus::Done;
-                }
-            }
-            false
-        })
-        .collect();
+pub async fn add_task_time(
+    task_id: u32,
+    minutes: DurationInput,
+    state: State<'_, AppState>
+) -> Result<Task, String> {
+    let minutes = minutes.minutes().map_err(|e| format!("Invalid time: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    Ok(filtered_tasks)
+    storage.modify_task(task_id, |task| task.add_time(minutes))
+        .map_err(|e| format!("Failed to update task: {}", e))
 }
 
+// An empty string clears the estimate, like leaving it out
 #[tauri::command]
-pub async fn get_all_tags(state: State<'_, AppState>) -> Result<Vec<String>, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    let tasks = storage.get_tasks().map_err(|e| format!("Failed to get tasks: {}", e))?;
-    
-    let mut all_tags: Vec<String> = tasks.iter()
-        .flat_map(|t| t.tags.iter())
-        .cloned()
-        .collect();
-    
-    all_tags.sort();
-    all_tags.dedup();
+pub async fn set_task_estimated_time(
+    task_id: u32,
+    estimated_minutes: Option<DurationInput>,
+    state: State<'_, AppState>
+) -> Result<Task, String> {
+    let estimated_minutes = match estimated_minutes.filter(|d| !d.is_blank()) {
+        Some(duration) => Some(duration.minutes().map_err(|e| format!("Invalid estimate: {}", e))?),
+        None => None,
+    };
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    Ok(all_tags)
+    storage.modify_task(task_id, |task| task.set_estimated_time(estimated_minutes))
+        .map_err(|e| format!("Failed to update task: {}", e))
 }
 
-// Project Management Commands
-
+// Takes a label from the task's project size scale ("M") or its points ("3"); an empty string
+// clears the size
 #[tauri::command]
-pub async fn create_project(
-    request: ProjectCreateRequest,
+pub async fn set_task_size(
+    task_id: u32,
+    size: Option<String>,
     state: State<'_, AppState>
-) -> Result<Project, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    
-    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
-    
-    // Generate new project ID
-    let new_id = data.projects.iter()
-        .map(|p| p.id)
-        .max()
-        .unwrap_or(0) + 1;
-    
-    let project = Project::new_with_details(
-        new_id,
-        request.name,
-        request.description.unwrap_or_default(),
-        request.color,
-        request.icon,
-    );
-    
-    data.projects.push(project.clone());
-    
-    // Set as current project if it's the first one
-    if data.current_project_id.is_none() {
-        data.current_project_id = Some(new_id);
-    }
+) -> Result<Task, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    let task = storage.task(task_id).ok_or_else(|| format!("Task with id {} not found", task_id))?;
+    let points = match size.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(size) => {
+            let scale = storage.project(task.project_id).map(|p| p.settings.size_scale.as_slice()).unwrap_or(&[]);
+            Some(sizing::parse(scale, size).map_err(|e| format!("Invalid size: {}", e))?)
+        }
+        None => None,
+    };
     
-    Ok(project)
+    storage.modify_task(task_id, |task| task.set_size(points))
+        .map_err(|e| format!("Failed to update task: {}", e))
 }
 
+// Lets input fields preview what a duration will be stored as
 #[tauri::command]
-pub async fn get_projects(state: State<'_, AppState>) -> Result<Vec<Project>, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    let data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
-    
-    Ok(data.projects)
+pub async fn parse_duration(text: String) -> Result<u32, String> {
+    duration::parse_minutes(&text).map_err(|e| format!("Invalid duration: {}", e))
 }
 
 #[tauri::command]
-pub async fn get_current_project(state: State<'_, AppState>) -> Result<Option<Project>, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    let data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+pub async fn set_task_description_format(
+    task_id: u32,
+    format: DescriptionFormat,
+    state: State<'_, AppState>
+) -> Result<Task, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    if let Some(current_id) = data.current_project_id {
-        let project = data.projects.iter()
-            .find(|p| p.id == current_id)
-            .cloned();
-        Ok(project)
-    } else {
-        Ok(None)
-    }
+    storage.modify_task(task_id, |task| task.set_description_format(format))
+        .map_err(|e| format!("Failed to update task: {}", e))
 }
 
+// Checklist, links and #tags found in a Markdown description (empty for plaintext)
 #[tauri::command]
-pub async fn switch_project(
-    project_id: u32,
+pub async fn get_task_description_metadata(
+    task_id: u32,
     state: State<'_, AppState>
-) -> Result<Project, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    
-    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
-    
-    // Verify project exists
-    let project = data.projects.iter()
-        .find(|p| p.id == project_id)
-        .ok_or_else(|| format!("Project with id {} not found", project_id))?
-        .clone();
-    
-    data.current_project_id = Some(project_id);
-    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+) -> Result<DescriptionMetadata, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    Ok(project)
+    let task = storage.task(task_id)
+        .ok_or_else(|| format!("Task with id {} not found", task_id))?;
+    if task.description_format != DescriptionFormat::Markdown {
+        return Ok(DescriptionMetadata::default());
+    }
+    Ok(markdown::extract(&task.description))
 }
 
 #[tauri::command]
-pub async fn update_project(
-    request: ProjectUpdateRequest,
+pub async fn log_time_session(
+    task_id: u32,
+    started_at: String,
+    ended_at: String,
     state: State<'_, AppState>
-) -> Result<Project, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    
+) -> Result<Task, String> {
+    let parse = |value: &str| chrono::DateTime::parse_from_rfc3339(value)
+        .map(|d| d.with_timezone(&chrono::Utc))
+        .map_err(|e| format!("Invalid timestamp {}: {}", value, e));
+    let started_at = parse(&started_at)?;
+    let ended_at = parse(&ended_at)?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    storage.log_time_session(task_id, started_at, ended_at)
+        .map_err(|e| format!("Failed to log time session: {}", e))
+}
+
+// `day` is a local calendar date (YYYY-MM-DD); sessions count towards the day they started
+#[tauri::command]
+pub async fn get_time_log(day: String, state: State<'_, AppState>) -> Result<TimeLog, String> {
+    use chrono::{DateTime, Local, NaiveDate};
+    
+    let date = NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid day {}: {}", day, e))?;
+    
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.data();
+    
+    let mut sessions: Vec<TimeLogEntry> = data.time_log.iter()
+        .filter(|s| {
+            DateTime::parse_from_rfc3339(&s.started_at)
+                .map(|d| d.with_timezone(&Local).date_naive() == date)
+                .unwrap_or(false)
+        })
+        .map(|s| TimeLogEntry {
+            task_title: storage.task(s.task_id)
+                .map(|t| t.title.clone())
+                .unwrap_or_else(|| "Deleted task".to_string()),
+            session: s.clone(),
+        })
+        .collect();
+    sessions.sort_by(|a, b| a.session.started_at.cmp(&b.session.started_at));
+    
+    let mut totals: Vec<TaskTimeTotal> = Vec::new();
+    for entry in &sessions {
+        match totals.iter_mut().find(|t| t.task_id == entry.session.task_id) {
+            Some(total) => total.minutes += entry.session.minutes,
+            None => totals.push(TaskTimeTotal {
+                task_id: entry.session.task_id,
+                task_title: entry.task_title.clone(),
+                minutes: entry.session.minutes,
+            }),
+        }
+    }
+    totals.sort_by_key(|t| std::cmp::Reverse(t.minutes));
+    
+    Ok(TimeLog {
+        day,
+        total_minutes: totals.iter().map(|t| t.minutes).sum(),
+        sessions,
+        totals,
+    })
+}
+
+#[derive(serde::Serialize)]
+pub struct TimeLog {
+    pub day: String,
+    pub total_minutes: u32,
+    pub sessions: Vec<TimeLogEntry>,
+    pub totals: Vec<TaskTimeTotal>,
+}
+
+#[derive(serde::Serialize)]
+pub struct TimeLogEntry {
+    #[serde(flatten)]
+    pub session: TimeSession,
+    pub task_title: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct TaskTimeTotal {
+    pub task_id: u32,
+    pub task_title: String,
+    pub minutes: u32,
+}
+
+#[tauri::command]
+pub async fn get_tasks_by_tag(
+    tag: String,
+    fields: Option<Vec<String>>,
+    state: State<'_, AppState>
+) -> Result<Vec<serde_json::Value>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let tasks = storage.data().tasks.iter().filter(|t| t.tags.contains(&tag));
+    project_task_fields(tasks, fields)
+}
+
+// Tasks of a size, read against each task's project scale; no size finds the unsized tasks
+#[tauri::command]
+pub async fn get_tasks_by_size(
+    size: Option<String>,
+    project_id: Option<u32>,
+    fields: Option<Vec<String>>,
+    state: State<'_, AppState>
+) -> Result<Vec<serde_json::Value>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.data();
+    
+    if let Some(id) = project_id {
+        storage.project(id).ok_or_else(|| format!("Project with id {} not found", id))?;
+    }
+    let size = size.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let mut points_by_project: HashMap<u32, Option<u32>> = HashMap::new();
+    for project in &data.projects {
+        let points = size.and_then(|size| sizing::parse(&project.settings.size_scale, size).ok());
+        points_by_project.insert(project.id, points);
+    }
+    if size.is_some() && points_by_project.values().all(Option::is_none) {
+        return Err(format!("Invalid size: \"{}\" is not on any project's size scale", size.unwrap_or_default()));
+    }
+    
+    let tasks = data.tasks.iter()
+        .filter(|t| project_id.map(|id| t.project_id == id).unwrap_or(true))
+        .filter(|t| match size {
+            Some(_) => t.size.is_some() && points_by_project.get(&t.project_id).copied().flatten() == t.size,
+            None => t.size.is_none(),
+        });
+    project_task_fields(tasks, fields)
+}
+
+#[tauri::command]
+pub async fn get_tasks_by_due_date(
+    due_date: String,
+    fields: Option<Vec<String>>,
+    state: State<'_, AppState>
+) -> Result<Vec<serde_json::Value>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let tasks = storage.data().tasks.iter().filter(|t| t.due_date.as_deref() == Some(due_date.as_str()));
+    project_task_fields(tasks, fields)
+}
+
+#[tauri::command]
+pub async fn get_overdue_tasks(
+    fields: Option<Vec<String>>,
+    state: State<'_, AppState>
+) -> Result<Vec<serde_json::Value>, String> {
+    use chrono::Utc;
+    
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.data();
+    let now = Utc::now();
+    let tasks = data.tasks.iter().filter(|t| workdays::is_overdue(t, now, &data.settings.working_days));
+    project_task_fields(tasks, fields)
+}
+
+const DEFAULT_RECENT_TASKS: usize = 10;
+
+// Most recently modified tasks first, across all projects unless one is given
+#[tauri::command]
+pub async fn get_recent_tasks(
+    limit: Option<usize>,
+    project_id: Option<u32>,
+    fields: Option<Vec<String>>,
+    state: State<'_, AppState>
+) -> Result<Vec<serde_json::Value>, String> {
+    use chrono::{DateTime, Utc};
+    use std::cmp::Reverse;
+    
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut tasks: Vec<&Task> = storage.data().tasks.iter()
+        .filter(|t| project_id.is_none_or(|id| t.project_id == id))
+        .collect();
+    
+    // Parsed so timestamps with different offsets still order correctly
+    tasks.sort_by_cached_key(|t| {
+        Reverse(DateTime::parse_from_rfc3339(&t.updated_at).ok().map(|d| d.with_timezone(&Utc)))
+    });
+    
+    project_task_fields(tasks.into_iter().take(limit.unwrap_or(DEFAULT_RECENT_TASKS)), fields)
+}
+
+const DEFAULT_SEARCH_RESULTS: usize = 20;
+
+#[tauri::command]
+pub async fn global_search(
+    query: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>
+) -> Result<SearchResults, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    Ok(search::search(storage.data(), &query, limit.unwrap_or(DEFAULT_SEARCH_RESULTS)))
+}
+
+#[tauri::command]
+pub async fn get_all_tags(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let tasks = storage.get_tasks().map_err(|e| format!("Failed to get tasks: {}", e))?;
+    
+    let mut all_tags: Vec<String> = tasks.iter()
+        .flat_map(|t| t.tags.iter())
+        .cloned()
+        .collect();
+    
+    all_tags.sort();
+    all_tags.dedup();
+    
+    Ok(all_tags)
+}
+
+// Project Management Commands
+
+#[tauri::command]
+pub async fn create_project(
+    request: ProjectCreateRequest,
+    state: State<'_, AppState>
+) -> Result<Project, String> {
+    let (color, icon) = validate_appearance(request.color, request.icon)?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
     let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
     
-    let project = data.projects.iter_mut()
-        .find(|p| p.id == request.id)
-        .ok_or_else(|| format!("Project with id {} not found", request.id))?;
+    // Generate new project ID
+    let new_id = data.projects.iter()
+        .map(|p| p.id)
+        .max()
+        .unwrap_or(0) + 1;
+    
+    // Projects created without a color get one that sets them apart from the others
+    let color = color.unwrap_or_else(|| palette::next_color(&data.projects));
+    let icon = icon.unwrap_or_else(|| palette::DEFAULT_ICON.to_string());
+    
+    let project = Project::new_with_details(
+        new_id,
+        request.name,
+        request.description.unwrap_or_default(),
+        Some(color),
+        Some(icon),
+    );
+    
+    data.projects.push(project.clone());
     
-    project.update_info(request.name, request.description, request.color, request.icon);
-    let updated_project = project.clone();
+    // Set as current project if it's the first one
+    if data.current_project_id.is_none() {
+        data.current_project_id = Some(new_id);
+    }
     
     storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
     
-    Ok(updated_project)
+    Ok(project)
+}
+
+// Blank values count as not given
+fn validate_appearance(color: Option<String>, icon: Option<String>) -> Result<(Option<String>, Option<String>), String> {
+    let color = color.filter(|c| !c.trim().is_empty())
+        .map(|c| palette::normalize_color(&c))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let icon = icon.filter(|i| !i.trim().is_empty())
+        .map(|i| palette::validate_icon(&i))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    Ok((color, icon))
+}
+
+#[tauri::command]
+pub async fn get_color_palette(state: State<'_, AppState>) -> Result<ColorPalette, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(palette::palette(&storage.data().projects))
 }
 
 #[tauri::command]
-pub async fn delete_project(
+pub async fn get_projects(state: State<'_, AppState>) -> Result<Vec<Project>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.data().projects.clone())
+}
+
+#[tauri::command]
+pub async fn get_current_project(state: State<'_, AppState>) -> Result<Option<Project>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.data();
+    
+    if let Some(current_id) = data.current_project_id {
+        Ok(storage.project(current_id).cloned())
+    } else {
+        Ok(None)
+    }
+}
+
+#[tauri::command]
+pub async fn switch_project(
+    project_id: u32,
+    state: State<'_, AppState>
+) -> Result<Project, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    // Verify project exists
+    let project = storage.project(project_id)
+        .ok_or_else(|| format!("Project with id {} not found", project_id))?
+        .clone();
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    data.current_project_id = Some(project_id);
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(project)
+}
+
+// Selections pointing at deleted tasks or projects are dropped rather than returned
+#[tauri::command]
+pub async fn get_last_selection(state: State<'_, AppState>) -> Result<LastSelection, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut selection = storage.data().last_selection.clone();
+    
+    if selection.project_id.is_some_and(|id| storage.project(id).is_none()) {
+        selection.project_id = None;
+    }
+    if selection.task_id.is_some_and(|id| storage.task(id).is_none()) {
+        selection.task_id = None;
+    }
+    
+    Ok(selection)
+}
+
+#[tauri::command]
+pub async fn set_last_selection(
+    project_id: Option<u32>,
+    task_id: Option<u32>,
+    state: State<'_, AppState>
+) -> Result<LastSelection, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    if let Some(id) = project_id {
+        storage.project(id).ok_or_else(|| format!("Project with id {} not found", id))?;
+    }
+    // A selected task implies its project
+    let project_id = match task_id {
+        Some(id) => Some(storage.task(id).ok_or_else(|| format!("Task with id {} not found", id))?.project_id),
+        None => project_id,
+    };
+    
+    let selection = LastSelection {
+        project_id,
+        task_id,
+        updated_at: Some(chrono::Utc::now().to_rfc3339()),
+    };
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    data.last_selection = selection.clone();
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(selection)
+}
+
+#[tauri::command]
+pub async fn update_project(
+    request: ProjectUpdateRequest,
+    state: State<'_, AppState>
+) -> Result<Project, String> {
+    let (color, icon) = validate_appearance(request.color, request.icon)?;
+    if let Some(settings) = &request.settings {
+        sizing::validate_scale(&settings.size_scale).map_err(|e| e.to_string())?;
+        status_mapping::validate(&settings.status_mapping).map_err(|e| e.to_string())?;
+    }
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    storage.modify_project(request.id, |project| {
+        project.update_info(request.name, request.description, color, icon);
+        if let Some(settings) = request.settings {
+            project.settings = settings;
+        }
+    }).map_err(|e| format!("Failed to update project: {}", e))
+}
+
+// How the project's importers read other trackers' workflow states, e.g. "In Review" -> in-progress
+#[tauri::command]
+pub async fn get_status_mapping(
+    project_id: u32,
+    state: State<'_, AppState>
+) -> Result<BTreeMap<String, TaskStatus>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let project = storage.project(project_id).ok_or_else(|| format!("Project with id {} not found", project_id))?;
+    
+    Ok(project.settings.status_mapping.clone())
+}
+
+// Replaces the whole table; states left out fall back to the common names
+#[tauri::command]
+pub async fn update_status_mapping(
     project_id: u32,
+    mapping: BTreeMap<String, TaskStatus>,
     state: State<'_, AppState>
+) -> Result<BTreeMap<String, TaskStatus>, String> {
+    let mapping: BTreeMap<String, TaskStatus> = mapping.into_iter()
+        .map(|(name, status)| (name.trim().to_string(), status))
+        .collect();
+    status_mapping::validate(&mapping).map_err(|e| e.to_string())?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let project = storage.modify_project(project_id, |project| {
+        project.settings.status_mapping = mapping;
+        project.updated_at = chrono::Utc::now().to_rfc3339();
+    }).map_err(|e| format!("Failed to update status mapping: {}", e))?;
+    Ok(project.settings.status_mapping)
+}
+
+#[tauri::command]
+pub async fn delete_project(
+    project_id: u32,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pending: State<'_, PendingDeletes>
 ) -> Result<(), String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
     let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
     
@@ -510,14 +1152,21 @@ pub async fn delete_project(
     data.projects.retain(|p| p.id != project_id);
     
     // Remove all tasks from this project
+    let removed: Vec<u32> = data.tasks.iter().filter(|t| t.project_id == project_id).map(|t| t.id).collect();
     data.tasks.retain(|t| t.project_id != project_id);
     
+    // Drop references from other projects' tasks that depended on the removed ones
+    for task in &mut data.tasks {
+        task.dependencies.retain(|d| !removed.contains(d));
+    }
+    
     // If current project was deleted, switch to first available
     if data.current_project_id == Some(project_id) {
         data.current_project_id = data.projects.first().map(|p| p.id);
     }
     
     storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    pending.deleted(&app, &removed);
     
     Ok(())
 }
@@ -525,16 +1174,12 @@ pub async fn delete_project(
 #[tauri::command]
 pub async fn get_tasks_by_project(
     project_id: u32,
+    fields: Option<Vec<String>>,
     state: State<'_, AppState>
-) -> Result<Vec<Task>, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    let data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
-    
-    let filtered_tasks: Vec<Task> = data.tasks.into_iter()
-        .filter(|t| t.project_id == project_id)
-        .collect();
-    
-    Ok(filtered_tasks)
+) -> Result<Vec<serde_json::Value>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let tasks = storage.data().tasks.iter().filter(|t| t.project_id == project_id);
+    project_task_fields(tasks, fields)
 }
 
 #[tauri::command]
@@ -542,27 +1187,34 @@ pub async fn get_project_stats(
     project_id: u32,
     state: State<'_, AppState>
 ) -> Result<ProjectStats, String> {
-    let tasks = get_tasks_by_project(project_id, state).await?;
-    
-    let todo_count = tasks.iter().filter(|t| t.status == TaskStatus::Todo).count();
-    let in_progress_count = tasks.iter().filter(|t| t.status == TaskStatus::InProgress).count();
-    let done_count = tasks.iter().filter(|t| t.status == TaskStatus::Done).count();
-    let total_count = tasks.len();
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut stats = ProjectStats::empty(project_id);
+    for task in storage.data().tasks.iter().filter(|t| t.project_id == project_id) {
+        stats.count(task);
+    }
+    stats.update_progress();
     
-    let progress_percentage = if total_count > 0 {
-        (done_count as f64 / total_count as f64) * 100.0
-    } else {
-        0.0
-    };
+    Ok(stats)
+}
+
+// Stats for every project in one pass over the tasks, in project order
+#[tauri::command]
+pub async fn get_all_project_stats(state: State<'_, AppState>) -> Result<Vec<ProjectStats>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.data();
+    
+    let mut stats: Vec<ProjectStats> = data.projects.iter().map(|p| ProjectStats::empty(p.id)).collect();
+    let positions: HashMap<u32, usize> = data.projects.iter().enumerate().map(|(i, p)| (p.id, i)).collect();
+    for task in &data.tasks {
+        if let Some(&i) = positions.get(&task.project_id) {
+            stats[i].count(task);
+        }
+    }
+    for project_stats in &mut stats {
+        project_stats.update_progress();
+    }
     
-    Ok(ProjectStats {
-        project_id,
-        total_tasks: total_count,
-        todo_tasks: todo_count,
-        in_progress_tasks: in_progress_count,
-        done_tasks: done_count,
-        progress_percentage,
-    })
+    Ok(stats)
 }
 
 #[derive(serde::Serialize)]
@@ -573,48 +1225,472 @@ pub struct ProjectStats {
     pub in_progress_tasks: usize,
     pub done_tasks: usize,
     pub progress_percentage: f64,
+    pub total_points: u32,
+    pub done_points: u32,
+    pub unsized_tasks: usize,
 }
 
-// Enhanced Data Export/Import Commands
+impl ProjectStats {
+    fn empty(project_id: u32) -> Self {
+        ProjectStats {
+            project_id,
+            total_tasks: 0,
+            todo_tasks: 0,
+            in_progress_tasks: 0,
+            done_tasks: 0,
+            progress_percentage: 0.0,
+            total_points: 0,
+            done_points: 0,
+            unsized_tasks: 0,
+        }
+    }
+
+    fn count(&mut self, task: &Task) {
+        self.total_tasks += 1;
+        match task.status {
+            TaskStatus::Todo => self.todo_tasks += 1,
+            TaskStatus::InProgress => self.in_progress_tasks += 1,
+            TaskStatus::Done => self.done_tasks += 1,
+        }
+        match task.size {
+            Some(points) => {
+                self.total_points += points;
+                if task.status == TaskStatus::Done {
+                    self.done_points += points;
+                }
+            }
+            None => self.unsized_tasks += 1,
+        }
+    }
+
+    fn update_progress(&mut self) {
+        self.progress_percentage = if self.total_tasks > 0 {
+            (self.done_tasks as f64 / self.total_tasks as f64) * 100.0
+        } else {
+            0.0
+        };
+    }
+}
 
+// Totals, deadlines and activity across every project, for the dashboard
 #[tauri::command]
-pub async fn export_data_dialog(
-    state: State<'_, AppState>
-) -> Result<String, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    let data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+pub async fn get_workspace_overview(state: State<'_, AppState>) -> Result<WorkspaceOverview, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    // Create export data with metadata
-    let export_data = ExportData {
-        version: "0.2.1".to_string(),
-        export_date: chrono::Utc::now().to_rfc3339(),
-        data,
-    };
+    Ok(overview::overview(storage.data(), chrono::Utc::now()))
+}
+
+// Kanban columns (status by default) split into optional swimlanes; project_id defaults to the
+// current project
+#[tauri::command]
+pub async fn get_board(
+    project_id: Option<u32>,
+    group_by: Option<BoardGrouping>,
+    lane_by: Option<BoardGrouping>,
+    state: State<'_, AppState>
+) -> Result<Board, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.data();
     
-    let json_content = serde_json::to_string_pretty(&export_data)
-        .map_err(|e| format!("Failed to serialize data: {}", e))?;
+    let project_id = project_id.or(data.current_project_id)
+        .ok_or_else(|| "No project selected".to_string())?;
+    storage.project(project_id).ok_or_else(|| format!("Project with id {} not found", project_id))?;
     
-    // Return the JSON content - frontend will handle file saving with dialog
-    Ok(json_content)
+    board::board(data, project_id, group_by.unwrap_or(BoardGrouping::Status), lane_by)
+        .map_err(|e| format!("Failed to build board: {}", e))
 }
 
+const VELOCITY_WINDOW_DAYS: i64 = 14;
+
+// Estimated vs spent time for a project (or the whole workspace), with a completion forecast
 #[tauri::command]
-pub async fn export_data_to_file(
-    file_path: String,
+pub async fn get_effort_summary(
+    project_id: Option<u32>,
     state: State<'_, AppState>
-) -> Result<(), String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    let data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+) -> Result<EffortSummary, String> {
+    use chrono::{DateTime, Duration, Utc};
     
-    // Create export data with metadata
-    let export_data = ExportData {
-        version: "0.2.1".to_string(),
-        export_date: chrono::Utc::now().to_rfc3339(),
-        data,
-    };
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.data();
     
-    let json_content = serde_json::to_string_pretty(&export_data)
-        .map_err(|e| format!("Failed to serialize data: {}", e))?;
+    if let Some(id) = project_id {
+        storage.project(id).ok_or_else(|| format!("Project with id {} not found", id))?;
+    }
+    let tasks: Vec<&Task> = data.tasks.iter()
+        .filter(|t| project_id.map(|id| t.project_id == id).unwrap_or(true))
+        .collect();
+    let open_tasks: Vec<&&Task> = tasks.iter().filter(|t| t.status != TaskStatus::Done).collect();
+    
+    let estimated_minutes: u32 = tasks.iter().filter_map(|t| t.estimated_time).sum();
+    let spent_minutes: u32 = tasks.iter().map(|t| t.time_spent).sum();
+    let remaining_minutes: u32 = open_tasks.iter()
+        .filter_map(|t| t.estimated_time.map(|e| e.saturating_sub(t.time_spent)))
+        .sum();
+    
+    // Velocity: minutes logged by the timer over the last two weeks, or failing that,
+    // the estimates of tasks finished in that window
+    let now = Utc::now();
+    let window_start = now - Duration::days(VELOCITY_WINDOW_DAYS);
+    let in_window = |timestamp: &str| DateTime::parse_from_rfc3339(timestamp)
+        .map(|d| d.with_timezone(&Utc) >= window_start)
+        .unwrap_or(false);
+    let task_ids: Vec<u32> = tasks.iter().map(|t| t.id).collect();
+    
+    let logged: u32 = data.time_log.iter()
+        .filter(|s| task_ids.contains(&s.task_id) && in_window(&s.started_at))
+        .map(|s| s.minutes)
+        .sum();
+    let recent_minutes = if logged > 0 {
+        logged
+    } else {
+        tasks.iter()
+            .filter(|t| t.status == TaskStatus::Done && in_window(&t.updated_at))
+            .filter_map(|t| t.estimated_time)
+            .sum()
+    };
+    let velocity_minutes_per_day = recent_minutes as f64 / VELOCITY_WINDOW_DAYS as f64;
+    
+    // Points have no timer, so their velocity only comes from finished tasks
+    let total_points: u32 = tasks.iter().filter_map(|t| t.size).sum();
+    let remaining_points: u32 = open_tasks.iter().filter_map(|t| t.size).sum();
+    let recent_points: u32 = tasks.iter()
+        .filter(|t| t.status == TaskStatus::Done && in_window(&t.updated_at))
+        .filter_map(|t| t.size)
+        .sum();
+    let velocity_points_per_day = recent_points as f64 / VELOCITY_WINDOW_DAYS as f64;
+    let points_forecast_completion_date = if remaining_points > 0 && velocity_points_per_day > 0.0 {
+        let days = (remaining_points as f64 / velocity_points_per_day).ceil() as i64;
+        Some((now + Duration::days(days)).to_rfc3339())
+    } else {
+        None
+    };
+    
+    let forecast_completion_date = if remaining_minutes == 0 {
+        None
+    } else if velocity_minutes_per_day > 0.0 {
+        let days = (remaining_minutes as f64 / velocity_minutes_per_day).ceil() as i64;
+        Some((now + Duration::days(days)).to_rfc3339())
+    } else {
+        None
+    };
+    
+    Ok(EffortSummary {
+        project_id,
+        estimated_minutes,
+        spent_minutes,
+        remaining_minutes,
+        open_tasks: open_tasks.len(),
+        unestimated_open_tasks: open_tasks.iter().filter(|t| t.estimated_time.is_none()).count(),
+        velocity_minutes_per_day,
+        forecast_completion_date,
+        total_points,
+        remaining_points,
+        unsized_open_tasks: open_tasks.iter().filter(|t| t.size.is_none()).count(),
+        velocity_points_per_day,
+        points_forecast_completion_date,
+    })
+}
+
+#[derive(serde::Serialize)]
+pub struct EffortSummary {
+    pub project_id: Option<u32>,
+    pub estimated_minutes: u32,
+    pub spent_minutes: u32,
+    pub remaining_minutes: u32, // Open tasks only: estimate minus time already spent
+    pub open_tasks: usize,
+    pub unestimated_open_tasks: usize, // Not covered by the forecast
+    pub velocity_minutes_per_day: f64,
+    pub forecast_completion_date: Option<String>, // None when nothing remains or there is no recent activity
+    pub total_points: u32,
+    pub remaining_points: u32, // Sizes of open tasks
+    pub unsized_open_tasks: usize,
+    pub velocity_points_per_day: f64,
+    pub points_forecast_completion_date: Option<String>,
+}
+
+// Activity within a calendar period (week, month, fiscal quarter or fiscal year) around `date`,
+// a local YYYY-MM-DD day defaulting to today. Period boundaries follow the locale settings.
+#[tauri::command]
+pub async fn get_period_report(
+    period: Period,
+    date: Option<String>,
+    project_id: Option<u32>,
+    state: State<'_, AppState>
+) -> Result<PeriodReport, String> {
+    use chrono::{Local, NaiveDate};
+    
+    let date = match date {
+        Some(day) => NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid date {}: {}", day, e))?,
+        None => Local::now().date_naive(),
+    };
+    
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.data();
+    let locale = &data.settings.locale;
+    
+    if let Some(id) = project_id {
+        storage.project(id).ok_or_else(|| format!("Project with id {} not found", id))?;
+    }
+    
+    let (start, end) = calendar::period_bounds(period, date, locale);
+    let in_period = |timestamp: &str| calendar::local_date(timestamp)
+        .map(|d| d >= start && d <= end)
+        .unwrap_or(false);
+    let tasks: Vec<&Task> = data.tasks.iter()
+        .filter(|t| project_id.is_none_or(|id| t.project_id == id))
+        .collect();
+    let task_ids: Vec<u32> = tasks.iter().map(|t| t.id).collect();
+    
+    let mut due_tasks: Vec<Task> = tasks.iter()
+        .filter(|t| t.due_date.as_deref().map(in_period).unwrap_or(false))
+        .map(|t| (*t).clone())
+        .collect();
+    due_tasks.sort_by(|a, b| a.due_date.cmp(&b.due_date));
+    
+    Ok(PeriodReport {
+        period,
+        start: start.format("%Y-%m-%d").to_string(),
+        end: end.format("%Y-%m-%d").to_string(),
+        label: format!("{} – {}", calendar::format_date(start, locale), calendar::format_date(end, locale)),
+        created_tasks: tasks.iter().filter(|t| in_period(&t.created_at)).count(),
+        // Tasks don't record when they were finished, so the last update of a done task stands in
+        completed_tasks: tasks.iter().filter(|t| t.status == TaskStatus::Done && in_period(&t.updated_at)).count(),
+        logged_minutes: data.time_log.iter()
+            .filter(|s| task_ids.contains(&s.task_id) && in_period(&s.started_at))
+            .map(|s| s.minutes)
+            .sum(),
+        due_tasks,
+    })
+}
+
+#[derive(serde::Serialize)]
+pub struct PeriodReport {
+    pub period: Period,
+    pub start: String, // YYYY-MM-DD, inclusive
+    pub end: String, // YYYY-MM-DD, inclusive
+    pub label: String, // Both dates in the configured date format
+    pub created_tasks: usize,
+    pub completed_tasks: usize,
+    pub logged_minutes: u32,
+    pub due_tasks: Vec<Task>,
+}
+
+#[tauri::command]
+pub async fn get_locale_settings(state: State<'_, AppState>) -> Result<LocaleSettings, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.data().settings.locale.clone())
+}
+
+#[tauri::command]
+pub async fn update_locale_settings(
+    settings: LocaleSettings,
+    state: State<'_, AppState>
+) -> Result<LocaleSettings, String> {
+    calendar::validate(&settings).map_err(|e| format!("Invalid locale settings: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    data.settings.locale = settings.clone();
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(settings)
+}
+
+// `start` and `end` are local YYYY-MM-DD days; the plan covers every week touching the range
+#[tauri::command]
+pub async fn get_capacity_plan(
+    start: String,
+    end: String,
+    project_id: Option<u32>,
+    state: State<'_, AppState>
+) -> Result<CapacityPlan, String> {
+    use chrono::{Local, NaiveDate};
+    
+    let start = NaiveDate::parse_from_str(&start, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid start date {}: {}", start, e))?;
+    let end = NaiveDate::parse_from_str(&end, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid end date {}: {}", end, e))?;
+    
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if let Some(id) = project_id {
+        storage.project(id).ok_or_else(|| format!("Project with id {} not found", id))?;
+    }
+    
+    capacity::plan(storage.data(), start, end, project_id, Local::now().date_naive())
+        .map_err(|e| format!("Failed to build capacity plan: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_capacity_settings(state: State<'_, AppState>) -> Result<CapacitySettings, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.data().settings.capacity.clone())
+}
+
+#[tauri::command]
+pub async fn update_capacity_settings(
+    settings: CapacitySettings,
+    state: State<'_, AppState>
+) -> Result<CapacitySettings, String> {
+    capacity::validate(&settings).map_err(|e| format!("Invalid capacity settings: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    data.settings.capacity = settings.clone();
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn get_working_days_settings(state: State<'_, AppState>) -> Result<WorkingDaysSettings, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.data().settings.working_days.clone())
+}
+
+#[tauri::command]
+pub async fn update_working_days_settings(
+    settings: WorkingDaysSettings,
+    state: State<'_, AppState>
+) -> Result<WorkingDaysSettings, String> {
+    workdays::validate(&settings).map_err(|e| format!("Invalid working days settings: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    data.settings.working_days = settings.clone();
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn get_weekly_review_settings(state: State<'_, AppState>) -> Result<WeeklyReviewSettings, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.data().settings.weekly_review.clone())
+}
+
+#[tauri::command]
+pub async fn update_weekly_review_settings(
+    settings: WeeklyReviewSettings,
+    state: State<'_, AppState>
+) -> Result<WeeklyReviewSettings, String> {
+    weekly_review::validate(&settings).map_err(|e| format!("Invalid weekly review settings: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let mut settings = settings;
+    // Turning the reminder on starts from now rather than firing for a review already past
+    settings.last_reminded_at = if settings.enabled && !data.settings.weekly_review.enabled {
+        Some(chrono::Utc::now().to_rfc3339())
+    } else {
+        data.settings.weekly_review.last_reminded_at.clone()
+    };
+    data.settings.weekly_review = settings.clone();
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(settings)
+}
+
+// Overdue, stale and unestimated open tasks to walk through, for one project or all of them
+#[tauri::command]
+pub async fn get_review_packet(
+    project_id: Option<u32>,
+    state: State<'_, AppState>
+) -> Result<ReviewPacket, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if let Some(id) = project_id {
+        storage.project(id).ok_or_else(|| format!("Project with id {} not found", id))?;
+    }
+    
+    Ok(weekly_review::packet(storage.data(), project_id, chrono::Utc::now()))
+}
+
+// Accepts an .ics holiday calendar or a text/CSV file of "YYYY-MM-DD,Name" lines
+#[tauri::command]
+pub async fn import_holidays(
+    path: String,
+    state: State<'_, AppState>
+) -> Result<WorkingDaysSettings, String> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let holidays = workdays::parse_holidays(&content)
+        .map_err(|e| format!("Failed to import holidays: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    workdays::merge_holidays(&mut data.settings.working_days, holidays);
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(data.settings.working_days)
+}
+
+// `estimate` is in minutes, worked at the daily share of the weekly capacity
+#[tauri::command]
+pub async fn suggest_due_date(
+    estimate: u32,
+    state: State<'_, AppState>
+) -> Result<DueDateSuggestion, String> {
+    use chrono::Local;
+    
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    workdays::suggest_due_date(storage.data(), estimate, Local::now().date_naive())
+        .map_err(|e| format!("Failed to suggest a due date: {}", e))
+}
+
+// Enhanced Data Export/Import Commands
+
+#[tauri::command]
+pub async fn export_data_dialog(
+    state: State<'_, AppState>
+) -> Result<String, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    
+    // Create export data with metadata
+    let export_data = ExportData::new(data).map_err(|e| format!("Failed to checksum data: {}", e))?;
+    
+    let json_content = serde_json::to_string_pretty(&export_data)
+        .map_err(|e| format!("Failed to serialize data: {}", e))?;
+    
+    // Return the JSON content - frontend will handle file saving with dialog
+    Ok(json_content)
+}
+
+// Same format as export_data_dialog, with all free text scrambled for attaching to bug reports
+#[tauri::command]
+pub async fn export_anonymized(state: State<'_, AppState>) -> Result<String, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = anonymize::anonymize(storage.data());
+    
+    let export_data = ExportData::new(data).map_err(|e| format!("Failed to checksum data: {}", e))?;
+    
+    serde_json::to_string_pretty(&export_data)
+        .map_err(|e| format!("Failed to serialize data: {}", e))
+}
+
+#[tauri::command]
+pub async fn export_data_to_file(
+    file_path: String,
+    state: State<'_, AppState>
+) -> Result<(), String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    
+    // Create export data with metadata
+    let export_data = ExportData::new(data).map_err(|e| format!("Failed to checksum data: {}", e))?;
+    
+    let json_content = serde_json::to_string_pretty(&export_data)
+        .map_err(|e| format!("Failed to serialize data: {}", e))?;
     
     std::fs::write(file_path, json_content)
         .map_err(|e| format!("Failed to write file: {}", e))?;
@@ -622,16 +1698,139 @@ pub async fn export_data_to_file(
     Ok(())
 }
 
+// Compact JSON for sharing a single task, see snippet.rs
+#[tauri::command]
+pub async fn export_task(task_id: u32, state: State<'_, AppState>) -> Result<String, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let task = storage.task(task_id).ok_or_else(|| format!("Task with id {} not found", task_id))?;
+    snippet::to_snippet(task).map_err(|e| format!("Failed to export task: {}", e))
+}
+
+#[tauri::command]
+pub async fn import_task(
+    snippet: String,
+    project_id: u32,
+    state: State<'_, AppState>
+) -> Result<Task, String> {
+    let snippet = snippet::parse(&snippet).map_err(|e| format!("Failed to import task: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    storage.project(project_id).ok_or_else(|| format!("Project with id {} not found", project_id))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let new_id = data.tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    let task = snippet::into_task(snippet, new_id, project_id);
+    data.tasks.push(task.clone());
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(task)
+}
+
+// CSV import, step one: headers, a few rows and a guessed column mapping
+#[tauri::command]
+pub async fn inspect_csv(path: String) -> Result<CsvPreview, String> {
+    csv_import::inspect(Path::new(&path)).map_err(|e| format!("Failed to read CSV: {}", e))
+}
+
+// Step two: import with the mapping the user confirmed
+#[tauri::command]
+pub async fn import_csv(
+    path: String,
+    mapping: CsvMapping,
+    state: State<'_, AppState>
+) -> Result<CsvImportResult, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let result = csv_import::import(&mut data, Path::new(&path), &mapping)
+        .map_err(|e| format!("Failed to import CSV: {}", e))?;
+    
+    if result.imported_tasks > 0 {
+        storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    }
+    
+    Ok(result)
+}
+
+// Imports (or re-imports, updating what was imported before) a GitHub Projects board
+#[tauri::command]
+pub async fn import_github_project(
+    options: GithubImportOptions,
+    state: State<'_, AppState>
+) -> Result<BoardImportResult, String> {
+    // Fetched before taking the lock; large boards take a while
+    let fetch_options = options.clone();
+    let board = blocking(move || github_import::fetch(&fetch_options)).await?
+        .map_err(|e| format!("Failed to import from GitHub: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let result = github_import::apply(&mut data, board, &options)
+        .map_err(|e| format!("Failed to import from GitHub: {}", e))?;
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn import_from_azure_devops(
+    csv_or_query: AzureDevopsSource,
+    project_id: Option<u32>,
+    status_map: Option<HashMap<String, TaskStatus>>,
+    state: State<'_, AppState>
+) -> Result<BoardImportResult, String> {
+    let board = blocking(move || azure_devops_import::fetch(&csv_or_query)).await?
+        .map_err(|e| format!("Failed to import from Azure DevOps: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let result = azure_devops_import::apply(&mut data, board, project_id, &status_map.unwrap_or_default())
+        .map_err(|e| format!("Failed to import from Azure DevOps: {}", e))?;
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn import_from_clickup(
+    csv: String,
+    status_map: Option<HashMap<String, TaskStatus>>,
+    state: State<'_, AppState>
+) -> Result<Vec<BoardImportResult>, String> {
+    let boards = clickup_import::read(&csv).map_err(|e| format!("Failed to import from ClickUp: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let results = clickup_import::apply(&mut data, boards, &status_map.unwrap_or_default())
+        .map_err(|e| format!("Failed to import from ClickUp: {}", e))?;
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(results)
+}
+
 #[tauri::command]
 pub async fn import_data_from_content(
     json_content: String,
     merge_mode: bool,
     state: State<'_, AppState>
 ) -> Result<ImportResult, String> {
-    let storage = state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    // A truncated or tampered file is rejected before anything is loaded
+    let mut value = integrity::parse_json(json_content.as_bytes()).map_err(|e| format!("Failed to import data: {}", e))?;
+    integrity::verify(&value).map_err(|e| format!("Failed to import data: {}", e))?;
+    // Older files are brought up to date and files from a newer version refused, as on restore
+    let data = match value.get_mut("data") {
+        Some(data) => data,
+        None => &mut value,
+    };
+    migrations::migrate(data).map_err(|e| format!("Failed to import data: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
     // Try to parse as export data first
-    let import_result = if let Ok(export_data) = serde_json::from_str::<ExportData>(&json_content) {
+    let import_result = if let Ok(mut export_data) = serde_json::from_value::<ExportData>(value.clone()) {
         if merge_mode {
             // Merge with existing data
             let mut current_data = storage.load_data().map_err(|e| format!("Failed to load current data: {}", e))?;
@@ -642,28 +1841,71 @@ pub async fn import_data_from_content(
             
             let mut imported_tasks = 0;
             let mut imported_projects = 0;
+            let mut skipped_duplicates = 0;
+            let mut updated_duplicates = 0;
+            // Task ids in the file -> ids here, to carry dependencies over to the new tasks
+            let mut task_ids: HashMap<u32, u32> = HashMap::new();
+            let mut added_tasks: Vec<u32> = Vec::new();
             
             // Import projects
             for mut project in export_data.data.projects {
-                max_project_id += 1;
                 let old_id = project.id;
-                project.id = max_project_id;
                 
-                // Update task references to new project ID
-                for task in &mut export_data.data.tasks.clone() {
-                    if task.project_id == old_id {
-                        // This task belongs to the imported project
-                        max_task_id += 1;
-                        let mut new_task = task.clone();
-                        new_task.id = max_task_id;
-                        new_task.project_id = max_project_id;
-                        current_data.tasks.push(new_task);
-                        imported_tasks += 1;
+                // Re-importing the same file merges into the project it created last time
+                let existing_project_id = current_data.projects.iter()
+                    .find(|p| p.name == project.name && p.created_at == project.created_at)
+                    .map(|p| p.id);
+                let target_project_id = match existing_project_id {
+                    Some(id) => id,
+                    None => {
+                        max_project_id += 1;
+                        project.id = max_project_id;
+                        current_data.projects.push(project);
+                        imported_projects += 1;
+                        max_project_id
                     }
-                }
+                };
                 
-                current_data.projects.push(project);
-                imported_projects += 1;
+                for task in export_data.data.tasks.iter().filter(|t| t.project_id == old_id) {
+                    let duplicate = current_data.tasks.iter_mut().find(|t| {
+                        t.project_id == target_project_id && t.title == task.title && t.created_at == task.created_at
+                    });
+                    
+                    match duplicate {
+                        // Same task seen again: keep whichever copy was edited last
+                        Some(existing) if is_later(&task.updated_at, &existing.updated_at) => {
+                            // Dependency ids in the file refer to the exporting workspace, so keep ours
+                            let id = existing.id;
+                            let dependencies = std::mem::take(&mut existing.dependencies);
+                            *existing = task.clone();
+                            existing.id = id;
+                            existing.project_id = target_project_id;
+                            existing.dependencies = dependencies;
+                            task_ids.insert(task.id, id);
+                            updated_duplicates += 1;
+                        }
+                        Some(existing) => {
+                            task_ids.insert(task.id, existing.id);
+                            skipped_duplicates += 1;
+                        }
+                        None => {
+                            max_task_id += 1;
+                            let mut new_task = task.clone();
+                            new_task.id = max_task_id;
+                            new_task.project_id = target_project_id;
+                            current_data.tasks.push(new_task);
+                            task_ids.insert(task.id, max_task_id);
+                            added_tasks.push(max_task_id);
+                            imported_tasks += 1;
+                        }
+                    }
+                }
+            }
+            
+            // The new tasks' dependencies are renumbered along with the tasks; ones on tasks that
+            // weren't in the file are dropped
+            for task in current_data.tasks.iter_mut().filter(|t| added_tasks.contains(&t.id)) {
+                task.dependencies = task.dependencies.iter().filter_map(|d| task_ids.get(d).copied()).collect();
             }
             
             storage.save_data(&current_data).map_err(|e| format!("Failed to save merged data: {}", e))?;
@@ -672,36 +1914,51 @@ pub async fn import_data_from_content(
                 success: true,
                 imported_tasks,
                 imported_projects,
-                message: format!("Successfully merged {} tasks and {} projects", imported_tasks, imported_projects),
+                skipped_duplicates,
+                updated_duplicates,
+                message: if skipped_duplicates + updated_duplicates > 0 {
+                    format!("Successfully merged {} tasks and {} projects ({} duplicates skipped, {} updated)",
+                        imported_tasks, imported_projects, skipped_duplicates, updated_duplicates)
+                } else {
+                    format!("Successfully merged {} tasks and {} projects", imported_tasks, imported_projects)
+                },
                 export_version: export_data.version,
                 export_date: Some(export_data.export_date),
             }
         } else {
             // Replace all data
+            secrets::carry_over(storage.data(), &mut export_data.data);
             storage.save_data(&export_data.data).map_err(|e| format!("Failed to save imported data: {}", e))?;
+            storage.flush().map_err(|e| format!("Failed to save imported data: {}", e))?;
             
             ImportResult {
                 success: true,
                 imported_tasks: export_data.data.tasks.len(),
                 imported_projects: export_data.data.projects.len(),
+                skipped_duplicates: 0,
+                updated_duplicates: 0,
                 message: format!("Successfully imported {} tasks and {} projects", 
                     export_data.data.tasks.len(), export_data.data.projects.len()),
                 export_version: export_data.version,
                 export_date: Some(export_data.export_date),
             }
         }
-    } else if let Ok(legacy_data) = serde_json::from_str::<crate::models::RoadmapData>(&json_content) {
+    } else if let Ok(mut legacy_data) = serde_json::from_value::<crate::models::RoadmapData>(value) {
         // Direct RoadmapData import
         if merge_mode {
             return Err("Merge mode not supported for legacy data format".to_string());
         }
         
+        secrets::carry_over(storage.data(), &mut legacy_data);
         storage.save_data(&legacy_data).map_err(|e| format!("Failed to save legacy data: {}", e))?;
+        storage.flush().map_err(|e| format!("Failed to save legacy data: {}", e))?;
         
         ImportResult {
             success: true,
             imported_tasks: legacy_data.tasks.len(),
             imported_projects: legacy_data.projects.len(),
+            skipped_duplicates: 0,
+            updated_duplicates: 0,
             message: format!("Successfully imported legacy data: {} tasks and {} projects", 
                 legacy_data.tasks.len(), legacy_data.projects.len()),
             export_version: "legacy".to_string(),
@@ -716,8 +1973,18 @@ pub async fn import_data_from_content(
 
 #[tauri::command]
 pub async fn validate_import_data(
-    json_content: String
+    json_content: String,
+    strict: Option<bool>
 ) -> Result<ImportValidation, String> {
+    if strict.unwrap_or(false) {
+        return Ok(validate_import_data_strict(&json_content));
+    }
+    
+    // Truncated files and checksum mismatches are reported as such, not as an unknown format
+    if let Err(e) = integrity::parse_json(json_content.as_bytes()).and_then(|value| integrity::verify(&value)) {
+        return Ok(invalid_import(e.to_string()));
+    }
+    
     // Try to parse as export data
     if let Ok(export_data) = serde_json::from_str::<ExportData>(&json_content) {
         Ok(ImportValidation {
@@ -747,38 +2014,128 @@ pub async fn validate_import_data(
             errors: vec![],
         })
     } else {
-        Ok(ImportValidation {
-            valid: false,
-            version: "unknown".to_string(),
-            export_date: None,
-            task_count: 0,
-            project_count: 0,
-            format_type: "invalid".to_string(),
-            warnings: vec![],
-            errors: vec!["Invalid JSON format or unrecognized data structure".to_string()],
-        })
+        Ok(invalid_import("Invalid JSON format or unrecognized data structure".to_string()))
+    }
+}
+
+fn invalid_import(error: String) -> ImportValidation {
+    ImportValidation {
+        valid: false,
+        version: "unknown".to_string(),
+        export_date: None,
+        task_count: 0,
+        project_count: 0,
+        format_type: "invalid".to_string(),
+        warnings: vec![],
+        errors: vec![error],
+    }
+}
+
+// Checks the raw JSON against the published export schema, reporting every violation with its path
+fn validate_import_data_strict(json_content: &str) -> ImportValidation {
+    let value: serde_json::Value = match serde_json::from_str(json_content) {
+        Ok(value) => value,
+        Err(e) => {
+            return ImportValidation {
+                valid: false,
+                version: "unknown".to_string(),
+                export_date: None,
+                task_count: 0,
+                project_count: 0,
+                format_type: "invalid".to_string(),
+                warnings: vec![],
+                errors: vec![format!("$: invalid JSON at line {}, column {}: {}", e.line(), e.column(), e)],
+            };
+        }
+    };
+    
+    let export_schema = schema::export_schema();
+    let is_export = value.get("data").is_some();
+    let (mut errors, data) = if is_export {
+        (schema::validate(&export_schema, &value), value.get("data"))
+    } else {
+        (schema::validate_definition(&export_schema, "RoadmapData", &value), Some(&value))
+    };
+    if let Err(e) = integrity::verify(&value) {
+        errors.push(format!("$.checksum: {}", e));
+    }
+    
+    let count = |key: &str| data
+        .and_then(|d| d.get(key))
+        .and_then(|v| v.as_array())
+        .map(|a| a.len())
+        .unwrap_or(0);
+    
+    let mut warnings = vec![];
+    if !is_export {
+        warnings.push("Legacy data format detected. Some features may not be available.".to_string());
+    }
+    
+    ImportValidation {
+        valid: errors.is_empty(),
+        version: value.get("version").and_then(|v| v.as_str()).unwrap_or("unknown").to_string(),
+        export_date: value.get("export_date").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        task_count: count("tasks"),
+        project_count: count("projects"),
+        format_type: if is_export { "export" } else { "legacy" }.to_string(),
+        warnings,
+        errors,
     }
 }
 
+#[tauri::command]
+pub async fn get_export_schema() -> Result<serde_json::Value, String> {
+    Ok(schema::export_schema())
+}
+
 // Data structures for export/import
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct ExportData {
     pub version: String,
     pub export_date: String,
+    // SHA-256 of `data`, see integrity.rs; missing in files exported by older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
     pub data: crate::models::RoadmapData,
 }
 
+impl ExportData {
+    // Every export and backup is made here, so this is where credentials are left out
+    pub fn new(mut data: crate::models::RoadmapData) -> anyhow::Result<Self> {
+        secrets::strip(&mut data);
+        // Belongs to this device; a copy restored elsewhere would sync under the same device id
+        data.settings.sync = Default::default();
+        Ok(ExportData {
+            version: "0.2.1".to_string(),
+            export_date: chrono::Utc::now().to_rfc3339(),
+            checksum: Some(integrity::checksum(&data)?),
+            data,
+        })
+    }
+}
+
 #[derive(serde::Serialize)]
 pub struct ImportResult {
     pub success: bool,
     pub imported_tasks: usize,
     pub imported_projects: usize,
+    // Merge imports only: tasks already present (same project, title and creation time)
+    pub skipped_duplicates: usize,
+    pub updated_duplicates: usize,
     pub message: String,
     pub export_version: String,
     pub export_date: Option<String>,
 }
 
+// Timestamps are RFC 3339; fall back to string order if either one doesn't parse
+fn is_later(a: &str, b: &str) -> bool {
+    match (chrono::DateTime::parse_from_rfc3339(a), chrono::DateTime::parse_from_rfc3339(b)) {
+        (Ok(a), Ok(b)) => a > b,
+        _ => a > b,
+    }
+}
+
 #[derive(serde::Serialize)]
 pub struct ImportValidation {
     pub valid: bool,
@@ -789,4 +2146,1020 @@ pub struct ImportValidation {
     pub format_type: String,
     pub warnings: Vec<String>,
     pub errors: Vec<String>,
-}
\ No newline at end of file
+}
+// Calendar Feed Commands
+// The storage lock is always taken before the feed's; the feed reads the data file, never the storage
+
+#[tauri::command]
+pub async fn start_ical_feed(
+    port: Option<u16>,
+    state: State<'_, AppState>,
+    feed_state: State<'_, IcalFeedState>
+) -> Result<IcalFeedInfo, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut feed = feed_state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    
+    // Keep the token stable across restarts so existing subscriptions keep working
+    let settings = &mut data.settings.ical_feed;
+    let token = settings.token.get_or_insert_with(generate_token).clone();
+    if let Some(port) = port {
+        settings.port = port;
+    }
+    settings.enabled = true;
+    let port = settings.port;
+    
+    // Stop any running server before rebinding; the new one is only kept once the settings are saved
+    feed.take();
+    let server = IcalFeedServer::start(storage.get_data_file_path().to_path_buf(), port, token)
+        .map_err(|e| format!("Failed to start calendar feed: {}", e))?;
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    let info = server.info();
+    *feed = Some(server);
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_ical_feed(
+    state: State<'_, AppState>,
+    feed_state: State<'_, IcalFeedState>
+) -> Result<(), String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut feed = feed_state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    feed.take();
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    data.settings.ical_feed.enabled = false;
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_ical_feed_status(
+    state: State<'_, AppState>,
+    feed_state: State<'_, IcalFeedState>
+) -> Result<IcalFeedInfo, String> {
+    // Storage before the feed, like every other feed command; the settings are copied so the two
+    // locks are never held together
+    let settings = {
+        let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        storage.data().settings.ical_feed.clone()
+    };
+    let feed = feed_state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if let Some(server) = feed.as_ref() {
+        return Ok(server.info());
+    }
+    
+    Ok(IcalFeedInfo {
+        running: false,
+        port: settings.port,
+        token: settings.token.clone(),
+        url: None,
+    })
+}
+
+#[tauri::command]
+pub async fn regenerate_ical_feed_token(
+    state: State<'_, AppState>,
+    feed_state: State<'_, IcalFeedState>
+) -> Result<IcalFeedInfo, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut feed = feed_state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let token = generate_token();
+    data.settings.ical_feed.token = Some(token.clone());
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    // Restart a running feed so the old token stops working immediately
+    let port = data.settings.ical_feed.port;
+    if feed.take().is_some() {
+        let server = IcalFeedServer::start(storage.get_data_file_path().to_path_buf(), port, token)
+            .map_err(|e| format!("Failed to restart calendar feed: {}", e))?;
+        let info = server.info();
+        *feed = Some(server);
+        return Ok(info);
+    }
+    
+    Ok(IcalFeedInfo {
+        running: false,
+        port,
+        token: Some(token),
+        url: None,
+    })
+}
+
+// Inbox Endpoint Commands
+// A running server is dropped before the storage lock is taken: its worker may be waiting for it
+
+#[tauri::command]
+pub async fn start_inbox(
+    port: Option<u16>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    inbox_state: State<'_, InboxState>
+) -> Result<InboxInfo, String> {
+    let mut inbox = inbox_state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    inbox.take();
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    
+    // Keep the token stable across restarts so configured automations keep working
+    let settings = &mut data.settings.inbox;
+    let token = settings.token.get_or_insert_with(generate_token).clone();
+    if let Some(port) = port {
+        settings.port = port;
+    }
+    settings.enabled = true;
+    let port = settings.port;
+    let project_id = settings.project_id;
+    
+    // As with the calendar feed, the new server is only kept once the settings are saved
+    let server = InboxServer::start(app.clone(), port, token)
+        .map_err(|e| format!("Failed to start inbox endpoint: {}", e))?;
+    let info = server.info(project_id);
+    
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    *inbox = Some(server);
+    
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_inbox(
+    state: State<'_, AppState>,
+    inbox_state: State<'_, InboxState>
+) -> Result<(), String> {
+    let mut inbox = inbox_state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    inbox.take();
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    data.settings.inbox.enabled = false;
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_inbox_status(
+    state: State<'_, AppState>,
+    inbox_state: State<'_, InboxState>
+) -> Result<InboxInfo, String> {
+    let inbox = inbox_state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let settings = &storage.data().settings.inbox;
+    if let Some(server) = inbox.as_ref() {
+        return Ok(server.info(settings.project_id));
+    }
+    
+    Ok(InboxInfo {
+        running: false,
+        port: settings.port,
+        token: settings.token.clone(),
+        project_id: settings.project_id,
+        url: None,
+    })
+}
+
+// None sends new items to the "Inbox" project
+#[tauri::command]
+pub async fn set_inbox_project(
+    project_id: Option<u32>,
+    state: State<'_, AppState>
+) -> Result<(), String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    if let Some(id) = project_id {
+        data.projects.iter().find(|p| p.id == id).ok_or_else(|| format!("Project with id {} not found", id))?;
+    }
+    data.settings.inbox.project_id = project_id;
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn regenerate_inbox_token(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    inbox_state: State<'_, InboxState>
+) -> Result<InboxInfo, String> {
+    let mut inbox = inbox_state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let was_running = inbox.take().is_some();
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let token = generate_token();
+    data.settings.inbox.token = Some(token.clone());
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    // Restart a running endpoint so the old token stops working immediately
+    let settings = &data.settings.inbox;
+    if was_running {
+        let server = InboxServer::start(app.clone(), settings.port, token)
+            .map_err(|e| format!("Failed to restart inbox endpoint: {}", e))?;
+        let info = server.info(settings.project_id);
+        *inbox = Some(server);
+        return Ok(info);
+    }
+    
+    Ok(InboxInfo {
+        running: false,
+        port: settings.port,
+        token: Some(token),
+        project_id: settings.project_id,
+        url: None,
+    })
+}
+
+// Email Inbox Commands
+
+#[tauri::command]
+pub async fn get_email_inbox_settings(state: State<'_, AppState>) -> Result<EmailInboxSettings, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.data().settings.email_inbox.clone())
+}
+
+#[tauri::command]
+pub async fn update_email_inbox_settings(
+    settings: EmailInboxSettings,
+    state: State<'_, AppState>
+) -> Result<EmailInboxSettings, String> {
+    email_inbox::validate(&settings).map_err(|e| format!("Invalid email inbox settings: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let current = &data.settings.email_inbox;
+    let mut settings = settings;
+    // Progress is the poller's to track; pointing at another folder starts over with its unread mail
+    let same_folder = settings.host.trim() == current.host.trim()
+        && settings.username.trim() == current.username.trim()
+        && settings.folder.trim() == current.folder.trim();
+    settings.uid_validity = current.uid_validity.filter(|_| same_folder);
+    settings.last_uid = current.last_uid.filter(|_| same_folder);
+    settings.last_checked_at = current.last_checked_at.clone();
+    settings.last_error = current.last_error.clone();
+    data.settings.email_inbox = settings.clone();
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(settings)
+}
+
+// Polls right away, whether or not polling is enabled
+#[tauri::command]
+pub async fn check_email_inbox_now(app: AppHandle) -> Result<EmailPollResult, String> {
+    blocking(move || email_inbox::poll(&app)).await?
+        .map_err(|e| format!("Failed to check the email inbox: {}", e))
+}
+
+// Remote Backup Commands
+
+#[tauri::command]
+pub async fn get_remote_backup_settings(state: State<'_, AppState>) -> Result<RemoteBackupSettings, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.data().settings.remote_backup.clone())
+}
+
+#[tauri::command]
+pub async fn update_remote_backup_settings(
+    settings: RemoteBackupSettings,
+    state: State<'_, AppState>
+) -> Result<RemoteBackupSettings, String> {
+    remote_backup::validate(&settings).map_err(|e| format!("Invalid remote backup settings: {}", e))?;
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let mut settings = settings;
+    // Kept by the scheduler
+    settings.last_backup_at = data.settings.remote_backup.last_backup_at.clone();
+    settings.last_error = data.settings.remote_backup.last_error.clone();
+    data.settings.remote_backup = settings.clone();
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(settings)
+}
+
+// Uploads right away, whether or not scheduled backups are enabled
+#[tauri::command]
+pub async fn backup_to_remote_now(app: AppHandle) -> Result<RemoteBackup, String> {
+    blocking(move || remote_backup::backup_now(&app)).await?
+        .map_err(|e| format!("Failed to upload backup: {}", e))
+}
+
+#[tauri::command]
+pub async fn list_remote_backups(state: State<'_, AppState>) -> Result<Vec<RemoteBackup>, String> {
+    let settings = {
+        let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        storage.data().settings.remote_backup.clone()
+    };
+    
+    blocking(move || remote_backup::list(&settings)).await?
+        .map_err(|e| format!("Failed to list remote backups: {}", e))
+}
+
+#[tauri::command]
+pub async fn restore_from_remote(backup_id: String, app: AppHandle) -> Result<(), String> {
+    blocking(move || remote_backup::restore(&app, &backup_id)).await?
+        .map_err(|e| format!("Failed to restore data: {}", e))
+}
+
+// Syncs with the other devices through the remote backup folder, encrypted with its passphrase
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncResult, String> {
+    blocking(move || sync::sync_now(&app)).await?
+        .map_err(|e| format!("Failed to sync: {}", e))
+}
+
+// Share Board Commands
+
+#[tauri::command]
+pub async fn start_share_board(
+    port: Option<u16>,
+    lan: Option<bool>,
+    project_id: Option<u32>, // None follows whichever project is current
+    state: State<'_, AppState>,
+    board_state: State<'_, ShareBoardState>
+) -> Result<ShareBoardInfo, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut board = board_state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    if let Some(id) = project_id {
+        storage.project(id).ok_or_else(|| format!("Project with id {} not found", id))?;
+    }
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    
+    // Like the calendar feed, the token survives restarts so open displays reconnect
+    let settings = &mut data.settings.share_board;
+    let token = settings.token.get_or_insert_with(generate_token).clone();
+    if let Some(port) = port {
+        settings.port = port;
+    }
+    if let Some(lan) = lan {
+        settings.lan = lan;
+    }
+    settings.project_id = project_id;
+    settings.enabled = true;
+    let settings = settings.clone();
+    
+    // As with the calendar feed, the new server is only kept once the settings are saved
+    board.take();
+    let server = ShareBoardServer::start(storage.get_data_file_path().to_path_buf(), settings.port, settings.lan, token, settings.project_id)
+        .map_err(|e| format!("Failed to start share board: {}", e))?;
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    let info = server.info();
+    *board = Some(server);
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_share_board(
+    state: State<'_, AppState>,
+    board_state: State<'_, ShareBoardState>
+) -> Result<(), String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut board = board_state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    board.take();
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    data.settings.share_board.enabled = false;
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_share_board_status(
+    state: State<'_, AppState>,
+    board_state: State<'_, ShareBoardState>
+) -> Result<ShareBoardInfo, String> {
+    // Storage before the board, as in the other share board commands
+    let settings = {
+        let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        storage.data().settings.share_board.clone()
+    };
+    let board = board_state.0.lock().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if let Some(server) = board.as_ref() {
+        return Ok(server.info());
+    }
+    
+    Ok(ShareBoardInfo {
+        running: false,
+        port: settings.port,
+        lan: settings.lan,
+        token: settings.token.clone(),
+        project_id: settings.project_id,
+        url: None,
+    })
+}
+
+// Task Dependency Commands
+
+#[tauri::command]
+pub async fn add_task_dependency(
+    task_id: u32,
+    depends_on_id: u32,
+    state: State<'_, AppState>
+) -> Result<Task, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    if task_id == depends_on_id {
+        return Err("A task cannot depend on itself".to_string());
+    }
+    if storage.task(depends_on_id).is_none() {
+        return Err(format!("Task with id {} not found", depends_on_id));
+    }
+    if storage.data().dependency_creates_cycle(task_id, depends_on_id) {
+        return Err(format!("Task {} already depends on task {}; this would create a cycle", depends_on_id, task_id));
+    }
+    
+    storage.modify_task(task_id, |task| task.add_dependency(depends_on_id))
+        .map_err(|e| format!("Failed to update task: {}", e))
+}
+
+#[tauri::command]
+pub async fn remove_task_dependency(
+    task_id: u32,
+    depends_on_id: u32,
+    state: State<'_, AppState>
+) -> Result<Task, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    storage.modify_task(task_id, |task| task.remove_dependency(depends_on_id))
+        .map_err(|e| format!("Failed to update task: {}", e))
+}
+
+#[tauri::command]
+pub async fn export_dependency_graph(
+    project_id: u32,
+    format: Option<String>,
+    state: State<'_, AppState>
+) -> Result<String, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let dot = export::dependency_graph_dot(storage.data(), project_id)
+        .map_err(|e| format!("Failed to build dependency graph: {}", e))?;
+    
+    match format.as_deref().unwrap_or("dot") {
+        "dot" => Ok(dot),
+        "svg" => export::render_dot_to_svg(&dot).map_err(|e| format!("Failed to render SVG: {}", e)),
+        other => Err(format!("Unsupported graph format: {}", other)),
+    }
+}
+
+#[tauri::command]
+pub async fn export_mermaid_gantt(
+    project_id: u32,
+    state: State<'_, AppState>
+) -> Result<String, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    export::mermaid_gantt(storage.data(), project_id)
+        .map_err(|e| format!("Failed to build Gantt chart: {}", e))
+}
+
+#[tauri::command]
+pub async fn export_opml(
+    project_id: Option<u32>,
+    state: State<'_, AppState>
+) -> Result<String, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    opml::export(storage.data(), project_id)
+        .map_err(|e| format!("Failed to export OPML: {}", e))
+}
+
+#[tauri::command]
+pub async fn import_opml(
+    content: String,
+    project_id: Option<u32>,
+    state: State<'_, AppState>
+) -> Result<OpmlImportResult, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let result = opml::import(&mut data, &content, project_id)
+        .map_err(|e| format!("Failed to import OPML: {}", e))?;
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(result)
+}
+
+// Template Export Commands
+
+#[tauri::command]
+pub async fn list_export_templates(state: State<'_, AppState>) -> Result<Vec<TemplateInfo>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(templates::list(&templates::templates_dir(storage.get_data_file_path())))
+}
+
+// Renders `template` for the scope; written to `path` when one is given, and returned either way
+#[tauri::command]
+pub async fn export_with_template(
+    template: String,
+    scope: ExportScope,
+    path: Option<String>,
+    state: State<'_, AppState>
+) -> Result<String, String> {
+    let output = {
+        let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        let dir = templates::templates_dir(storage.get_data_file_path());
+        templates::render(storage.data(), &template, &scope, &dir)
+            .map_err(|e| format!("Failed to render template: {}", e))?
+    };
+    
+    if let Some(path) = path {
+        std::fs::write(&path, &output)
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+    }
+    
+    Ok(output)
+}
+
+// Webhook Integration Commands
+
+#[tauri::command]
+pub async fn get_webhook_settings(state: State<'_, AppState>) -> Result<WebhookSettings, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.data().settings.webhooks.clone())
+}
+
+#[tauri::command]
+pub async fn update_webhook_settings(
+    settings: WebhookSettings,
+    state: State<'_, AppState>
+) -> Result<WebhookSettings, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    
+    let mut settings = settings;
+    // Blank inputs from the settings form mean "not configured"
+    settings.slack_url = settings.slack_url.filter(|url| !url.trim().is_empty());
+    settings.discord_url = settings.discord_url.filter(|url| !url.trim().is_empty());
+    // Delivery bookkeeping is owned by the backend
+    settings.notified_overdue = data.settings.webhooks.notified_overdue.clone();
+    settings.notified_due_soon = data.settings.webhooks.notified_due_soon.clone();
+    
+    data.settings.webhooks = settings.clone();
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn test_webhook(state: State<'_, AppState>) -> Result<(), String> {
+    let data = {
+        let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?
+    };
+    
+    blocking(move || webhooks::send_test_message(&data)).await?
+        .map_err(|e| format!("Webhook test failed: {}", e))
+}
+
+// Member and Notification Commands
+
+#[tauri::command]
+pub async fn get_members(state: State<'_, AppState>) -> Result<Vec<Member>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.data().members.clone())
+}
+
+#[tauri::command]
+pub async fn add_member(
+    name: String,
+    handle: Option<String>,
+    state: State<'_, AppState>
+) -> Result<Member, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Member name cannot be empty".to_string());
+    }
+    // Default handle: the name in lowercase without spaces, e.g. "Ada Lovelace" -> "adalovelace"
+    let handle = handle
+        .map(|h| h.trim().trim_start_matches('@').to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| name.split_whitespace().collect::<String>().to_lowercase());
+    if mentions::extract_mentions(&format!("@{}", handle)) != vec![handle.clone()] {
+        return Err(format!("Invalid handle @{}: use letters, digits, '.', '_' or '-'", handle));
+    }
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    
+    if data.members.iter().any(|m| m.handle.eq_ignore_ascii_case(&handle)) {
+        return Err(format!("Handle @{} is already taken", handle));
+    }
+    
+    let member = Member {
+        id: data.members.iter().map(|m| m.id).max().unwrap_or(0) + 1,
+        name,
+        handle,
+    };
+    data.members.push(member.clone());
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(member)
+}
+
+#[tauri::command]
+pub async fn remove_member(member_id: u32, state: State<'_, AppState>) -> Result<(), String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    
+    if !data.members.iter().any(|m| m.id == member_id) {
+        return Err(format!("Member with id {} not found", member_id));
+    }
+    data.members.retain(|m| m.id != member_id);
+    data.notifications.retain(|n| n.member_id != member_id);
+    for task in &mut data.tasks {
+        if task.assignee == Some(member_id) {
+            task.assignee = None;
+        }
+        task.unwatch(member_id);
+    }
+    
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))
+}
+
+// `user` is a member handle or name; newest notifications first
+#[tauri::command]
+pub async fn get_my_notifications(
+    user: String,
+    unread_only: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<Vec<Notification>, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.data();
+    
+    let user = user.trim_start_matches('@');
+    let member = data.members.iter()
+        .find(|m| m.matches(user))
+        .ok_or_else(|| format!("No member named {}", user))?;
+    
+    let mut notifications: Vec<Notification> = data.notifications.iter()
+        .filter(|n| n.member_id == member.id && !(unread_only.unwrap_or(false) && n.read))
+        .cloned()
+        .collect();
+    notifications.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    
+    Ok(notifications)
+}
+
+// Marks the given notifications read, or all of the user's when `ids` is omitted
+#[tauri::command]
+pub async fn mark_notifications_read(
+    user: String,
+    ids: Option<Vec<u32>>,
+    state: State<'_, AppState>
+) -> Result<(), String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    
+    let user = user.trim_start_matches('@');
+    let member_id = data.members.iter()
+        .find(|m| m.matches(user))
+        .map(|m| m.id)
+        .ok_or_else(|| format!("No member named {}", user))?;
+    
+    for notification in data.notifications.iter_mut().filter(|n| n.member_id == member_id) {
+        if ids.as_ref().map(|ids| ids.contains(&notification.id)).unwrap_or(true) {
+            notification.read = true;
+        }
+    }
+    
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))
+}
+
+fn find_member_id(data: &RoadmapData, user: &str) -> Result<u32, String> {
+    let user = user.trim_start_matches('@');
+    data.members.iter()
+        .find(|m| m.matches(user))
+        .map(|m| m.id)
+        .ok_or_else(|| format!("No member named {}", user))
+}
+
+// `user` is a member handle or name; None unassigns the task
+#[tauri::command]
+pub async fn set_task_assignee(
+    task_id: u32,
+    user: Option<String>,
+    state: State<'_, AppState>
+) -> Result<Task, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let member_id = user.map(|user| find_member_id(storage.data(), &user)).transpose()?;
+    storage.modify_task(task_id, |task| task.assign(member_id))
+        .map_err(|e| format!("Failed to assign task: {}", e))
+}
+
+#[tauri::command]
+pub async fn watch_task(
+    task_id: u32,
+    user: String,
+    state: State<'_, AppState>
+) -> Result<Task, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let member_id = find_member_id(storage.data(), &user)?;
+    storage.modify_task(task_id, |task| task.watch(member_id))
+        .map_err(|e| format!("Failed to watch task: {}", e))
+}
+
+#[tauri::command]
+pub async fn unwatch_task(
+    task_id: u32,
+    user: String,
+    state: State<'_, AppState>
+) -> Result<Task, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let member_id = find_member_id(storage.data(), &user)?;
+    storage.modify_task(task_id, |task| task.unwatch(member_id))
+        .map_err(|e| format!("Failed to unwatch task: {}", e))
+}
+
+const MY_WORK_COMMENT_DAYS: u32 = 14;
+
+#[derive(serde::Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MyWorkReason {
+    Assigned,
+    Watching,
+    Commented,
+}
+
+#[derive(serde::Serialize)]
+pub struct MyWorkItem {
+    pub task: Task,
+    pub reasons: Vec<MyWorkReason>,
+}
+
+// Open tasks assigned to or watched by `user`, plus any task they commented on in the last
+// `days` days (default 14). Soonest due first; tasks without a due date last.
+#[tauri::command]
+pub async fn get_my_work(
+    user: String,
+    days: Option<u32>,
+    state: State<'_, AppState>
+) -> Result<Vec<MyWorkItem>, String> {
+    use chrono::{DateTime, Duration, Utc};
+    
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let data = storage.data();
+    
+    let member_id = find_member_id(data, &user)?;
+    let member = data.members.iter().find(|m| m.id == member_id)
+        .ok_or_else(|| format!("Member with id {} not found", member_id))?;
+    let since = Utc::now() - Duration::days(days.unwrap_or(MY_WORK_COMMENT_DAYS) as i64);
+    
+    let mut items: Vec<MyWorkItem> = data.tasks.iter().filter_map(|task| {
+        let open = task.status != TaskStatus::Done;
+        let mut reasons = Vec::new();
+        if open && task.assignee == Some(member_id) {
+            reasons.push(MyWorkReason::Assigned);
+        }
+        if open && task.watchers.contains(&member_id) {
+            reasons.push(MyWorkReason::Watching);
+        }
+        let commented = task.comments.iter().any(|c| {
+            member.matches(&c.author) && DateTime::parse_from_rfc3339(&c.created_at)
+                .map(|d| d.with_timezone(&Utc) >= since)
+                .unwrap_or(false)
+        });
+        if commented {
+            reasons.push(MyWorkReason::Commented);
+        }
+        (!reasons.is_empty()).then(|| MyWorkItem { task: task.clone(), reasons })
+    }).collect();
+    items.sort_by(|a, b| {
+        let due = |item: &MyWorkItem| item.task.due_date.as_deref()
+            .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+            .map(|d| d.with_timezone(&Utc));
+        match (due(a), due(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        }
+        .then_with(|| b.task.updated_at.cmp(&a.task.updated_at))
+    });
+    
+    Ok(items)
+}
+
+// Escalation Rule Commands
+
+#[tauri::command]
+pub async fn get_escalation_settings(state: State<'_, AppState>) -> Result<EscalationSettings, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.data().settings.escalation.clone())
+}
+
+#[tauri::command]
+pub async fn update_escalation_settings(
+    settings: EscalationSettings,
+    state: State<'_, AppState>
+) -> Result<EscalationSettings, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    
+    let mut settings = settings;
+    // New rules arrive without an id
+    let first_id = settings.rules.iter().map(|r| r.id).max().unwrap_or(0) + 1;
+    for (rule, id) in settings.rules.iter_mut().filter(|r| r.id == 0).zip(first_id..) {
+        rule.id = id;
+    }
+    // Keep the record of applied rules, minus rules that were removed
+    settings.applied = data.settings.escalation.applied.iter()
+        .filter(|a| settings.rules.iter().any(|r| r.id == a.rule_id))
+        .cloned()
+        .collect();
+    
+    data.settings.escalation = settings.clone();
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(settings)
+}
+
+// Evaluates the rules right away instead of waiting for the next scheduled check
+#[tauri::command]
+pub async fn run_escalation_rules(state: State<'_, AppState>) -> Result<EscalationOutcome, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let outcome = escalation::apply_rules(&mut data, chrono::Utc::now());
+    if outcome.changed {
+        storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    }
+    
+    for message in &outcome.messages {
+        webhooks::dispatch(&data.settings.webhooks, message.clone());
+    }
+    
+    Ok(outcome)
+}
+
+// Development Commands
+
+#[tauri::command]
+pub async fn seed_demo_data(
+    tasks: u32,
+    projects: u32,
+    state: State<'_, AppState>
+) -> Result<SeedResult, String> {
+    if !cfg!(debug_assertions) {
+        return Err("seed_demo_data is only available in development builds".to_string());
+    }
+    
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let result = seed::seed_demo_data(&mut data, tasks, projects);
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(result)
+}
+
+// First-run onboarding
+
+#[tauri::command]
+pub async fn get_onboarding_state(state: State<'_, AppState>) -> Result<OnboardingState, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.data().onboarding.clone())
+}
+
+// Adds the demo project and switches to it; only one sample project exists at a time
+#[tauri::command]
+pub async fn create_sample_workspace(state: State<'_, AppState>) -> Result<SampleWorkspace, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    if let Some(id) = storage.data().onboarding.sample_project_id {
+        if storage.project(id).is_some() {
+            return Err("The sample project already exists".to_string());
+        }
+    }
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    let sample = seed::create_sample_workspace(&mut data);
+    data.current_project_id = Some(sample.project.id);
+    data.onboarding.sample_project_id = Some(sample.project.id);
+    storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    
+    Ok(sample)
+}
+
+#[tauri::command]
+pub async fn complete_onboarding(state: State<'_, AppState>) -> Result<OnboardingState, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let mut data = storage.load_data().map_err(|e| format!("Failed to load data: {}", e))?;
+    if !data.onboarding.completed {
+        data.onboarding.completed = true;
+        data.onboarding.completed_at = Some(chrono::Utc::now().to_rfc3339());
+        storage.save_data(&data).map_err(|e| format!("Failed to save data: {}", e))?;
+    }
+    
+    Ok(data.onboarding)
+}
+
+#[tauri::command]
+pub async fn get_data_location(state: State<'_, AppState>) -> Result<DataLocation, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(location::current(&storage))
+}
+
+// Moves the data file (and its attachments and templates folders) to `new_path`, a file or a folder
+#[tauri::command]
+pub async fn relocate_data(
+    new_path: String,
+    state: State<'_, AppState>,
+    feed_state: State<'_, IcalFeedState>,
+    board_state: State<'_, ShareBoardState>
+) -> Result<RelocationResult, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let result = location::relocate(&mut storage, Path::new(&new_path))
+        .map_err(|e| format!("Failed to move data: {}", e))?;
+    
+    // The servers read the data file directly
+    feed_state.restart(&storage);
+    board_state.restart(&storage);
+    
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn set_portable_mode(
+    enabled: bool,
+    state: State<'_, AppState>,
+    feed_state: State<'_, IcalFeedState>,
+    board_state: State<'_, ShareBoardState>
+) -> Result<RelocationResult, String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    let result = location::set_portable(&mut storage, enabled)
+        .map_err(|e| format!("Failed to switch portable mode: {}", e))?;
+    
+    feed_state.restart(&storage);
+    board_state.restart(&storage);
+    
+    Ok(result)
+}
+
+// Saves are written in the background; this reports whether they are reaching the disk
+#[tauri::command]
+pub async fn get_persistence_status(state: State<'_, AppState>) -> Result<PersistenceStatus, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    
+    Ok(storage.persistence_status())
+}
+
+#[tauri::command]
+pub async fn get_storage_format(state: State<'_, AppState>) -> Result<StorageFormat, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    Ok(storage.get_format())
+}
+
+#[tauri::command]
+pub async fn convert_storage_format(format: StorageFormat, state: State<'_, AppState>) -> Result<(), String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    storage.convert_format(format).map_err(|e| format!("Failed to convert data file: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_read_only(state: State<'_, AppState>) -> Result<bool, String> {
+    let storage = state.0.read().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    Ok(storage.is_read_only())
+}
+
+// While read-only, every command that changes data fails with "Permission denied"
+#[tauri::command]
+pub async fn set_read_only(read_only: bool, state: State<'_, AppState>) -> Result<(), String> {
+    let mut storage = state.0.write().map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    storage.set_read_only(read_only).map_err(|e| format!("Failed to leave read-only mode: {}", e))
+}
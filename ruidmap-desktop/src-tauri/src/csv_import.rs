@@ -0,0 +1,278 @@
+use crate::models::{Project, RoadmapData, Task, TaskPriority};
+use crate::palette;
+use crate::status_mapping;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+// Two-step import of tasks from a CSV exported by some other tracker: `inspect` shows the
+// columns so the user can map them, `import` applies the mapping row by row.
+
+const SAMPLE_ROWS: usize = 5;
+
+#[derive(Debug, Serialize)]
+pub struct CsvPreview {
+    pub headers: Vec<String>,
+    pub sample_rows: Vec<Vec<String>>,
+    pub total_rows: usize,
+    pub delimiter: char,
+    pub suggested_mapping: CsvMapping,
+}
+
+// Each field names the CSV column (by header) it is read from; None leaves the field at its default
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CsvMapping {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    pub due_date: Option<String>,
+    pub tags: Option<String>,
+    pub estimated_time: Option<String>, // Minutes, or with a unit like "90m", "2h", "1.5h"
+    pub project: Option<String>, // Matched to projects by name; unknown names create a project
+    pub project_id: Option<u32>, // Target for rows without a project column, defaults to the current project
+}
+
+#[derive(Debug, Serialize)]
+pub struct CsvImportResult {
+    pub imported_tasks: usize,
+    pub created_projects: usize,
+    pub errors: Vec<CsvRowError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CsvRowError {
+    pub row: usize, // Line in the file, counting the header as line 1
+    pub message: String,
+}
+
+pub fn inspect(path: &Path) -> Result<CsvPreview> {
+    let (content, delimiter) = read(path)?;
+    let mut reader = reader(&content, delimiter);
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+
+    let mut sample_rows = Vec::new();
+    let mut total_rows = 0;
+    for record in reader.records() {
+        let record = record?;
+        if sample_rows.len() < SAMPLE_ROWS {
+            sample_rows.push(record.iter().map(str::to_string).collect());
+        }
+        total_rows += 1;
+    }
+
+    Ok(CsvPreview {
+        suggested_mapping: suggest_mapping(&headers),
+        headers,
+        sample_rows,
+        total_rows,
+        delimiter: delimiter as char,
+    })
+}
+
+// Rows that fail are reported and skipped; the rest are added to `data`
+pub fn import(data: &mut RoadmapData, path: &Path, mapping: &CsvMapping) -> Result<CsvImportResult> {
+    let (content, delimiter) = read(path)?;
+    let mut reader = reader(&content, delimiter);
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+
+    let column = |name: &Option<String>| -> Result<Option<usize>> {
+        name.as_deref()
+            .map(|name| headers.iter().position(|h| h == name).ok_or_else(|| anyhow!("Column \"{}\" not found", name)))
+            .transpose()
+    };
+    let title = column(&mapping.title)?
+        .ok_or_else(|| anyhow!("Choose the column that holds the task title"))?;
+    let description = column(&mapping.description)?;
+    let status = column(&mapping.status)?;
+    let priority = column(&mapping.priority)?;
+    let due_date = column(&mapping.due_date)?;
+    let tags = column(&mapping.tags)?;
+    let estimated_time = column(&mapping.estimated_time)?;
+    let project = column(&mapping.project)?;
+
+    let default_project_id = mapping.project_id.or(data.current_project_id).unwrap_or(1);
+    if !data.projects.iter().any(|p| p.id == default_project_id) {
+        return Err(anyhow!("Project with id {} not found", default_project_id));
+    }
+
+    let mut result = CsvImportResult { imported_tasks: 0, created_projects: 0, errors: Vec::new() };
+    let mut next_task_id = data.tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+
+    for (record, row) in reader.records().zip(2..) {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                result.errors.push(CsvRowError { row, message: e.to_string() });
+                continue;
+            }
+        };
+        // Quoted cells can span lines, so prefer the reader's own line count
+        let row = record.position().map(|p| p.line() as usize).unwrap_or(row);
+        let cell = |index: Option<usize>| index.and_then(|i| record.get(i)).map(str::trim).unwrap_or("");
+
+        let title = cell(Some(title));
+        if title.is_empty() {
+            result.errors.push(CsvRowError { row, message: "Missing title".to_string() });
+            continue;
+        }
+
+        let parsed = (|| -> Result<Task> {
+            let mut task = Task::new(next_task_id, default_project_id, title.to_string(), cell(description).to_string());
+            if !cell(status).is_empty() {
+                // Read with the mapping of the project the row lands in; a new project has none yet
+                let project_name = cell(project);
+                let target = data.projects.iter().find(|p| match project_name {
+                    "" => p.id == default_project_id,
+                    name => p.name.eq_ignore_ascii_case(name),
+                });
+                let mapping = target.map(|p| &p.settings.status_mapping);
+                task.status = status_mapping::status(cell(status), mapping.into_iter().flatten())
+                    .ok_or_else(|| anyhow!("Unknown status \"{}\"", cell(status)))?;
+            }
+            if !cell(priority).is_empty() {
+                task.priority = parse_priority(cell(priority))?;
+            }
+            if !cell(due_date).is_empty() {
+                task.due_date = Some(parse_due_date(cell(due_date))?);
+            }
+            if !cell(estimated_time).is_empty() {
+                task.estimated_time = Some(parse_minutes(cell(estimated_time))?);
+            }
+            for tag in cell(tags).split([',', ';', '|']).map(str::trim).filter(|t| !t.is_empty()) {
+                task.add_tag(tag.trim_start_matches('#').to_string());
+            }
+            Ok(task)
+        })();
+
+        let mut task = match parsed {
+            Ok(task) => task,
+            Err(e) => {
+                result.errors.push(CsvRowError { row, message: e.to_string() });
+                continue;
+            }
+        };
+
+        let project_name = cell(project);
+        if !project_name.is_empty() {
+            task.project_id = match data.projects.iter().find(|p| p.name.eq_ignore_ascii_case(project_name)) {
+                Some(existing) => existing.id,
+                None => {
+                    let id = data.projects.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+                    let color = palette::next_color(&data.projects);
+                    data.projects.push(Project::new_with_details(
+                        id,
+                        project_name.to_string(),
+                        String::new(),
+                        Some(color),
+                        Some(palette::DEFAULT_ICON.to_string()),
+                    ));
+                    result.created_projects += 1;
+                    id
+                }
+            };
+        }
+
+        data.tasks.push(task);
+        next_task_id += 1;
+        result.imported_tasks += 1;
+    }
+
+    Ok(result)
+}
+
+fn read(path: &Path) -> Result<(String, u8)> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let content = content.trim_start_matches('\u{feff}').to_string();
+    let delimiter = sniff_delimiter(&content);
+    Ok((content, delimiter))
+}
+
+pub fn reader(content: &str, delimiter: u8) -> csv::Reader<&[u8]> {
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(content.as_bytes())
+}
+
+// Whichever of , ; or tab occurs most often in the header line
+pub fn sniff_delimiter(content: &str) -> u8 {
+    let header = content.lines().next().unwrap_or("");
+    [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|d| (header.matches(*d as char).count(), *d == b','))
+        .unwrap_or(b',')
+}
+
+fn suggest_mapping(headers: &[String]) -> CsvMapping {
+    let find = |names: &[&str]| headers.iter()
+        .find(|h| names.iter().any(|n| h.eq_ignore_ascii_case(n)))
+        .cloned();
+
+    CsvMapping {
+        title: find(&["title", "name", "summary", "task", "subject"]).or_else(|| headers.first().cloned()),
+        description: find(&["description", "details", "body", "notes", "content"]),
+        status: find(&["status", "state", "column", "stage"]),
+        priority: find(&["priority", "importance", "severity"]),
+        due_date: find(&["due", "due date", "due_date", "deadline", "due on"]),
+        tags: find(&["tags", "labels", "label", "tag"]),
+        estimated_time: find(&["estimate", "estimated time", "estimated_time", "original estimate", "time estimate"]),
+        project: find(&["project", "list", "board"]),
+        project_id: None,
+    }
+}
+
+
+pub fn parse_priority(value: &str) -> Result<TaskPriority> {
+    match value.to_lowercase().as_str() {
+        "low" | "minor" | "trivial" | "p3" | "p4" | "3" | "4" => Ok(TaskPriority::Low),
+        "medium" | "normal" | "moderate" | "p2" | "2" => Ok(TaskPriority::Medium),
+        "high" | "major" | "urgent" | "critical" | "highest" | "blocker" | "p0" | "p1" | "0" | "1" => Ok(TaskPriority::High),
+        _ => Err(anyhow!("Unknown priority \"{}\"", value)),
+    }
+}
+
+// RFC 3339 timestamps are kept as they are; plain dates mean the end of that local day
+pub fn parse_due_date(value: &str) -> Result<String> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.to_rfc3339());
+    }
+
+    let local = |naive: NaiveDateTime| Local.from_local_datetime(&naive).earliest()
+        .map(|d| d.to_rfc3339())
+        .ok_or_else(|| anyhow!("Due date \"{}\" does not exist in the local time zone", value));
+
+    for format in ["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(value, format) {
+            return local(naive);
+        }
+    }
+    for format in ["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(value, format) {
+            return local(date.and_time(NaiveTime::from_hms_opt(23, 59, 0).unwrap_or_default()));
+        }
+    }
+    Err(anyhow!("Unrecognized due date \"{}\", use YYYY-MM-DD", value))
+}
+
+fn parse_minutes(value: &str) -> Result<u32> {
+    let value = value.to_lowercase().replace(' ', "");
+    let invalid = || anyhow!("Unrecognized estimate \"{}\"", value);
+
+    let (number, factor) = if let Some(hours) = value.strip_suffix('h') {
+        (hours, 60.0)
+    } else if let Some(minutes) = value.strip_suffix('m') {
+        (minutes, 1.0)
+    } else {
+        (value.as_str(), 1.0)
+    };
+    let amount: f64 = number.parse().map_err(|_| invalid())?;
+    if !amount.is_finite() || amount < 0.0 {
+        return Err(invalid());
+    }
+    Ok((amount * factor).round() as u32)
+}
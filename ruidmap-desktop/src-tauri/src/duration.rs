@@ -0,0 +1,90 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+// Human-friendly durations for estimates and logged time: "2h 30m", "1.5h", "1d", "90" or
+// "1:30". A day is a working day of 8 hours and a week has 5 of them; a bare number is minutes.
+
+const MINUTES_PER_HOUR: f64 = 60.0;
+const MINUTES_PER_DAY: f64 = 8.0 * MINUTES_PER_HOUR;
+const MINUTES_PER_WEEK: f64 = 5.0 * MINUTES_PER_DAY;
+
+// Commands take either plain minutes or text to parse
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum DurationInput {
+    Minutes(u32),
+    Text(String),
+}
+
+impl DurationInput {
+    pub fn minutes(&self) -> Result<u32> {
+        match self {
+            DurationInput::Minutes(minutes) => Ok(*minutes),
+            DurationInput::Text(text) => parse_minutes(text),
+        }
+    }
+
+    pub fn is_blank(&self) -> bool {
+        matches!(self, DurationInput::Text(text) if text.trim().is_empty())
+    }
+}
+
+pub fn parse_minutes(text: &str) -> Result<u32> {
+    let input = text.trim().to_lowercase();
+    if input.is_empty() {
+        return Err(anyhow!("Enter a duration such as \"2h 30m\" or \"1d\""));
+    }
+
+    // "1:30" is hours and minutes
+    if let Some((hours, minutes)) = input.split_once(':') {
+        let hours: u32 = hours.trim().parse().map_err(|_| invalid(text))?;
+        let minutes: u32 = minutes.trim().parse().map_err(|_| invalid(text))?;
+        if minutes >= 60 {
+            return Err(invalid(text));
+        }
+        return to_minutes(hours as f64 * MINUTES_PER_HOUR + minutes as f64, text);
+    }
+
+    let mut total = 0.0;
+    let mut rest = input.as_str();
+    let mut parts = 0;
+    while !rest.is_empty() {
+        let number_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ',')).unwrap_or(rest.len());
+        if number_len == 0 {
+            return Err(invalid(text));
+        }
+        let number: f64 = rest[..number_len].replace(',', ".").parse().map_err(|_| invalid(text))?;
+        rest = rest[number_len..].trim_start();
+
+        let unit_len = rest.find(|c: char| !c.is_alphabetic()).unwrap_or(rest.len());
+        let unit = &rest[..unit_len];
+        rest = rest[unit_len..].trim_start_matches([' ', ',']);
+
+        let factor = match unit {
+            // Only a lone number may leave out the unit
+            "" if parts == 0 && rest.is_empty() => 1.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => 1.0,
+            "h" | "hr" | "hrs" | "hour" | "hours" => MINUTES_PER_HOUR,
+            "d" | "day" | "days" => MINUTES_PER_DAY,
+            "w" | "wk" | "wks" | "week" | "weeks" => MINUTES_PER_WEEK,
+            "" => return Err(anyhow!("Missing unit in \"{}\": use m, h, d or w", text.trim())),
+            _ => return Err(anyhow!("Unknown unit \"{}\" in \"{}\": use m, h, d or w", unit, text.trim())),
+        };
+        total += number * factor;
+        parts += 1;
+    }
+
+    to_minutes(total, text)
+}
+
+fn to_minutes(total: f64, text: &str) -> Result<u32> {
+    let minutes = total.round();
+    if minutes > u32::MAX as f64 {
+        return Err(anyhow!("\"{}\" is too long", text.trim()));
+    }
+    Ok(minutes as u32)
+}
+
+fn invalid(text: &str) -> anyhow::Error {
+    anyhow!("Could not read \"{}\" as a duration, try \"2h 30m\" or \"1d\"", text.trim())
+}
@@ -0,0 +1,294 @@
+use crate::commands::AppState;
+use crate::imap::{self, ImapSession};
+use crate::inbox::{self, InboxItem, InboxTags};
+use crate::location;
+use crate::mime::{self, Email};
+use crate::models::{Attachment, AttachmentKind, EmailInboxSettings, RoadmapData};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+// Turns emails in an IMAP folder into tasks: the subject becomes the title, the text the
+// description and attachments are saved next to the data file. Messages are flagged as seen once
+// imported. The first poll takes the folder's unread messages; later polls take whatever arrived
+// since, read or not.
+
+const POLL_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const MAX_MESSAGES_PER_POLL: usize = 50;
+const MAX_ATTACHMENT_BYTES: usize = 25 * 1024 * 1024;
+const EMAIL_TAG: &str = "email";
+
+// Keeps the scheduled poll and check_email_inbox_now from importing the same messages twice
+static POLLING: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct EmailPollResult {
+    pub imported_tasks: usize,
+    pub attachments: usize,
+    pub skipped: Vec<String>, // Attachments left out, with the reason
+}
+
+// A message read from the server, waiting to be added. `email` is None for a message too large
+// to read; `attachments` are already saved.
+struct Fetched {
+    uid: u32,
+    email: Option<Email>,
+    attachments: Vec<Attachment>,
+    attachments_dir: Option<PathBuf>,
+}
+
+pub fn validate(settings: &EmailInboxSettings) -> Result<()> {
+    if settings.poll_minutes == 0 {
+        return Err(anyhow!("The poll interval must be at least one minute"));
+    }
+    if settings.enabled {
+        if settings.host.trim().is_empty() || settings.username.trim().is_empty() {
+            return Err(anyhow!("Set the IMAP server and user name"));
+        }
+        if settings.folder.trim().is_empty() {
+            return Err(anyhow!("Choose the folder to read tasks from"));
+        }
+    }
+    Ok(())
+}
+
+pub fn spawn_email_poller(app: AppHandle) {
+    thread::spawn(move || loop {
+        if let Err(e) = check(&app) {
+            eprintln!("Email inbox check failed: {}", e);
+        }
+        thread::sleep(POLL_CHECK_INTERVAL);
+    });
+}
+
+// Polls when enabled and due; tells the frontend through email-tasks-imported or email-inbox-failed
+fn check(app: &AppHandle) -> Result<()> {
+    let state = app.state::<AppState>();
+    let settings = {
+        let storage = state.0.read().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+        if storage.is_read_only() {
+            return Ok(());
+        }
+        storage.data().settings.email_inbox.clone()
+    };
+    if !settings.enabled || !is_due(&settings, Utc::now()) {
+        return Ok(());
+    }
+
+    match poll(app) {
+        Ok(result) if result.imported_tasks > 0 => {
+            app.emit("email-tasks-imported", result).map_err(|e| anyhow!("Failed to send the event: {}", e))
+        }
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let _ = app.emit("email-inbox-failed", e.to_string());
+            Err(e)
+        }
+    }
+}
+
+fn is_due(settings: &EmailInboxSettings, now: DateTime<Utc>) -> bool {
+    let last = settings.last_checked_at.as_deref().and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+    match last {
+        Some(last) => now - last.with_timezone(&Utc) >= ChronoDuration::minutes(settings.poll_minutes as i64),
+        None => true,
+    }
+}
+
+// Reads new messages, adds them as tasks and flags them as seen. The storage lock is only held
+// while the tasks are added, not during network traffic or while attachments are written.
+pub fn poll(app: &AppHandle) -> Result<EmailPollResult> {
+    let _polling = POLLING.lock().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    let state = app.state::<AppState>();
+    let (settings, attachments_dir) = {
+        let storage = state.0.read().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+        // Checked up front so no message is fetched that couldn't be added
+        storage.check_writable()?;
+        // Absolute, so moving the data file can tell which attachments to update
+        let data_file_path = location::absolute(storage.get_data_file_path())?;
+        let attachments_dir = data_file_path.parent().unwrap_or(Path::new(".")).join("attachments");
+        (storage.data().settings.email_inbox.clone(), attachments_dir)
+    };
+
+    let mut session = None;
+    let fetched = connect(&settings).and_then(|mut connected| {
+        let fetched = fetch(&mut connected, &settings);
+        session = Some(connected);
+        fetched
+    });
+
+    let mut result = EmailPollResult::default();
+    let fetched = fetched.and_then(|(uid_validity, last_uid, mut messages)| {
+        for message in &mut messages {
+            save_attachments(message, uid_validity, &attachments_dir, &mut result)?;
+        }
+        Ok((uid_validity, last_uid, messages))
+    });
+
+    let mut storage = state.0.write().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    let mut data = storage.load_data()?;
+    let email_settings = &mut data.settings.email_inbox;
+    email_settings.last_checked_at = Some(Utc::now().to_rfc3339());
+    let (uid_validity, last_uid, messages) = match fetched {
+        Ok(fetched) => fetched,
+        Err(e) => {
+            email_settings.last_error = Some(e.to_string());
+            storage.save_data(&data)?;
+            return Err(e);
+        }
+    };
+    email_settings.last_error = None;
+    email_settings.uid_validity = uid_validity;
+    email_settings.last_uid = last_uid;
+
+    let project_id = settings.project_id;
+    let mut imported_uids = Vec::new();
+    let mut unused_dirs = Vec::new();
+    for message in messages {
+        let uid = message.uid;
+        match message.email {
+            Some(email) => {
+                if !add_email(&mut data, project_id, email, message.attachments, &mut result)? {
+                    unused_dirs.extend(message.attachments_dir);
+                }
+                imported_uids.push(uid);
+            }
+            None => result.skipped.push(format!("Message {}: larger than {} MB", uid, imap::MAX_LITERAL_BYTES / 1024 / 1024)),
+        }
+    }
+    storage.save_data(&data)?;
+    drop(storage);
+
+    // Attachments of messages that were already imported
+    for dir in unused_dirs {
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    if let Some(mut session) = session {
+        // Already imported; the UID bookmark keeps them from coming back if this fails
+        if let Err(e) = session.mark_seen(&imported_uids) {
+            eprintln!("Failed to flag imported emails as seen: {}", e);
+        }
+        session.logout();
+    }
+    Ok(result)
+}
+
+fn connect(settings: &EmailInboxSettings) -> Result<ImapSession> {
+    let password = settings.password.as_deref().ok_or_else(|| anyhow!("No password set"))?;
+    let mut session = ImapSession::connect(settings.host.trim(), settings.port)?;
+    session.login(settings.username.trim(), password)?;
+    Ok(session)
+}
+
+// The folder's UIDVALIDITY, the bookmark to store and the messages to add
+fn fetch(session: &mut ImapSession, settings: &EmailInboxSettings) -> Result<(Option<u32>, Option<u32>, Vec<Fetched>)> {
+    let mailbox = session.select(settings.folder.trim())?;
+    // A bookmark only holds while the folder keeps its numbering
+    let bookmark = settings.last_uid.filter(|_| settings.uid_validity.is_some() && settings.uid_validity == mailbox.uid_validity);
+
+    let mut uids = match bookmark {
+        Some(last_uid) => session.search(&format!("UID {}:*", last_uid.saturating_add(1)))?
+            .into_iter()
+            // "n:*" also matches the newest message when nothing is newer than n
+            .filter(|uid| *uid > last_uid)
+            .collect(),
+        None => session.search("UNSEEN")?,
+    };
+    let complete = uids.len() <= MAX_MESSAGES_PER_POLL;
+    uids.truncate(MAX_MESSAGES_PER_POLL);
+
+    let mut messages = Vec::new();
+    for uid in &uids {
+        let email = session.fetch(*uid)?.map(|raw| mime::parse(&raw));
+        messages.push(Fetched { uid: *uid, email, attachments: Vec::new(), attachments_dir: None });
+    }
+
+    // Once the unread backlog is done, continue from the folder's end; otherwise unread is still
+    // the way to find the rest
+    let last_uid = match (bookmark, uids.last()) {
+        (Some(_), Some(uid)) => Some(*uid),
+        (Some(bookmark), None) => Some(bookmark),
+        (None, _) if complete => mailbox.uid_next.map(|next| next.saturating_sub(1)).or(uids.last().copied()),
+        (None, _) => None,
+    };
+    Ok((mailbox.uid_validity, last_uid, messages))
+}
+
+// Writes the message's attachments to their own folder, named after the message since its task
+// doesn't exist yet
+fn save_attachments(message: &mut Fetched, uid_validity: Option<u32>, attachments_dir: &Path, result: &mut EmailPollResult) -> Result<()> {
+    let Some(email) = &mut message.email else {
+        return Ok(());
+    };
+    let dir = attachments_dir.join(format!("email-{}-{}", uid_validity.unwrap_or(0), message.uid));
+    for attachment in std::mem::take(&mut email.attachments) {
+        if attachment.content.len() > MAX_ATTACHMENT_BYTES {
+            result.skipped.push(format!("{} on \"{}\": larger than {} MB", attachment.filename, email.subject, MAX_ATTACHMENT_BYTES / 1024 / 1024));
+            continue;
+        }
+        fs::create_dir_all(&dir)?;
+        message.attachments_dir = Some(dir.clone());
+        let filename = safe_filename(&attachment.filename);
+        let mut path = dir.join(&filename);
+        let mut n = 1;
+        while path.exists() {
+            n += 1;
+            path = dir.join(format!("{}-{}", n, filename));
+        }
+        fs::write(&path, &attachment.content)?;
+
+        message.attachments.push(Attachment {
+            id: message.attachments.len() as u32 + 1,
+            filename: attachment.filename,
+            file_path: path.display().to_string(),
+            file_size: attachment.content.len() as u64,
+            mime_type: attachment.mime_type,
+            created_at: Utc::now().to_rfc3339(),
+            kind: AttachmentKind::File,
+            url: None,
+        });
+    }
+    Ok(())
+}
+
+// Returns false when the email was already imported
+fn add_email(data: &mut RoadmapData, project_id: Option<u32>, email: Email, attachments: Vec<Attachment>, result: &mut EmailPollResult) -> Result<bool> {
+    let external_id = email.message_id.as_ref().map(|id| format!("email:{}", id));
+    if external_id.is_some() && data.tasks.iter().any(|t| t.external_id == external_id) {
+        return Ok(false);
+    }
+
+    let title = if email.subject.is_empty() { "(no subject)".to_string() } else { email.subject };
+    let item = InboxItem {
+        title,
+        notes: email.text,
+        tags: InboxTags::List(vec![EMAIL_TAG.to_string()]),
+    };
+    let task = inbox::add_item(data, project_id, item)?;
+    let task = data.tasks.iter_mut().find(|t| t.id == task.id).ok_or_else(|| anyhow!("Failed to add task"))?;
+    task.external_id = external_id;
+    if !email.from.is_empty() {
+        task.custom_fields.insert("From".to_string(), email.from);
+    }
+    result.attachments += attachments.len();
+    task.attachments = attachments;
+
+    result.imported_tasks += 1;
+    Ok(true)
+}
+
+// Keeps attachment names from escaping the folder or tripping up the file system
+fn safe_filename(name: &str) -> String {
+    let name: String = name.chars()
+        .map(|c| if c.is_control() || "/\\:*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+    let name = name.trim().trim_start_matches('.');
+    if name.is_empty() { "attachment".to_string() } else { name.to_string() }
+}
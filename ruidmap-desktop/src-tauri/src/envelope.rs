@@ -0,0 +1,160 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroU32;
+
+// Passphrase-encrypted envelope for data kept on hosts that shouldn't read it: remote backups,
+// and the change batches synced through the same folder (see sync.rs). The passphrase is
+// stretched with PBKDF2-HMAC-SHA256 and the content sealed with AES-256-GCM. Everything but the
+// ciphertext is readable without the passphrase, and it is authenticated along with the content,
+// so an edited header fails to open instead of decrypting with weaker parameters. Later versions can change the KDF or cipher; `version` says which.
+
+const FORMAT: &str = "ruidmap-encrypted";
+const VERSION: u32 = 1;
+const KDF: &str = "pbkdf2-sha256";
+const CIPHER: &str = "aes-256-gcm";
+const ITERATIONS: u32 = 600_000;
+// Envelopes asking for more would take minutes to open
+const MAX_ITERATIONS: u32 = 10_000_000;
+const SALT_LEN: usize = 16;
+const BASE64: base64::engine::GeneralPurpose = base64::engine::general_purpose::STANDARD;
+
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    format: String,
+    version: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String, // Base64
+    cipher: String,
+    nonce: String, // Base64
+    ciphertext: String, // Base64, with the authentication tag at the end
+}
+
+impl Envelope {
+    fn header(&self) -> String {
+        format!("{}:{}:{}:{}:{}:{}:{}", self.format, self.version, self.kdf, self.iterations, self.salt, self.cipher, self.nonce)
+    }
+}
+
+#[derive(Deserialize)]
+struct Format {
+    format: Option<String>,
+}
+
+pub fn seal(content: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    if passphrase.is_empty() {
+        return Err(anyhow!("The passphrase is empty"));
+    }
+    let random = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    random.fill(&mut salt).map_err(|_| anyhow!("Failed to generate a salt"))?;
+    random.fill(&mut nonce).map_err(|_| anyhow!("Failed to generate a nonce"))?;
+
+    let mut envelope = Envelope {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        kdf: KDF.to_string(),
+        iterations: ITERATIONS,
+        salt: BASE64.encode(salt),
+        cipher: CIPHER.to_string(),
+        nonce: BASE64.encode(nonce),
+        ciphertext: String::new(),
+    };
+    let key = key(passphrase, &salt, ITERATIONS)?;
+    let mut sealed = content.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(envelope.header()), &mut sealed)
+        .map_err(|_| anyhow!("Failed to encrypt"))?;
+    envelope.ciphertext = BASE64.encode(sealed);
+    Ok(serde_json::to_vec(&envelope)?)
+}
+
+pub fn open(bytes: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let envelope: Envelope = serde_json::from_slice(bytes)
+        .map_err(|e| anyhow!("Not an encrypted RuidMap file: {}", e))?;
+    if envelope.format != FORMAT {
+        return Err(anyhow!("Not an encrypted RuidMap file"));
+    }
+    if envelope.version > VERSION {
+        return Err(anyhow!("The file was encrypted by a newer version of RuidMap (format version {})", envelope.version));
+    }
+    if envelope.kdf != KDF || envelope.cipher != CIPHER {
+        return Err(anyhow!("Unsupported encryption \"{}\" with \"{}\"", envelope.cipher, envelope.kdf));
+    }
+    if envelope.iterations > MAX_ITERATIONS {
+        return Err(anyhow!("The file asks for {} key derivation rounds, more than the {} allowed", envelope.iterations, MAX_ITERATIONS));
+    }
+
+    let salt = BASE64.decode(&envelope.salt).map_err(|e| anyhow!("Invalid salt: {}", e))?;
+    let nonce = BASE64.decode(&envelope.nonce).map_err(|e| anyhow!("Invalid nonce: {}", e))?;
+    let nonce = Nonce::try_assume_unique_for_key(&nonce).map_err(|_| anyhow!("Invalid nonce length"))?;
+    let mut content = BASE64.decode(&envelope.ciphertext).map_err(|e| anyhow!("Invalid ciphertext: {}", e))?;
+
+    let key = key(passphrase, &salt, envelope.iterations)?;
+    let opened = key.open_in_place(nonce, Aad::from(envelope.header()), &mut content)
+        .map_err(|_| anyhow!("Wrong passphrase, or the file was modified after it was encrypted"))?;
+    Ok(opened.to_vec())
+}
+
+// Whether `bytes` is an envelope, as opposed to plain JSON or MessagePack data
+pub fn is_sealed(bytes: &[u8]) -> bool {
+    serde_json::from_slice::<Format>(bytes)
+        .map(|f| f.format.as_deref() == Some(FORMAT))
+        .unwrap_or(false)
+}
+
+fn key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations).ok_or_else(|| anyhow!("Invalid key derivation rounds"))?;
+    let mut key = [0u8; 32];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    let key = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| anyhow!("Invalid key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tampered(sealed: &[u8], edit: impl FnOnce(&mut Envelope)) -> Vec<u8> {
+        let mut envelope: Envelope = serde_json::from_slice(sealed).unwrap();
+        edit(&mut envelope);
+        serde_json::to_vec(&envelope).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let sealed = seal(b"{\"tasks\":[]}", "correct horse").unwrap();
+        assert!(is_sealed(&sealed));
+        assert!(!is_sealed(b"{\"tasks\":[]}"));
+        assert_eq!(open(&sealed, "correct horse").unwrap(), b"{\"tasks\":[]}");
+    }
+
+    #[test]
+    fn rejects_wrong_passphrase_and_tampering() {
+        let sealed = seal(b"secret", "correct horse").unwrap();
+        assert!(open(&sealed, "wrong horse").is_err());
+
+        let flipped = tampered(&sealed, |e| {
+            let mut ciphertext = BASE64.decode(&e.ciphertext).unwrap();
+            ciphertext[0] ^= 1;
+            e.ciphertext = BASE64.encode(ciphertext);
+        });
+        assert!(open(&flipped, "correct horse").is_err());
+
+        // The header is authenticated, so weakening the key derivation doesn't open the content
+        let weakened = tampered(&sealed, |e| e.iterations = 1);
+        assert!(open(&weakened, "correct horse").is_err());
+
+        let newer = tampered(&sealed, |e| e.version = VERSION + 1);
+        assert!(open(&newer, "correct horse").unwrap_err().to_string().contains("newer version"));
+    }
+
+    #[test]
+    fn rejects_empty_passphrase() {
+        assert!(seal(b"secret", "").is_err());
+    }
+}
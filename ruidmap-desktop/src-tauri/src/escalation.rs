@@ -0,0 +1,111 @@
+use crate::commands::AppState;
+use crate::models::{AppliedEscalation, EscalationAction, RoadmapData, TaskPriority, TaskStatus};
+use crate::webhooks;
+use crate::workdays;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+// Rules that act on tasks once they have been overdue for a while
+
+const ESCALATION_CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+#[derive(serde::Serialize, Default)]
+pub struct EscalationOutcome {
+    pub escalated_tasks: Vec<u32>,
+    pub messages: Vec<String>,
+    pub changed: bool,
+}
+
+pub fn spawn_escalation_watcher(app: AppHandle) {
+    thread::spawn(move || loop {
+        if let Err(e) = run(&app) {
+            eprintln!("Escalation check failed: {}", e);
+        }
+        thread::sleep(ESCALATION_CHECK_INTERVAL);
+    });
+}
+
+fn run(app: &AppHandle) -> Result<()> {
+    let state = app.state::<AppState>();
+    let mut storage = state.0.write().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    if !storage.data().settings.escalation.enabled || storage.is_read_only() {
+        return Ok(());
+    }
+
+    let mut data = storage.load_data()?;
+    let outcome = apply_rules(&mut data, Utc::now());
+    if outcome.changed {
+        storage.save_data(&data)?;
+    }
+
+    for message in outcome.messages {
+        webhooks::dispatch(&data.settings.webhooks, message);
+    }
+    Ok(())
+}
+
+// Applies every rule that hasn't fired for a task yet. Notify messages are returned rather than sent.
+pub fn apply_rules(data: &mut RoadmapData, now: DateTime<Utc>) -> EscalationOutcome {
+    let mut outcome = EscalationOutcome::default();
+    let rules = data.settings.escalation.rules.clone();
+
+    // Weekends and holidays don't count towards how long a task has been overdue
+    let working_days = &data.settings.working_days;
+    let overdue: Vec<(u32, ChronoDuration)> = data.tasks.iter()
+        .filter(|t| t.status != TaskStatus::Done)
+        .filter_map(|t| {
+            let due = DateTime::parse_from_rfc3339(t.due_date.as_deref()?).ok()?.with_timezone(&Utc);
+            Some((t.id, workdays::overdue_by(due, now, working_days)?))
+        })
+        .collect();
+
+    // Forget tasks that were completed or rescheduled so the rules can fire again later
+    let applied = &mut data.settings.escalation.applied;
+    let before = applied.len();
+    applied.retain(|a| overdue.iter().any(|(id, _)| *id == a.task_id));
+    outcome.changed = applied.len() != before;
+
+    for (task_id, overdue_by) in overdue {
+        for rule in &rules {
+            let overdue_long_enough = overdue_by > ChronoDuration::days(rule.overdue_days as i64);
+            let already_applied = data.settings.escalation.applied.iter()
+                .any(|a| a.rule_id == rule.id && a.task_id == task_id);
+            if !overdue_long_enough || already_applied {
+                continue;
+            }
+
+            let Some(task) = data.tasks.iter_mut().find(|t| t.id == task_id) else {
+                continue;
+            };
+            match &rule.action {
+                EscalationAction::BumpPriority => {
+                    let bumped = match task.priority {
+                        TaskPriority::Low => TaskPriority::Medium,
+                        _ => TaskPriority::High,
+                    };
+                    task.update_priority(bumped);
+                }
+                EscalationAction::AddTag { tag } => task.add_tag(tag.clone()),
+                EscalationAction::Notify { message } => {
+                    let project = data.projects.iter().find(|p| p.id == task.project_id);
+                    let muted = project.map(|p| p.settings.notifications.muted).unwrap_or(false);
+                    if !muted {
+                        let message = message.replace("{days_overdue}", &overdue_by.num_days().to_string());
+                        outcome.messages.push(webhooks::render(&message, Some(task), project, &data.settings.locale));
+                    }
+                }
+            }
+
+            data.settings.escalation.applied.push(AppliedEscalation { rule_id: rule.id, task_id });
+            if !outcome.escalated_tasks.contains(&task_id) {
+                outcome.escalated_tasks.push(task_id);
+            }
+            outcome.changed = true;
+        }
+    }
+
+    outcome
+}
@@ -0,0 +1,287 @@
+use crate::models::{RoadmapData, Task, TaskPriority, TaskStatus};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Dependency graph export (GraphViz DOT, optionally rendered to SVG)
+
+pub fn dependency_graph_dot(data: &RoadmapData, project_id: u32) -> Result<String> {
+    let project = data.projects.iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| anyhow!("Project with id {} not found", project_id))?;
+
+    let tasks_by_id: HashMap<u32, &Task> = data.tasks.iter().map(|t| (t.id, t)).collect();
+    let project_tasks: Vec<&Task> = data.tasks.iter()
+        .filter(|t| t.project_id == project_id)
+        .collect();
+
+    // Prerequisites living in other projects are drawn as dashed nodes
+    let mut external: Vec<&Task> = Vec::new();
+    for task in &project_tasks {
+        for dep_id in &task.dependencies {
+            if let Some(dep) = tasks_by_id.get(dep_id) {
+                if dep.project_id != project_id && !external.iter().any(|t| t.id == dep.id) {
+                    external.push(dep);
+                }
+            }
+        }
+    }
+
+    let graph_tasks: Vec<&Task> = project_tasks.iter().chain(external.iter()).copied().collect();
+    let critical_path = critical_path(&graph_tasks);
+    let critical_edges: HashSet<(u32, u32)> = critical_path.windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+
+    let mut dot = String::new();
+    dot.push_str(&format!("digraph \"{}\" {{\n", escape_dot(&project.name)));
+    dot.push_str("  rankdir=LR;\n");
+    dot.push_str("  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n");
+    dot.push_str("  edge [color=\"#6b7280\"];\n");
+
+    for task in &graph_tasks {
+        let mut label = format!("#{} {}", task.id, task.title);
+        if let Some(minutes) = task.estimated_time {
+            label.push_str(&format!("\n{}", format_minutes(minutes)));
+        }
+
+        let mut attrs = vec![
+            format!("label=\"{}\"", escape_dot(&label)),
+            format!("fillcolor=\"{}\"", status_color(&task.status)),
+        ];
+        if task.project_id != project_id {
+            attrs.push("style=\"rounded,filled,dashed\"".to_string());
+        }
+        if critical_path.contains(&task.id) {
+            attrs.push("color=\"#dc2626\"".to_string());
+            attrs.push("penwidth=2".to_string());
+        }
+
+        dot.push_str(&format!("  t{} [{}];\n", task.id, attrs.join(", ")));
+    }
+
+    for task in &project_tasks {
+        for dep_id in &task.dependencies {
+            if !tasks_by_id.contains_key(dep_id) {
+                continue;
+            }
+            if critical_edges.contains(&(*dep_id, task.id)) {
+                dot.push_str(&format!("  t{} -> t{} [color=\"#dc2626\", penwidth=2];\n", dep_id, task.id));
+            } else {
+                dot.push_str(&format!("  t{} -> t{};\n", dep_id, task.id));
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    Ok(dot)
+}
+
+// Requires GraphViz's `dot` binary on PATH
+pub fn render_dot_to_svg(dot: &str) -> Result<String> {
+    let mut child = Command::new("dot")
+        .arg("-Tsvg")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("GraphViz `dot` is not available: {}", e))?;
+
+    child.stdin.take()
+        .ok_or_else(|| anyhow!("Failed to open GraphViz stdin"))?
+        .write_all(dot.as_bytes())?;
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow!("GraphViz failed: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+// Longest chain of prerequisites weighted by estimated minutes (chain length breaks ties)
+fn critical_path(tasks: &[&Task]) -> Vec<u32> {
+    let by_id: HashMap<u32, &Task> = tasks.iter().map(|t| (t.id, *t)).collect();
+    let mut memo: HashMap<u32, (u64, usize, Option<u32>)> = HashMap::new();
+
+    fn longest(
+        id: u32,
+        by_id: &HashMap<u32, &Task>,
+        memo: &mut HashMap<u32, (u64, usize, Option<u32>)>,
+        visiting: &mut HashSet<u32>,
+    ) -> (u64, usize) {
+        if let Some((weight, len, _)) = memo.get(&id) {
+            return (*weight, *len);
+        }
+        // Guard against cycles in hand-edited data files
+        if !visiting.insert(id) {
+            return (0, 0);
+        }
+
+        let task = by_id[&id];
+        let mut best = (0, 0, None);
+        for dep_id in &task.dependencies {
+            if by_id.contains_key(dep_id) {
+                let (weight, len) = longest(*dep_id, by_id, memo, visiting);
+                if (weight, len) > (best.0, best.1) {
+                    best = (weight, len, Some(*dep_id));
+                }
+            }
+        }
+
+        visiting.remove(&id);
+        let result = (best.0 + task.estimated_time.unwrap_or(0) as u64, best.1 + 1, best.2);
+        memo.insert(id, result);
+        (result.0, result.1)
+    }
+
+    let mut visiting = HashSet::new();
+    let mut end = None;
+    let mut end_score = (0, 0);
+    for task in tasks {
+        let score = longest(task.id, &by_id, &mut memo, &mut visiting);
+        if score > end_score {
+            end_score = score;
+            end = Some(task.id);
+        }
+    }
+
+    // A lone task is not a meaningful path
+    if end_score.1 < 2 {
+        return Vec::new();
+    }
+
+    let mut path = Vec::new();
+    let mut current = end;
+    while let Some(id) = current {
+        path.push(id);
+        current = memo.get(&id).and_then(|(_, _, prev)| *prev);
+    }
+    path.reverse();
+    path
+}
+
+// Mermaid Gantt export, fenced for pasting straight into GitHub Markdown
+
+pub fn mermaid_gantt(data: &RoadmapData, project_id: u32) -> Result<String> {
+    let project = data.projects.iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| anyhow!("Project with id {} not found", project_id))?;
+
+    let tasks: Vec<&Task> = data.tasks.iter()
+        .filter(|t| t.project_id == project_id)
+        .collect();
+    let task_ids: HashSet<u32> = tasks.iter().map(|t| t.id).collect();
+    let now = Utc::now();
+
+    let mut lines = vec![
+        "```mermaid".to_string(),
+        "gantt".to_string(),
+        format!("    title {}", mermaid_text(&project.name)),
+        "    dateFormat YYYY-MM-DD HH:mm".to_string(),
+        "    axisFormat %b %d".to_string(),
+    ];
+
+    let sections = [
+        (TaskStatus::Todo, "To Do"),
+        (TaskStatus::InProgress, "In Progress"),
+        (TaskStatus::Done, "Done"),
+    ];
+
+    for (status, section_name) in sections {
+        let section_tasks: Vec<&&Task> = tasks.iter().filter(|t| t.status == status).collect();
+        if section_tasks.is_empty() {
+            continue;
+        }
+
+        lines.push(format!("    section {}", section_name));
+        for task in section_tasks {
+            let due = task.due_date.as_deref().and_then(parse_timestamp);
+            let estimate = task.estimated_time.map(|m| Duration::minutes(m as i64));
+
+            let mut tags = Vec::new();
+            match task.status {
+                TaskStatus::Done => tags.push("done".to_string()),
+                TaskStatus::InProgress => tags.push("active".to_string()),
+                TaskStatus::Todo => {}
+            }
+            let overdue = due.map(|d| d < now).unwrap_or(false);
+            if task.status != TaskStatus::Done && (task.priority == TaskPriority::High || overdue) {
+                tags.push("crit".to_string());
+            }
+            tags.push(format!("t{}", task.id));
+
+            // Prerequisites in the same project anchor the bar, otherwise work back from the due date
+            let deps: Vec<String> = task.dependencies.iter()
+                .filter(|d| task_ids.contains(d))
+                .map(|d| format!("t{}", d))
+                .collect();
+            let start = if !deps.is_empty() {
+                format!("after {}", deps.join(" "))
+            } else {
+                let created = parse_timestamp(&task.created_at).unwrap_or(now);
+                let start = match (due, estimate) {
+                    (Some(due), Some(estimate)) => due - estimate,
+                    (Some(due), None) => created.min(due - Duration::days(1)),
+                    _ => created,
+                };
+                format_gantt_date(&start)
+            };
+
+            let end = match (due, estimate) {
+                (Some(due), _) => format_gantt_date(&due),
+                (None, Some(estimate)) => format!("{}m", estimate.num_minutes().max(1)),
+                (None, None) => "1d".to_string(),
+            };
+
+            lines.push(format!("    {} :{}, {}, {}", mermaid_text(&task.title), tags.join(", "), start, end));
+        }
+    }
+
+    lines.push("```".to_string());
+    Ok(lines.join("\n") + "\n")
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+fn format_gantt_date(value: &DateTime<Utc>) -> String {
+    value.format("%Y-%m-%d %H:%M").to_string()
+}
+
+// Colons and semicolons are syntax in Mermaid task lines
+fn mermaid_text(value: &str) -> String {
+    value
+        .replace(':', " -")
+        .replace(';', ",")
+        .replace(['\r', '\n'], " ")
+        .replace('#', "")
+}
+
+fn status_color(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Todo => "#e5e7eb",
+        TaskStatus::InProgress => "#fde68a",
+        TaskStatus::Done => "#bbf7d0",
+    }
+}
+
+fn format_minutes(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+fn escape_dot(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,189 @@
+use crate::board_import::{self, BoardImportResult, ImportedItem, RemoteBoard};
+use crate::csv_import;
+use crate::models::{RoadmapData, TaskStatus};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+// Imports a GitHub Projects (v2) board through the GraphQL API. The board's Status field gives
+// the column, labels become tags, a "Priority" field and a date field named like "Due date" are
+// used as such, and every other field is kept as a custom field (or a tag, for `tag_fields`).
+// The token needs the read:project scope (and repo for items from private repositories); it is
+// only used for this request and never stored.
+
+const API_URL: &str = "https://api.github.com/graphql";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const PAGE_SIZE: u32 = 100;
+// Stops runaway paging; 100 items per page
+const MAX_PAGES: usize = 50;
+const DUE_DATE_FIELDS: [&str; 4] = ["due", "due date", "deadline", "target date"];
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct GithubImportOptions {
+    pub owner: String, // User or organization login
+    pub number: u32, // As in github.com/orgs/<owner>/projects/<number>
+    pub token: String,
+    #[serde(default)]
+    pub project_id: Option<u32>, // None imports into a project named after the board
+    #[serde(default)]
+    pub status_field: Option<String>, // Single-select field holding the column, "Status" by default
+    #[serde(default)]
+    pub status_map: HashMap<String, TaskStatus>, // Column name -> status, for non-standard columns
+    #[serde(default)]
+    pub tag_fields: Vec<String>, // Fields whose values become tags instead of custom fields
+}
+
+const QUERY: &str = r#"
+query($owner: String!, $number: Int!, $first: Int!, $cursor: String) {
+  repositoryOwner(login: $owner) {
+    ... on ProjectV2Owner {
+      projectV2(number: $number) {
+        title
+        items(first: $first, after: $cursor) {
+          pageInfo { hasNextPage endCursor }
+          nodes {
+            id
+            isArchived
+            content {
+              ... on Issue { title body url state createdAt labels(first: 50) { nodes { name } } }
+              ... on PullRequest { title body url state createdAt labels(first: 50) { nodes { name } } }
+              ... on DraftIssue { title body createdAt }
+            }
+            fieldValues(first: 50) {
+              nodes {
+                ... on ProjectV2ItemFieldSingleSelectValue { name field { ... on ProjectV2FieldCommon { name } } }
+                ... on ProjectV2ItemFieldTextValue { text field { ... on ProjectV2FieldCommon { name } } }
+                ... on ProjectV2ItemFieldNumberValue { number field { ... on ProjectV2FieldCommon { name } } }
+                ... on ProjectV2ItemFieldDateValue { date field { ... on ProjectV2FieldCommon { name } } }
+                ... on ProjectV2ItemFieldIterationValue { title field { ... on ProjectV2FieldCommon { name } } }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+pub fn fetch(options: &GithubImportOptions) -> Result<RemoteBoard> {
+    let (name, nodes) = fetch_nodes(options)?;
+
+    let mut board = RemoteBoard { name, ..Default::default() };
+    for node in &nodes {
+        match to_item(node, options) {
+            Ok(Some(item)) => board.items.push(item),
+            Ok(None) => {}
+            Err(e) => board.skipped.push(e.to_string()),
+        }
+    }
+    Ok(board)
+}
+
+pub fn apply(data: &mut RoadmapData, board: RemoteBoard, options: &GithubImportOptions) -> Result<BoardImportResult> {
+    board_import::apply(data, "GitHub Projects", board, options.project_id, &options.status_map)
+}
+
+// The board's title and all of its items
+fn fetch_nodes(options: &GithubImportOptions) -> Result<(String, Vec<Value>)> {
+    if options.token.trim().is_empty() {
+        return Err(anyhow!("A GitHub token is required"));
+    }
+    let agent = ureq::AgentBuilder::new()
+        .timeout(REQUEST_TIMEOUT)
+        .build();
+
+    let mut title = String::new();
+    let mut nodes = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let body = json!({
+            "query": QUERY,
+            "variables": { "owner": options.owner.trim(), "number": options.number, "first": PAGE_SIZE, "cursor": cursor },
+        });
+        let response: Value = agent.post(API_URL)
+            .set("Authorization", &format!("Bearer {}", options.token.trim()))
+            .set("User-Agent", "RuidMap")
+            .send_json(body)
+            .map_err(|e| match e {
+                ureq::Error::Status(401, _) => anyhow!("GitHub rejected the token"),
+                e => anyhow!("GitHub request failed: {}", e),
+            })?
+            .into_json()?;
+
+        if let Some(message) = response["errors"].as_array().and_then(|errors| errors.first()).and_then(|e| e["message"].as_str()) {
+            return Err(anyhow!("GitHub: {}", message));
+        }
+        let project = &response["data"]["repositoryOwner"]["projectV2"];
+        if project.is_null() {
+            return Err(anyhow!("Project {} of {} not found, or the token can't read it", options.number, options.owner));
+        }
+
+        title = project["title"].as_str().unwrap_or("GitHub project").to_string();
+        let items = &project["items"];
+        nodes.extend(items["nodes"].as_array().cloned().unwrap_or_default());
+        if items["pageInfo"]["hasNextPage"].as_bool() != Some(true) {
+            return Ok((title, nodes));
+        }
+        cursor = items["pageInfo"]["endCursor"].as_str().map(str::to_string);
+    }
+
+    Err(anyhow!("{} has more than {} items; archive some and try again", title, MAX_PAGES * PAGE_SIZE as usize))
+}
+
+// None for archived items, which aren't on the board anymore
+fn to_item(node: &Value, options: &GithubImportOptions) -> Result<Option<ImportedItem>> {
+    if node["isArchived"].as_bool() == Some(true) {
+        return Ok(None);
+    }
+    let id = node["id"].as_str().ok_or_else(|| anyhow!("An item without an id was skipped"))?;
+    let content = &node["content"];
+    // Items from repositories the token can't see come back without content
+    let title = content["title"].as_str()
+        .ok_or_else(|| anyhow!("Item {}: no access to its issue or pull request", id))?;
+
+    let mut item = ImportedItem {
+        external_id: format!("github:{}", id),
+        title: title.to_string(),
+        description: content["body"].as_str().unwrap_or_default().to_string(),
+        closed: matches!(content["state"].as_str(), Some("CLOSED" | "MERGED")),
+        created_at: content["createdAt"].as_str().map(str::to_string),
+        tags: content["labels"]["nodes"].as_array()
+            .map(|labels| labels.iter().filter_map(|l| l["name"].as_str()).map(str::to_string).collect())
+            .unwrap_or_default(),
+        custom_fields: BTreeMap::new(),
+        ..Default::default()
+    };
+    if let Some(url) = content["url"].as_str() {
+        item.custom_fields.insert("GitHub URL".to_string(), url.to_string());
+    }
+
+    let status_field = options.status_field.as_deref().unwrap_or("Status");
+    for value in node["fieldValues"]["nodes"].as_array().into_iter().flatten() {
+        // The built-in Title field repeats the content's title
+        let Some(field) = value["field"]["name"].as_str().filter(|f| *f != "Title") else {
+            continue;
+        };
+        let text = match (&value["name"], &value["text"], &value["number"], &value["date"], &value["title"]) {
+            (Value::String(v), _, _, _, _) | (_, Value::String(v), _, _, _) | (_, _, _, Value::String(v), _) | (_, _, _, _, Value::String(v)) => v.clone(),
+            (_, _, Value::Number(n), _, _) => n.to_string(),
+            _ => continue,
+        };
+
+        if field.eq_ignore_ascii_case(status_field) {
+            item.column = Some(text);
+        } else if field.eq_ignore_ascii_case("priority") && csv_import::parse_priority(&text).is_ok() {
+            item.priority = csv_import::parse_priority(&text).ok();
+        } else if DUE_DATE_FIELDS.iter().any(|f| field.eq_ignore_ascii_case(f)) && value["date"].is_string() {
+            item.due_date = Some(csv_import::parse_due_date(&text).map_err(|e| anyhow!("{}: {}", title, e))?);
+        } else if options.tag_fields.iter().any(|f| f.eq_ignore_ascii_case(field)) {
+            item.tags.push(text);
+        } else {
+            item.custom_fields.insert(field.to_string(), text);
+        }
+    }
+
+    Ok(Some(item))
+}
@@ -0,0 +1,240 @@
+use crate::models::{RoadmapData, Task, TaskStatus};
+use crate::storage::Storage;
+use crate::tokens;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use tiny_http::{Header, Response, Server};
+
+// Read-only iCalendar feed served on localhost so calendar apps can subscribe to due dates
+
+pub struct IcalFeedState(pub Mutex<Option<IcalFeedServer>>);
+
+impl IcalFeedState {
+    // Restart the feed on launch if it was left enabled. Not for read-only files, whose settings
+    // and token may come from someone else's export.
+    pub fn resume(storage: &Storage) -> Self {
+        if storage.is_read_only() {
+            return IcalFeedState(Mutex::new(None));
+        }
+        IcalFeedState(Mutex::new(start_from_settings(storage)))
+    }
+
+    // Rebinds a running feed to the storage's current data file, e.g. after it was moved
+    pub fn restart(&self, storage: &Storage) {
+        if let Ok(mut feed) = self.0.lock() {
+            if feed.take().is_some() {
+                *feed = start_from_settings(storage);
+            }
+        }
+    }
+}
+
+fn start_from_settings(storage: &Storage) -> Option<IcalFeedServer> {
+    let settings = &storage.data().settings.ical_feed;
+    let (true, Some(token)) = (settings.enabled, &settings.token) else {
+        return None;
+    };
+    match IcalFeedServer::start(storage.get_data_file_path().to_path_buf(), settings.port, token.clone()) {
+        Ok(started) => Some(started),
+        Err(e) => {
+            eprintln!("Failed to resume calendar feed: {}", e);
+            None
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct IcalFeedInfo {
+    pub running: bool,
+    pub port: u16,
+    pub token: Option<String>,
+    pub url: Option<String>,
+}
+
+pub struct IcalFeedServer {
+    server: Arc<Server>,
+    handle: Option<JoinHandle<()>>,
+    port: u16,
+    token: String,
+}
+
+impl IcalFeedServer {
+    pub fn start(data_file_path: PathBuf, port: u16, token: String) -> Result<Self> {
+        let server = Server::http(("127.0.0.1", port))
+            .map_err(|e| anyhow!("Failed to bind 127.0.0.1:{}: {}", port, e))?;
+        let server = Arc::new(server);
+
+        let worker = Arc::clone(&server);
+        let feed_token = token.clone();
+        let handle = thread::spawn(move || {
+            for request in worker.incoming_requests() {
+                let response = handle_feed_request(&data_file_path, &feed_token, request.url());
+                let _ = request.respond(response);
+            }
+        });
+
+        Ok(IcalFeedServer {
+            server,
+            handle: Some(handle),
+            port,
+            token,
+        })
+    }
+
+    pub fn info(&self) -> IcalFeedInfo {
+        IcalFeedInfo {
+            running: true,
+            port: self.port,
+            token: Some(self.token.clone()),
+            url: Some(feed_url(self.port, &self.token)),
+        }
+    }
+}
+
+impl Drop for IcalFeedServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+pub fn feed_url(port: u16, token: &str) -> String {
+    format!("http://127.0.0.1:{}/calendar.ics?token={}", port, token)
+}
+
+pub fn generate_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+fn handle_feed_request(data_file_path: &Path, token: &str, url: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+    if path != "/calendar.ics" {
+        return Response::from_string("Not found").with_status_code(404);
+    }
+
+    if !tokens::in_query(query, token) {
+        return Response::from_string("Forbidden").with_status_code(403);
+    }
+
+    // Every request rereads the data file so subscribers always see the latest tasks. It is opened
+    // read-only: the app's storage is the only writer, and a missing or outdated file must not be
+    // created or migrated from here.
+    match Storage::open_read_only(data_file_path.to_path_buf()) {
+        Ok(storage) => {
+            let content_type = Header::from_bytes(&b"Content-Type"[..], &b"text/calendar; charset=utf-8"[..])
+                .expect("static header is valid");
+            Response::from_string(build_calendar(storage.data())).with_header(content_type)
+        }
+        Err(e) => Response::from_string(format!("Failed to load data: {}", e)).with_status_code(500),
+    }
+}
+
+pub fn build_calendar(data: &RoadmapData) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//RuidMap//RuidMap Desktop//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:RuidMap".to_string(),
+    ];
+
+    for task in &data.tasks {
+        if let Some(event) = task_event(task, data) {
+            lines.extend(event);
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut calendar = String::new();
+    for line in lines {
+        calendar.push_str(&fold_line(&line));
+        calendar.push_str("\r\n");
+    }
+    calendar
+}
+
+fn task_event(task: &Task, data: &RoadmapData) -> Option<Vec<String>> {
+    let due = parse_timestamp(task.due_date.as_deref()?)?;
+    let stamp = parse_timestamp(&task.updated_at).unwrap_or_else(Utc::now);
+
+    let project_name = data.projects.iter()
+        .find(|p| p.id == task.project_id)
+        .map(|p| p.name.as_str())
+        .unwrap_or("Unknown project");
+
+    let summary = if task.status == TaskStatus::Done {
+        format!("✔ {}", task.title)
+    } else {
+        task.title.clone()
+    };
+
+    let mut description = format!("Project: {}\nStatus: {}\nPriority: {}", project_name, task.status, task.priority);
+    if !task.description.is_empty() {
+        description.push_str("\n\n");
+        description.push_str(&task.description);
+    }
+
+    let mut event = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:task-{}@ruidmap", task.id),
+        format!("DTSTAMP:{}", format_timestamp(&stamp)),
+        format!("LAST-MODIFIED:{}", format_timestamp(&stamp)),
+        format!("DTSTART:{}", format_timestamp(&due)),
+        format!("DTEND:{}", format_timestamp(&due)),
+        format!("SUMMARY:{}", escape_text(&summary)),
+        format!("DESCRIPTION:{}", escape_text(&description)),
+    ];
+
+    if !task.tags.is_empty() {
+        let categories: Vec<String> = task.tags.iter().map(|t| escape_text(t)).collect();
+        event.push(format!("CATEGORIES:{}", categories.join(",")));
+    }
+
+    event.push("END:VEVENT".to_string());
+    Some(event)
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+fn format_timestamp(value: &DateTime<Utc>) -> String {
+    value.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// RFC 5545 limits content lines to 75 octets; continuation lines start with a space
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut line_len = 0;
+
+    for c in line.chars() {
+        let char_len = c.len_utf8();
+        if line_len + char_len > 75 {
+            folded.push_str("\r\n ");
+            line_len = 1;
+        }
+        folded.push(c);
+        line_len += char_len;
+    }
+
+    folded
+}
@@ -0,0 +1,182 @@
+use anyhow::{anyhow, Result};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Just enough IMAP (RFC 3501) over TLS to read new messages from one folder: log in, select,
+// search, fetch whole messages and flag them as seen. Only implicit TLS (port 993) is supported.
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+// A whole message is one literal. The size comes from the server, so larger ones are skipped
+// rather than allocated; base64 makes a 25 MB attachment about 34 MB.
+pub const MAX_LITERAL_BYTES: usize = 64 * 1024 * 1024;
+
+type TlsStream = rustls::StreamOwned<rustls::ClientConnection, TcpStream>;
+
+pub struct ImapSession {
+    stream: BufReader<TlsStream>,
+    next_tag: u32,
+}
+
+// A selected folder
+pub struct Mailbox {
+    pub uid_validity: Option<u32>, // Changes when the server renumbers the folder's messages
+    pub uid_next: Option<u32>, // The UID the next message will get
+}
+
+// Untagged lines of a response, with any literals ({n} strings) cut out into `literals`
+struct Response {
+    lines: Vec<String>,
+    literals: Vec<Vec<u8>>,
+    oversized: bool, // Literals over MAX_LITERAL_BYTES were read past and left out
+}
+
+impl ImapSession {
+    pub fn connect(host: &str, port: u16) -> Result<Self> {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+            .map_err(|_| anyhow!("Invalid server name \"{}\"", host))?;
+        let connection = rustls::ClientConnection::new(Arc::new(config), server_name)?;
+
+        let tcp = TcpStream::connect((host, port))
+            .map_err(|e| anyhow!("Could not connect to {}:{}: {}", host, port, e))?;
+        tcp.set_read_timeout(Some(TIMEOUT))?;
+        tcp.set_write_timeout(Some(TIMEOUT))?;
+
+        let mut session = ImapSession {
+            stream: BufReader::new(rustls::StreamOwned::new(connection, tcp)),
+            next_tag: 1,
+        };
+        let greeting = session.read_line()?;
+        if !greeting.starts_with("* OK") && !greeting.starts_with("* PREAUTH") {
+            return Err(anyhow!("Unexpected greeting from {}: {}", host, greeting.trim()));
+        }
+        Ok(session)
+    }
+
+    pub fn login(&mut self, username: &str, password: &str) -> Result<()> {
+        self.command(&format!("LOGIN {} {}", quote(username)?, quote(password)?))
+            .map_err(|e| anyhow!("Login failed: {}", e))?;
+        Ok(())
+    }
+
+    pub fn select(&mut self, folder: &str) -> Result<Mailbox> {
+        let response = self.command(&format!("SELECT {}", quote(folder)?))
+            .map_err(|e| anyhow!("Could not open the folder \"{}\": {}", folder, e))?;
+        Ok(Mailbox {
+            uid_validity: response_code(&response.lines, "UIDVALIDITY"),
+            uid_next: response_code(&response.lines, "UIDNEXT"),
+        })
+    }
+
+    // UIDs matching `criteria`, e.g. "UNSEEN" or "UID 42:*", lowest first
+    pub fn search(&mut self, criteria: &str) -> Result<Vec<u32>> {
+        let response = self.command(&format!("UID SEARCH {}", criteria))?;
+        let mut uids: Vec<u32> = response.lines.iter()
+            .filter_map(|line| line.strip_prefix("* SEARCH"))
+            .flat_map(|ids| ids.split_whitespace().filter_map(|id| id.parse().ok()))
+            .collect();
+        uids.sort_unstable();
+        uids.dedup();
+        Ok(uids)
+    }
+
+    // The raw message, or None when it is larger than MAX_LITERAL_BYTES; PEEK leaves it unread
+    // until `mark_seen`
+    pub fn fetch(&mut self, uid: u32) -> Result<Option<Vec<u8>>> {
+        let response = self.command(&format!("UID FETCH {} (BODY.PEEK[])", uid))?;
+        if response.oversized {
+            return Ok(None);
+        }
+        response.literals.into_iter()
+            .max_by_key(|literal| literal.len())
+            .map(Some)
+            .ok_or_else(|| anyhow!("Message {} is gone", uid))
+    }
+
+    pub fn mark_seen(&mut self, uids: &[u32]) -> Result<()> {
+        if uids.is_empty() {
+            return Ok(());
+        }
+        let set: Vec<String> = uids.iter().map(u32::to_string).collect();
+        self.command(&format!("UID STORE {} +FLAGS.SILENT (\\Seen)", set.join(",")))?;
+        Ok(())
+    }
+
+    pub fn logout(mut self) {
+        let _ = self.command("LOGOUT");
+    }
+
+    fn command(&mut self, command: &str) -> Result<Response> {
+        let tag = format!("a{}", self.next_tag);
+        self.next_tag += 1;
+        let stream = self.stream.get_mut();
+        stream.write_all(format!("{} {}\r\n", tag, command).as_bytes())?;
+        stream.flush()?;
+
+        let mut response = Response { lines: Vec::new(), literals: Vec::new(), oversized: false };
+        loop {
+            let mut line = self.read_line()?;
+            // A line ending in {n} is followed by n bytes of literal, then the rest of the line
+            while let Some(size) = literal_size(&line) {
+                if size > MAX_LITERAL_BYTES {
+                    let skipped = std::io::copy(&mut self.stream.by_ref().take(size as u64), &mut std::io::sink())?;
+                    if skipped < size as u64 {
+                        return Err(anyhow!("The server closed the connection"));
+                    }
+                    response.oversized = true;
+                } else {
+                    let mut literal = vec![0; size];
+                    self.stream.read_exact(&mut literal)?;
+                    response.literals.push(literal);
+                }
+                line.push_str(&self.read_line()?);
+            }
+
+            if let Some(status) = line.strip_prefix(&format!("{} ", tag)) {
+                let status = status.trim();
+                return match status.split_whitespace().next() {
+                    Some("OK") => Ok(response),
+                    _ => Err(anyhow!("{}", status.split_once(' ').map(|(_, text)| text).unwrap_or(status))),
+                };
+            }
+            response.lines.push(line);
+        }
+    }
+
+    fn read_line(&mut self) -> Result<String> {
+        let mut line = Vec::new();
+        if self.stream.read_until(b'\n', &mut line)? == 0 {
+            return Err(anyhow!("The server closed the connection"));
+        }
+        Ok(String::from_utf8_lossy(&line).to_string())
+    }
+}
+
+fn literal_size(line: &str) -> Option<usize> {
+    let line = line.trim_end().strip_suffix('}')?;
+    let start = line.rfind('{')?;
+    line[start + 1..].parse().ok()
+}
+
+// The number in a response code such as "* OK [UIDVALIDITY 3857529045]"
+fn response_code(lines: &[String], code: &str) -> Option<u32> {
+    let prefix = format!("[{} ", code);
+    lines.iter().find_map(|line| {
+        let start = line.find(&prefix)? + prefix.len();
+        line[start..].split(']').next()?.trim().parse().ok()
+    })
+}
+
+fn quote(value: &str) -> Result<String> {
+    if value.contains(['\r', '\n']) {
+        return Err(anyhow!("Line breaks are not allowed in IMAP credentials or folder names"));
+    }
+    Ok(format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")))
+}
@@ -0,0 +1,240 @@
+use crate::models::{Project, RoadmapData, Task};
+use crate::palette;
+use crate::commands::AppState;
+use crate::storage::PermissionDenied;
+use crate::tokens;
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use tauri::{AppHandle, Emitter, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// Token-protected `POST /inbox` on localhost for automations (Zapier, IFTTT, mail rules, shell
+// scripts) to drop tasks into an inbox project. It only listens on 127.0.0.1, so cloud services
+// need a tunnel to reach it. Unlike the calendar feed it writes, so requests go through the app's
+// storage rather than the data file.
+
+const INBOX_PROJECT_NAME: &str = "Inbox";
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+pub struct InboxState(pub Mutex<Option<InboxServer>>);
+
+impl InboxState {
+    // Restart the endpoint on launch if it was left enabled
+    pub fn resume(app: &AppHandle) -> Self {
+        InboxState(Mutex::new(start_from_settings(app)))
+    }
+}
+
+fn start_from_settings(app: &AppHandle) -> Option<InboxServer> {
+    let state = app.state::<AppState>();
+    let storage = state.0.read().ok()?;
+    // Read-only files may be someone else's export, with their settings and token
+    if storage.is_read_only() {
+        return None;
+    }
+    let settings = &storage.data().settings.inbox;
+    let (true, Some(token)) = (settings.enabled, &settings.token) else {
+        return None;
+    };
+    match InboxServer::start(app.clone(), settings.port, token.clone()) {
+        Ok(started) => Some(started),
+        Err(e) => {
+            eprintln!("Failed to resume inbox endpoint: {}", e);
+            None
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct InboxInfo {
+    pub running: bool,
+    pub port: u16,
+    pub token: Option<String>,
+    pub project_id: Option<u32>,
+    pub url: Option<String>,
+}
+
+// Body of POST /inbox; tags may also be one comma-separated string, as many automation tools send
+#[derive(Debug, Deserialize)]
+pub struct InboxItem {
+    pub title: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub tags: InboxTags,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum InboxTags {
+    List(Vec<String>),
+    Text(String),
+}
+
+impl Default for InboxTags {
+    fn default() -> Self {
+        InboxTags::List(Vec::new())
+    }
+}
+
+pub struct InboxServer {
+    server: Arc<Server>,
+    handle: Option<JoinHandle<()>>,
+    port: u16,
+    token: String,
+}
+
+impl InboxServer {
+    pub fn start(app: AppHandle, port: u16, token: String) -> Result<Self> {
+        let server = Server::http(("127.0.0.1", port))
+            .map_err(|e| anyhow!("Failed to bind 127.0.0.1:{}: {}", port, e))?;
+        let server = Arc::new(server);
+
+        let worker = Arc::clone(&server);
+        let inbox_token = token.clone();
+        let handle = thread::spawn(move || {
+            for mut request in worker.incoming_requests() {
+                let response = handle_request(&app, &inbox_token, &mut request);
+                let _ = request.respond(response);
+            }
+        });
+
+        Ok(InboxServer {
+            server,
+            handle: Some(handle),
+            port,
+            token,
+        })
+    }
+
+    pub fn info(&self, project_id: Option<u32>) -> InboxInfo {
+        InboxInfo {
+            running: true,
+            port: self.port,
+            token: Some(self.token.clone()),
+            project_id,
+            url: Some(inbox_url(self.port)),
+        }
+    }
+}
+
+// Joins the worker, which may be waiting for the storage lock: drop a server before taking it
+impl Drop for InboxServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+pub fn inbox_url(port: u16) -> String {
+    format!("http://127.0.0.1:{}/inbox", port)
+}
+
+fn handle_request(app: &AppHandle, token: &str, request: &mut Request) -> Response<std::io::Cursor<Vec<u8>>> {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    if path != "/inbox" {
+        return error(404, "Not found");
+    }
+    if *request.method() != Method::Post {
+        return error(405, "Use POST");
+    }
+
+    let bearer = request.headers().iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+        .map(str::trim);
+    let token_matches = bearer.is_some_and(|bearer| tokens::matches(bearer, token)) || tokens::in_query(query, token);
+    if !token_matches {
+        return error(403, "Forbidden");
+    }
+
+    let mut body = String::new();
+    if request.as_reader().take(MAX_BODY_BYTES + 1).read_to_string(&mut body).is_err() {
+        return error(400, "The body must be UTF-8 JSON");
+    }
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return error(413, "The body is too large");
+    }
+    let item: InboxItem = match serde_json::from_str(&body) {
+        Ok(item) => item,
+        Err(e) => return error(400, &format!("Expected {{\"title\", \"notes\", \"tags\"}}: {}", e)),
+    };
+
+    match add(app, item) {
+        Ok(task) => {
+            let _ = app.emit("inbox-task-created", &task);
+            json_response(201, json!({ "id": task.id, "title": task.title, "project_id": task.project_id }))
+        }
+        Err(e) if e.is::<PermissionDenied>() => error(403, &e.to_string()),
+        Err(e) => error(500, &e.to_string()),
+    }
+}
+
+fn add(app: &AppHandle, item: InboxItem) -> Result<Task> {
+    let state = app.state::<AppState>();
+    let mut storage = state.0.write().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    let mut data = storage.load_data()?;
+    let project_id = data.settings.inbox.project_id;
+    let task = add_item(&mut data, project_id, item)?;
+    storage.save_data(&data)?;
+    Ok(task)
+}
+
+// Adds the item to `project_id`, or to the "Inbox" project (created if needed)
+pub fn add_item(data: &mut RoadmapData, project_id: Option<u32>, item: InboxItem) -> Result<Task> {
+    let title = item.title.trim();
+    if title.is_empty() {
+        return Err(anyhow!("The title is empty"));
+    }
+
+    let project_id = match project_id.filter(|id| data.projects.iter().any(|p| p.id == *id)) {
+        Some(id) => id,
+        None => match data.projects.iter().find(|p| p.name.eq_ignore_ascii_case(INBOX_PROJECT_NAME)) {
+            Some(inbox) => inbox.id,
+            None => {
+                let id = data.projects.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+                let color = palette::next_color(&data.projects);
+                data.projects.push(Project::new_with_details(
+                    id,
+                    INBOX_PROJECT_NAME.to_string(),
+                    "Tasks sent from other apps, to sort into projects".to_string(),
+                    Some(color),
+                    Some(palette::DEFAULT_ICON.to_string()),
+                ));
+                id
+            }
+        },
+    };
+
+    let id = data.tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    let mut task = Task::new(id, project_id, title.to_string(), item.notes.trim().to_string());
+    let tags = match item.tags {
+        InboxTags::List(tags) => tags,
+        InboxTags::Text(text) => text.split(',').map(str::to_string).collect(),
+    };
+    for tag in tags.iter().map(|t| t.trim().trim_start_matches('#')).filter(|t| !t.is_empty()) {
+        task.add_tag(tag.to_string());
+    }
+
+    data.tasks.push(task.clone());
+    Ok(task)
+}
+
+fn error(status: u16, message: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    json_response(status, json!({ "error": message }))
+}
+
+fn json_response(status: u16, body: serde_json::Value) -> Response<std::io::Cursor<Vec<u8>>> {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header is valid");
+    Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(content_type)
+}
@@ -0,0 +1,51 @@
+use crate::models::RoadmapData;
+use anyhow::{anyhow, Result};
+use serde_json::error::Category;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+// SHA-256 checksums for backups and exports. The checksum covers the file's `data` member,
+// re-serialized compactly with sorted keys, so reformatting the file doesn't invalidate it but
+// any change to the data does.
+
+const PREFIX: &str = "sha256:";
+
+// "sha256:<hex>", as stored in the `checksum` field
+pub fn checksum(data: &RoadmapData) -> Result<String> {
+    Ok(checksum_of(&serde_json::to_value(data)?))
+}
+
+// Checks an export/backup envelope against its checksum. Files written before checksums were
+// added have none and are accepted as they are.
+pub fn verify(envelope: &Value) -> Result<()> {
+    let Some(expected) = envelope.get("checksum") else {
+        return Ok(());
+    };
+    let expected = expected.as_str()
+        .ok_or_else(|| anyhow!("The checksum must be a string"))?;
+    if !expected.starts_with(PREFIX) {
+        return Err(anyhow!("Unsupported checksum \"{}\", expected a {}<hex> value", expected, PREFIX));
+    }
+    let data = envelope.get("data")
+        .ok_or_else(|| anyhow!("The file has a checksum but no data; it is incomplete"))?;
+
+    if !checksum_of(data).eq_ignore_ascii_case(expected) {
+        return Err(anyhow!("Checksum mismatch: the file was truncated or modified after it was written, so none of it was loaded"));
+    }
+    Ok(())
+}
+
+// Parses JSON, telling a file that was cut off apart from one that is malformed
+pub fn parse_json(content: &[u8]) -> Result<Value> {
+    serde_json::from_slice(content).map_err(|e| match e.classify() {
+        Category::Eof => anyhow!("The file is truncated: it ends at line {}, column {} before the data is complete", e.line(), e.column()),
+        _ => anyhow!("Invalid JSON: {}", e),
+    })
+}
+
+fn checksum_of(data: &Value) -> String {
+    // Without serde_json's preserve_order feature objects are sorted maps, so this is canonical
+    let digest = Sha256::digest(data.to_string().as_bytes());
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}{}", PREFIX, hex)
+}
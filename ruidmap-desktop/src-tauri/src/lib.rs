@@ -1,21 +1,94 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 
-mod models;
-mod storage;
+pub mod models;
+pub mod storage;
+pub mod seed;
+mod anonymize;
+mod azure_devops_import;
+mod board;
+mod board_import;
+mod calendar;
+mod capacity;
+mod clickup_import;
 mod commands;
+mod csv_import;
+mod duration;
+mod email_inbox;
+mod envelope;
+mod escalation;
+mod export;
+mod github_import;
+mod ical;
+mod imap;
+mod inbox;
+mod integrity;
+mod links;
+mod location;
+mod markdown;
+mod mentions;
+mod migrations;
+mod mime;
+mod opml;
+mod overview;
+mod palette;
+mod persistence;
+mod remote_backup;
+mod schema;
+mod search;
+mod secrets;
+mod share;
+mod sizing;
+mod snippet;
+mod status_mapping;
+mod sync;
+mod templates;
+mod tokens;
+mod trash;
+mod webhooks;
+mod weekly_review;
+mod workdays;
 
 use commands::{
-    AppState, get_tasks, add_task, update_task, delete_task, get_task_by_id,
+    AppState, get_tasks, add_task, update_task, delete_task, delete_task_deferred,
+    cancel_pending_delete, get_pending_deletes, get_task_by_id,
     get_tasks_by_status, get_theme, set_theme, backup_data, restore_data,
-    toggle_task_status, get_task_stats, add_task_tag, remove_task_tag,
-    set_task_due_date, add_task_subtask, toggle_task_subtask, add_task_comment,
-    add_task_time, set_task_estimated_time, get_tasks_by_tag, get_tasks_by_due_date,
-    get_overdue_tasks, get_all_tags, create_project, get_projects, get_current_project,
-    switch_project, update_project, delete_project, get_tasks_by_project, get_project_stats,
-    export_data_dialog, export_data_to_file, import_data_from_content, validate_import_data
+    preview_partial_restore, restore_partial,
+    toggle_task_status, get_task_stats, add_task_tag, remove_task_tag, bulk_tag,
+    set_task_due_date, set_task_reminders, add_task_subtask, toggle_task_subtask, add_task_comment,
+    request_review, approve_task, reject_task, add_task_link_attachment,
+    add_task_time, set_task_estimated_time, set_task_size, parse_duration, set_task_description_format,
+    get_task_description_metadata, log_time_session, get_time_log, get_tasks_by_tag, get_tasks_by_size, get_tasks_by_due_date,
+    get_overdue_tasks, get_stale_tasks, get_recent_tasks, global_search, get_all_tags, create_project, get_color_palette,
+    get_projects, get_current_project,
+    switch_project, get_last_selection, set_last_selection, update_project, get_status_mapping, update_status_mapping, delete_project, get_tasks_by_project, get_project_stats, get_all_project_stats, get_workspace_overview, get_board, get_effort_summary,
+    get_period_report, get_locale_settings, update_locale_settings,
+    get_capacity_plan, get_capacity_settings, update_capacity_settings,
+    export_data_dialog, export_data_to_file, export_anonymized, export_task, import_task, import_data_from_content, validate_import_data,
+    inspect_csv, import_csv, import_github_project, import_from_azure_devops, import_from_clickup,
+    start_ical_feed, stop_ical_feed, get_ical_feed_status, regenerate_ical_feed_token,
+    start_inbox, stop_inbox, get_inbox_status, set_inbox_project, regenerate_inbox_token,
+    get_email_inbox_settings, update_email_inbox_settings, check_email_inbox_now,
+    get_remote_backup_settings, update_remote_backup_settings, backup_to_remote_now, list_remote_backups, restore_from_remote, sync_now,
+    start_share_board, stop_share_board, get_share_board_status,
+    set_task_assignee, watch_task, unwatch_task, get_my_work,
+    get_onboarding_state, create_sample_workspace, complete_onboarding,
+    get_data_location, relocate_data, set_portable_mode,
+    get_persistence_status, get_working_days_settings, update_working_days_settings, import_holidays, suggest_due_date, get_weekly_review_settings, update_weekly_review_settings, get_review_packet,
+    add_task_dependency, remove_task_dependency, export_dependency_graph, export_mermaid_gantt, export_opml, import_opml,
+    list_export_templates, export_with_template,
+    get_export_schema, get_webhook_settings, update_webhook_settings, test_webhook,
+    get_members, add_member, remove_member, get_my_notifications, mark_notifications_read,
+    get_escalation_settings, update_escalation_settings, run_escalation_rules,
+    seed_demo_data, get_storage_format, convert_storage_format, get_read_only, set_read_only
 };
+use ical::IcalFeedState;
+use inbox::InboxState;
+use share::ShareBoardState;
+use trash::PendingDeletes;
 use storage::Storage;
-use std::sync::Mutex;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tauri::{Emitter, Manager};
 
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -24,51 +97,207 @@ fn greet(name: &str) -> String {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize storage
-    let storage = Storage::new().expect("Failed to initialize storage");
+    // Initialize storage. `--read-only [file]` opens the data file, or a backup or export, for
+    // viewing only.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut storage = if args.iter().any(|arg| arg == "--read-only") {
+        let file = args.iter()
+            .find(|arg| !arg.starts_with("--"))
+            .map(PathBuf::from)
+            .unwrap_or_else(location::resolve_data_path);
+        Storage::open_read_only(file).expect("Failed to open data")
+    } else {
+        Storage::new().expect("Failed to initialize storage")
+    };
+    storage.start_persistence_worker();
+    let ical_feed = IcalFeedState::resume(&storage);
+    let share_board = ShareBoardState::resume(&storage);
     
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .manage(AppState(Mutex::new(storage)))
+        .manage(AppState(RwLock::new(storage)))
+        .manage(ical_feed)
+        .manage(share_board)
+        .manage(PendingDeletes::default())
+        .setup(|app| {
+            webhooks::spawn_overdue_watcher(app.handle().clone());
+            escalation::spawn_escalation_watcher(app.handle().clone());
+            weekly_review::spawn_review_scheduler(app.handle().clone());
+            email_inbox::spawn_email_poller(app.handle().clone());
+            remote_backup::spawn_backup_scheduler(app.handle().clone());
+            // Saves return before they reach the disk, so write failures are reported as they happen
+            let handle = app.handle().clone();
+            if let Ok(storage) = app.state::<AppState>().0.read() {
+                storage.set_persistence_listener(Box::new(move |status| {
+                    let _ = handle.emit("persistence-status-changed", status);
+                }));
+            }
+            app.manage(InboxState::resume(app.handle()));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             get_tasks,
             add_task,
             update_task,
             delete_task,
+            delete_task_deferred,
+            cancel_pending_delete,
+            get_pending_deletes,
             get_task_by_id,
             get_tasks_by_status,
             get_theme,
             set_theme,
             backup_data,
             restore_data,
+            preview_partial_restore,
+            restore_partial,
             toggle_task_status,
             get_task_stats,
             add_task_tag,
             remove_task_tag,
+            bulk_tag,
             set_task_due_date,
+            set_task_reminders,
             add_task_subtask,
             toggle_task_subtask,
             add_task_comment,
+            request_review,
+            approve_task,
+            reject_task,
+            add_task_link_attachment,
             add_task_time,
             set_task_estimated_time,
+            set_task_size,
+            parse_duration,
+            set_task_description_format,
+            get_task_description_metadata,
+            log_time_session,
+            get_time_log,
             get_tasks_by_tag,
+            get_tasks_by_size,
             get_tasks_by_due_date,
             get_overdue_tasks,
+            get_stale_tasks,
+            get_recent_tasks,
+            global_search,
             get_all_tags,
             create_project,
+            get_color_palette,
             get_projects,
             get_current_project,
             switch_project,
+            get_last_selection,
+            set_last_selection,
             update_project,
+            get_status_mapping,
+            update_status_mapping,
             delete_project,
             get_tasks_by_project,
             get_project_stats,
+            get_all_project_stats,
+            get_workspace_overview,
+            get_board,
+            get_effort_summary,
+            get_period_report,
+            get_locale_settings,
+            update_locale_settings,
+            get_capacity_plan,
+            get_capacity_settings,
+            update_capacity_settings,
             export_data_dialog,
             export_data_to_file,
+            export_anonymized,
+            export_task,
+            import_task,
             import_data_from_content,
-            validate_import_data
+            validate_import_data,
+            inspect_csv,
+            import_csv,
+            import_github_project,
+            import_from_azure_devops,
+            import_from_clickup,
+            start_ical_feed,
+            stop_ical_feed,
+            get_ical_feed_status,
+            regenerate_ical_feed_token,
+            start_inbox,
+            stop_inbox,
+            get_inbox_status,
+            set_inbox_project,
+            regenerate_inbox_token,
+            get_email_inbox_settings,
+            update_email_inbox_settings,
+            check_email_inbox_now,
+            get_remote_backup_settings,
+            update_remote_backup_settings,
+            backup_to_remote_now,
+            list_remote_backups,
+            restore_from_remote,
+            sync_now,
+            start_share_board,
+            stop_share_board,
+            get_share_board_status,
+            set_task_assignee,
+            watch_task,
+            unwatch_task,
+            get_my_work,
+            get_onboarding_state,
+            create_sample_workspace,
+            complete_onboarding,
+            get_data_location,
+            relocate_data,
+            set_portable_mode,
+            get_persistence_status,
+            get_working_days_settings,
+            update_working_days_settings,
+            import_holidays,
+            suggest_due_date,
+            get_weekly_review_settings,
+            update_weekly_review_settings,
+            get_review_packet,
+            add_task_dependency,
+            remove_task_dependency,
+            export_dependency_graph,
+            export_mermaid_gantt,
+            export_opml,
+            import_opml,
+            list_export_templates,
+            export_with_template,
+            get_export_schema,
+            get_webhook_settings,
+            update_webhook_settings,
+            test_webhook,
+            get_members,
+            add_member,
+            remove_member,
+            get_my_notifications,
+            mark_notifications_read,
+            get_escalation_settings,
+            update_escalation_settings,
+            run_escalation_rules,
+            seed_demo_data,
+            get_storage_format,
+            convert_storage_format,
+            get_read_only,
+            set_read_only
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running RuidMap application");
+        .build(tauri::generate_context!())
+        .expect("error while building RuidMap application")
+        .run(|app, event| {
+            // Saves are written in the background, so wait for the queue before the process ends
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<AppState>();
+                let flushed = match state.0.write() {
+                    Ok(mut storage) => {
+                        app.state::<PendingDeletes>().commit_all(&mut storage);
+                        storage.flush()
+                    }
+                    Err(e) => Err(anyhow::anyhow!("Failed to acquire lock: {}", e)),
+                };
+                if let Err(e) = flushed {
+                    eprintln!("Failed to save data on exit: {}", e);
+                }
+            }
+        });
 }
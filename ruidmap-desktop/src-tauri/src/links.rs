@@ -0,0 +1,70 @@
+use anyhow::{anyhow, Result};
+use std::io::Read;
+use std::time::Duration;
+
+// Link attachments: URL checks and fetching a page title to show instead of the bare URL
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_PAGE_BYTES: u64 = 512 * 1024;
+const MAX_TITLE_CHARS: usize = 200;
+
+pub fn validate_url(url: &str) -> Result<String> {
+    let url = url.trim();
+    let rest = url.strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(|| anyhow!("Only http and https links can be attached"))?;
+    if rest.is_empty() || rest.starts_with('/') || url.chars().any(char::is_whitespace) {
+        return Err(anyhow!("Invalid link \"{}\"", url));
+    }
+    Ok(url.to_string())
+}
+
+// The page's <title>, or None when it can't be fetched or isn't HTML
+pub fn fetch_title(url: &str) -> Option<String> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(FETCH_TIMEOUT)
+        .build();
+    let response = agent.get(url).call().ok()?;
+    if !response.content_type().contains("html") {
+        return None;
+    }
+
+    let mut body = Vec::new();
+    response.into_reader().take(MAX_PAGE_BYTES).read_to_end(&mut body).ok()?;
+    extract_title(&String::from_utf8_lossy(&body))
+}
+
+// Used when no title was given and none could be fetched
+pub fn fallback_title(url: &str) -> String {
+    url.trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn extract_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+
+    let title = decode_entities(&html[start..end])
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if title.is_empty() {
+        return None;
+    }
+    Some(title.chars().take(MAX_TITLE_CHARS).collect())
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,243 @@
+use crate::storage::Storage;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+// Where the data file lives. By default that is roadmap.json in the working directory; a file
+// named `portable` next to the executable switches to portable mode, where everything stays
+// beside the executable (e.g. on a USB stick). After `relocate`, a pointer file in that base
+// directory records the new path so the next launch finds it.
+
+pub const DATA_FILE_NAME: &str = "roadmap.json";
+const PORTABLE_MARKER: &str = "portable";
+const POINTER_FILE: &str = "roadmap.location";
+// Folders kept next to the data file that move along with it
+const SIDECAR_DIRS: [&str; 2] = ["attachments", "templates"];
+
+#[derive(Debug, Serialize)]
+pub struct DataLocation {
+    pub data_file: String,
+    pub portable: bool,
+    pub relocated: bool, // A pointer file redirects to a non-default path
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelocationResult {
+    pub location: DataLocation,
+    pub moved_dirs: Vec<String>,
+    pub updated_attachments: usize,
+}
+
+pub fn is_portable() -> bool {
+    executable_dir().map(|dir| dir.join(PORTABLE_MARKER).exists()).unwrap_or(false)
+}
+
+pub fn base_dir() -> PathBuf {
+    match executable_dir() {
+        Some(dir) if dir.join(PORTABLE_MARKER).exists() => dir,
+        _ => PathBuf::from("."),
+    }
+}
+
+// Used on startup; an unreadable or empty pointer falls back to the default file
+pub fn resolve_data_path() -> PathBuf {
+    let base = base_dir();
+    fs::read_to_string(base.join(POINTER_FILE))
+        .ok()
+        .map(|pointer| pointer.trim().to_string())
+        .filter(|pointer| !pointer.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| base.join(DATA_FILE_NAME))
+}
+
+// Whether `path` is the file the app loads on startup, as opposed to a backup or export
+pub fn is_data_file(path: &Path) -> bool {
+    matches!((absolute(path), absolute(&resolve_data_path())), (Ok(a), Ok(b)) if a == b)
+}
+
+pub fn current(storage: &Storage) -> DataLocation {
+    DataLocation {
+        data_file: storage.get_data_file_path().display().to_string(),
+        portable: is_portable(),
+        relocated: base_dir().join(POINTER_FILE).exists(),
+    }
+}
+
+// Moves the data file and its sidecar folders to `target` (a file, or a directory to put
+// roadmap.json in). Nothing is overwritten: an existing file or folder at the destination is
+// reported as a conflict before anything moves.
+pub fn relocate(storage: &mut Storage, target: &Path) -> Result<RelocationResult> {
+    relocate_within(storage, target, &base_dir())
+}
+
+// Portable mode moves the data next to the executable; turning it off moves it back to the
+// working directory
+pub fn set_portable(storage: &mut Storage, enabled: bool) -> Result<RelocationResult> {
+    let executable_dir = executable_dir().ok_or_else(|| anyhow!("Could not determine the executable's folder"))?;
+    let marker = executable_dir.join(PORTABLE_MARKER);
+    let previous_base = base_dir();
+    let new_base = if enabled { executable_dir.clone() } else { env::current_dir()? };
+
+    let mut result = relocate_within(storage, &new_base.join(DATA_FILE_NAME), &new_base)?;
+    if enabled {
+        fs::write(&marker, "Data is stored next to the executable while this file exists.\n")?;
+    } else if marker.exists() {
+        fs::remove_file(&marker)?;
+    }
+    // The old base's pointer no longer applies
+    if previous_base != new_base {
+        remove_if_exists(&previous_base.join(POINTER_FILE))?;
+    }
+
+    result.location = current(storage);
+    Ok(result)
+}
+
+fn relocate_within(storage: &mut Storage, target: &Path, base: &Path) -> Result<RelocationResult> {
+    storage.check_writable()?;
+    let target = if target.is_dir() { target.join(DATA_FILE_NAME) } else { target.to_path_buf() };
+    let target = absolute(&target)?;
+    let source = absolute(storage.get_data_file_path())?;
+    if target == source {
+        return Ok(RelocationResult { location: current(storage), moved_dirs: Vec::new(), updated_attachments: 0 });
+    }
+    if target.exists() {
+        return Err(anyhow!("{} already exists; choose another location or move that file first", target.display()));
+    }
+
+    let source_dir = source.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."));
+    let target_dir = target.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."));
+    let same_dir = source_dir == target_dir;
+    let dirs: Vec<&str> = SIDECAR_DIRS.iter()
+        .copied()
+        .filter(|dir| !same_dir && source_dir.join(dir).is_dir())
+        .collect();
+    if let Some(dir) = dirs.iter().find(|dir| target_dir.join(dir).exists()) {
+        return Err(anyhow!("{} already exists; choose another location or move that folder first", target_dir.join(dir).display()));
+    }
+
+    fs::create_dir_all(&target_dir)
+        .map_err(|e| anyhow!("Failed to create {}: {}", target_dir.display(), e))?;
+
+    // Attachment paths may be relative ("./attachments/12/x.pdf"), so they are made absolute to
+    // compare with the folders, while those still exist to resolve them against
+    let mut data = storage.load_data()?;
+    let mut updated_attachments = 0;
+    for dir in &dirs {
+        let from = source_dir.join(dir);
+        let to = target_dir.join(dir);
+        for attachment in data.tasks.iter_mut().flat_map(|t| t.attachments.iter_mut()) {
+            let Ok(path) = absolute(Path::new(&attachment.file_path)) else {
+                continue;
+            };
+            if let Ok(rest) = path.strip_prefix(&from) {
+                attachment.file_path = to.join(rest).display().to_string();
+                updated_attachments += 1;
+            }
+        }
+    }
+
+    // Folders first, so a failure leaves the data file where it was
+    let mut moved: Vec<&str> = Vec::new();
+    for dir in &dirs {
+        if let Err(e) = move_dir(&source_dir.join(dir), &target_dir.join(dir)) {
+            undo_moves(&moved, &source_dir, &target_dir);
+            return Err(anyhow!("Failed to move the {} folder: {}", dir, e));
+        }
+        moved.push(dir);
+    }
+
+    if let Err(e) = storage.relocate(target.clone(), data) {
+        undo_moves(&moved, &source_dir, &target_dir);
+        return Err(anyhow!("Failed to write {}: {}", target.display(), e));
+    }
+
+    // From here the new file is live; leftovers are only reported
+    let pointer = base.join(POINTER_FILE);
+    let pointer_result = if absolute(&base.join(DATA_FILE_NAME)).ok() == Some(target.clone()) {
+        remove_if_exists(&pointer)
+    } else {
+        write_atomically(&pointer, &target.display().to_string())
+    };
+    if let Err(e) = pointer_result {
+        eprintln!("Failed to update {}: {}", pointer.display(), e);
+    }
+    if let Err(e) = fs::remove_file(&source) {
+        eprintln!("Failed to remove the old data file {}: {}", source.display(), e);
+    }
+
+    Ok(RelocationResult {
+        location: current(storage),
+        moved_dirs: moved.iter().map(|dir| target_dir.join(dir).display().to_string()).collect(),
+        updated_attachments,
+    })
+}
+
+fn executable_dir() -> Option<PathBuf> {
+    env::current_exe().ok()?.parent().map(Path::to_path_buf)
+}
+
+pub fn absolute(path: &Path) -> Result<PathBuf> {
+    let path = if path.is_absolute() { path.to_path_buf() } else { env::current_dir()?.join(path) };
+    // Resolve `..` and symlinks through the parent, which exists even when the file doesn't yet
+    match (path.parent().and_then(|p| p.canonicalize().ok()), path.file_name()) {
+        (Some(parent), Some(name)) => Ok(parent.join(name)),
+        _ => Ok(path),
+    }
+}
+
+// Renames when possible and falls back to copy + delete across drives
+fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => {
+            if let Err(e) = copy_dir(from, to) {
+                let _ = fs::remove_dir_all(to);
+                return Err(e);
+            }
+            fs::remove_dir_all(from)?;
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            copy_dir(&path, &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(&path, to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+fn undo_moves(moved: &[&str], source_dir: &Path, target_dir: &Path) {
+    for dir in moved {
+        if let Err(e) = move_dir(&target_dir.join(dir), &source_dir.join(dir)) {
+            eprintln!("Failed to move {} back: {}", target_dir.join(dir).display(), e);
+        }
+    }
+}
+
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
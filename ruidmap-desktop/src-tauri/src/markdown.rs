@@ -0,0 +1,128 @@
+// Lightweight extraction of structure from Markdown task descriptions.
+// Not a full parser: fenced code blocks and inline code spans are skipped, everything else is scanned line by line.
+
+#[derive(Debug, serde::Serialize, Clone, PartialEq, Default)]
+pub struct DescriptionMetadata {
+    pub checklist: Vec<ChecklistItem>,
+    pub links: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize, Clone, PartialEq)]
+pub struct ChecklistItem {
+    pub title: String,
+    pub checked: bool,
+}
+
+pub fn extract(description: &str) -> DescriptionMetadata {
+    let mut metadata = DescriptionMetadata::default();
+    let mut in_code_block = false;
+
+    for line in description.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        if let Some(item) = checklist_item(trimmed) {
+            metadata.checklist.push(item);
+        }
+
+        let text = strip_inline_code(line);
+        for link in links(&text) {
+            if !metadata.links.contains(&link) {
+                metadata.links.push(link);
+            }
+        }
+        for tag in tags(&text) {
+            if !metadata.tags.contains(&tag) {
+                metadata.tags.push(tag);
+            }
+        }
+    }
+
+    metadata
+}
+
+// `- [ ] item`, `* [x] item` or `+ [X] item`
+fn checklist_item(line: &str) -> Option<ChecklistItem> {
+    let rest = line.strip_prefix(['-', '*', '+'])?.strip_prefix(' ')?.trim_start();
+    let (checked, title) = if let Some(title) = rest.strip_prefix("[ ]") {
+        (false, title)
+    } else if let Some(title) = rest.strip_prefix("[x]").or_else(|| rest.strip_prefix("[X]")) {
+        (true, title)
+    } else {
+        return None;
+    };
+
+    let title = title.trim();
+    if title.is_empty() {
+        return None;
+    }
+    Some(ChecklistItem { title: title.to_string(), checked })
+}
+
+fn strip_inline_code(line: &str) -> String {
+    line.split('`')
+        .enumerate()
+        .filter(|(i, _)| i % 2 == 0)
+        .map(|(_, part)| part)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// `[text](url)`, `<url>` and bare http(s) URLs
+fn links(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+
+    for (start, _) in text.match_indices("](") {
+        if let Some(end) = text[start + 2..].find(')') {
+            let url = text[start + 2..start + 2 + end].split_whitespace().next().unwrap_or("");
+            if !url.is_empty() {
+                found.push(url.to_string());
+            }
+        }
+    }
+
+    for word in text.split_whitespace() {
+        let word = word.trim_start_matches(['<', '(']);
+        let Some(start) = word.find("http://").or_else(|| word.find("https://")) else {
+            continue;
+        };
+        // Skip the target of a Markdown link, already collected above
+        if word[..start].ends_with("](") {
+            continue;
+        }
+        let url = word[start..].trim_end_matches(['>', ')', '.', ',', ';', ':', '!', '?']);
+        if !found.iter().any(|f| f == url) {
+            found.push(url.to_string());
+        }
+    }
+
+    found
+}
+
+// `#tag` at the start of a word; headings (`# Title`) and issue numbers (`#42`) don't count
+fn tags(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+
+    for (i, c) in chars.iter().enumerate() {
+        if *c != '#' || (i > 0 && !chars[i - 1].is_whitespace() && chars[i - 1] != '(') {
+            continue;
+        }
+        let tag: String = chars[i + 1..].iter()
+            .take_while(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/'))
+            .collect();
+        let tag = tag.trim_end_matches(['-', '_', '/']);
+        if !tag.is_empty() && !tag.chars().all(|c| c.is_ascii_digit()) {
+            found.push(tag.to_string());
+        }
+    }
+
+    found
+}
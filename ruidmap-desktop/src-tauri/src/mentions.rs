@@ -0,0 +1,58 @@
+use crate::models::{Comment, Notification, RoadmapData};
+
+// @mentions in comments, resolved against workspace members
+
+const EXCERPT_LENGTH: usize = 140;
+
+// `@handle` at the start of a word; trailing punctuation is not part of the handle
+pub fn extract_mentions(text: &str) -> Vec<String> {
+    let mut mentions = Vec::new();
+
+    for word in text.split_whitespace() {
+        let Some(handle) = word.trim_start_matches(['(', '"', '\'']).strip_prefix('@') else {
+            continue;
+        };
+        let handle = handle
+            .split(|c: char| !(c.is_alphanumeric() || matches!(c, '.' | '_' | '-')))
+            .next()
+            .unwrap_or("")
+            .trim_end_matches(['.', '-']);
+        if !handle.is_empty() && !mentions.iter().any(|m: &String| m.eq_ignore_ascii_case(handle)) {
+            mentions.push(handle.to_string());
+        }
+    }
+
+    mentions
+}
+
+// Adds a notification for every member mentioned in the comment, except its author.
+// Returns how many were created; unknown handles are ignored.
+pub fn notify_mentions(data: &mut RoadmapData, task_id: u32, comment: &Comment) -> usize {
+    let recipients: Vec<u32> = extract_mentions(&comment.text).iter()
+        .filter_map(|handle| data.members.iter().find(|m| m.handle.eq_ignore_ascii_case(handle)))
+        .filter(|m| !m.matches(&comment.author))
+        .map(|m| m.id)
+        .collect();
+
+    let excerpt = if comment.text.chars().count() > EXCERPT_LENGTH {
+        format!("{}…", comment.text.chars().take(EXCERPT_LENGTH).collect::<String>())
+    } else {
+        comment.text.clone()
+    };
+
+    let first_id = data.notifications.iter().map(|n| n.id).max().unwrap_or(0) + 1;
+    for (member_id, id) in recipients.iter().zip(first_id..) {
+        data.notifications.push(Notification {
+            id,
+            member_id: *member_id,
+            task_id,
+            comment_id: comment.id,
+            author: comment.author.clone(),
+            excerpt: excerpt.clone(),
+            created_at: comment.created_at.clone(),
+            read: false,
+        });
+    }
+
+    recipients.len()
+}
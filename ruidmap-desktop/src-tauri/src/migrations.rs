@@ -0,0 +1,189 @@
+use crate::models::{AppSettings, Project};
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+
+// Versioned upgrades for the data file. Steps work on raw JSON so they can run before the
+// file matches the current models, and each one only fills in what is missing so re-running
+// a step is harmless.
+
+// Must match the version of the last step below
+pub const CURRENT_VERSION: &str = "1.2.0";
+
+struct Migration {
+    version: &'static str,
+    apply: fn(&mut Value) -> Result<()>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: "1.0.0", apply: add_projects },
+    Migration { version: "1.1.0", apply: add_dependencies_and_settings },
+    Migration { version: "1.2.0", apply: add_onboarding },
+];
+
+// Files written before versioning (or with an unreadable version) count as "0.0.0"
+pub fn data_version(data: &Value) -> String {
+    data.get("version")
+        .and_then(Value::as_str)
+        .filter(|v| parse_version(v).is_some())
+        .unwrap_or("0.0.0")
+        .to_string()
+}
+
+// Errors if the file was written by a newer app than this one
+pub fn needs_migration(data: &Value) -> Result<bool> {
+    let version = data_version(data);
+    if is_newer(&version, CURRENT_VERSION) {
+        return Err(anyhow!(
+            "Data file version {} is newer than this version of RuidMap supports ({}). Please update the app.",
+            version, CURRENT_VERSION
+        ));
+    }
+    Ok(version != CURRENT_VERSION)
+}
+
+// Runs every step newer than the file's version, recording each applied version in the data
+pub fn migrate(data: &mut Value) -> Result<()> {
+    needs_migration(data)?;
+
+    for migration in MIGRATIONS {
+        if !is_newer(migration.version, &data_version(data)) {
+            continue;
+        }
+        (migration.apply)(data)
+            .map_err(|e| anyhow!("Migration to {} failed: {}", migration.version, e))?;
+        object_mut(data)?.insert("version".to_string(), json!(migration.version));
+    }
+
+    Ok(())
+}
+
+fn parse_version(version: &str) -> Option<Vec<u32>> {
+    version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+fn is_newer(version: &str, than: &str) -> bool {
+    parse_version(version).unwrap_or_default() > parse_version(than).unwrap_or_default()
+}
+
+fn object_mut(data: &mut Value) -> Result<&mut serde_json::Map<String, Value>> {
+    data.as_object_mut().ok_or_else(|| anyhow!("Data file is not a JSON object"))
+}
+
+// 1.0.0: tasks belong to projects. Pre-project files get a default project that owns every task.
+fn add_projects(data: &mut Value) -> Result<()> {
+    let object = object_mut(data)?;
+
+    let has_projects = object.get("projects")
+        .and_then(Value::as_array)
+        .map(|p| !p.is_empty())
+        .unwrap_or(false);
+    if !has_projects {
+        let default_project = Project::new(1, "Default Project".to_string());
+        object.insert("projects".to_string(), json!([serde_json::to_value(default_project)?]));
+        object.insert("current_project_id".to_string(), json!(1));
+    }
+
+    let project_id = object.get("current_project_id").and_then(Value::as_u64).unwrap_or(1);
+    if let Some(tasks) = object.get_mut("tasks").and_then(Value::as_array_mut) {
+        for task in tasks.iter_mut().filter_map(Value::as_object_mut) {
+            let missing = task.get("project_id").and_then(Value::as_u64).unwrap_or(0) == 0;
+            if missing {
+                task.insert("project_id".to_string(), json!(project_id));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// 1.1.0: task dependencies and app-wide settings are written out explicitly
+fn add_dependencies_and_settings(data: &mut Value) -> Result<()> {
+    let object = object_mut(data)?;
+
+    if let Some(tasks) = object.get_mut("tasks").and_then(Value::as_array_mut) {
+        for task in tasks.iter_mut().filter_map(Value::as_object_mut) {
+            task.entry("dependencies").or_insert_with(|| json!([]));
+        }
+    }
+
+    if !object.contains_key("settings") {
+        object.insert("settings".to_string(), serde_json::to_value(AppSettings::default())?);
+    }
+
+    Ok(())
+}
+
+// 1.2.0: first-run onboarding. Workspaces that already have tasks don't need it.
+fn add_onboarding(data: &mut Value) -> Result<()> {
+    let object = object_mut(data)?;
+
+    let has_tasks = object.get("tasks")
+        .and_then(Value::as_array)
+        .map(|t| !t.is_empty())
+        .unwrap_or(false);
+    object.entry("onboarding").or_insert_with(|| json!({
+        "completed": has_tasks,
+        "completed_at": null,
+        "sample_project_id": null
+    }));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RoadmapData;
+
+    #[test]
+    fn migrates_unversioned_file() {
+        let mut data = json!({
+            "tasks": [{
+                "id": 1,
+                "title": "Write docs",
+                "description": "",
+                "status": "todo",
+                "priority": "medium",
+                "created_at": "2024-01-01T00:00:00Z",
+                "updated_at": "2024-01-01T00:00:00Z",
+                "due_date": null,
+                "tags": [],
+                "subtasks": [],
+                "comments": [],
+                "time_spent": 0,
+                "estimated_time": null,
+                "attachments": []
+            }]
+        });
+        assert!(needs_migration(&data).unwrap());
+        migrate(&mut data).unwrap();
+
+        assert_eq!(data_version(&data), CURRENT_VERSION);
+        assert_eq!(data["projects"][0]["id"], 1);
+        assert_eq!(data["tasks"][0]["project_id"], 1);
+        assert_eq!(data["tasks"][0]["dependencies"], json!([]));
+        assert_eq!(data["onboarding"]["completed"], true);
+        assert!(!needs_migration(&data).unwrap());
+
+        let data: RoadmapData = serde_json::from_value(data).unwrap();
+        assert_eq!(data.tasks[0].project_id, 1);
+    }
+
+    #[test]
+    fn migration_is_repeatable() {
+        let mut data = json!({ "version": "1.0.0", "tasks": [], "projects": [] });
+        migrate(&mut data).unwrap();
+        let once = data.clone();
+        object_mut(&mut data).unwrap().insert("version".to_string(), json!("1.0.0"));
+        migrate(&mut data).unwrap();
+        assert_eq!(data, once);
+    }
+
+    #[test]
+    fn refuses_newer_version() {
+        let mut data = json!({ "version": "99.0.0", "tasks": [] });
+        assert!(needs_migration(&data).is_err());
+        assert!(migrate(&mut data).is_err());
+        assert_eq!(data["version"], "99.0.0");
+    }
+}
@@ -0,0 +1,368 @@
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::{DecodePaddingMode, Engine};
+use std::collections::HashMap;
+
+// Reading of email messages (RFC 5322 with MIME parts): headers including encoded words, the text
+// of the message and its attachments. Lenient by design; whatever can't be decoded is kept as is.
+
+// Mail clients don't all pad base64
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+#[derive(Debug, Default)]
+pub struct Email {
+    pub subject: String,
+    pub from: String,
+    pub message_id: Option<String>,
+    pub text: String, // The plain text part, or the HTML part converted to text
+    pub attachments: Vec<EmailAttachment>,
+}
+
+#[derive(Debug)]
+pub struct EmailAttachment {
+    pub filename: String,
+    pub mime_type: String,
+    pub content: Vec<u8>,
+}
+
+struct Part<'a> {
+    headers: Vec<(String, String)>, // Lowercased name, unfolded value
+    body: &'a [u8],
+}
+
+impl Part<'_> {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    // ("text/plain", parameters) from Content-Type, text/plain when missing
+    fn content_type(&self) -> (String, HashMap<String, String>) {
+        let (value, parameters) = split_parameters(self.header("content-type").unwrap_or("text/plain"));
+        (value.to_lowercase(), parameters)
+    }
+}
+
+pub fn parse(raw: &[u8]) -> Email {
+    let message = part(raw);
+    let mut email = Email {
+        subject: decode_words(message.header("subject").unwrap_or_default()).trim().to_string(),
+        from: decode_words(message.header("from").unwrap_or_default()).trim().to_string(),
+        message_id: message.header("message-id").map(|id| id.trim().trim_matches(['<', '>']).to_string()).filter(|id| !id.is_empty()),
+        ..Default::default()
+    };
+
+    let mut plain = None;
+    let mut html = None;
+    walk(&message, &mut plain, &mut html, &mut email.attachments);
+    email.text = plain.or_else(|| html.map(|html| html_to_text(&html))).unwrap_or_default();
+    email.text = strip_signature(&email.text).trim().to_string();
+    email
+}
+
+fn walk(part: &Part, plain: &mut Option<String>, html: &mut Option<String>, attachments: &mut Vec<EmailAttachment>) {
+    let (mime_type, parameters) = part.content_type();
+    if let Some(boundary) = parameters.get("boundary").filter(|_| mime_type.starts_with("multipart/")) {
+        for body in split_multipart(part.body, boundary) {
+            walk(&self::part(body), plain, html, attachments);
+        }
+        return;
+    }
+
+    let (disposition, disposition_parameters) = split_parameters(part.header("content-disposition").unwrap_or_default());
+    let filename = disposition_parameters.get("filename").or(parameters.get("name")).map(|name| decode_words(name));
+    let content = decode_body(part.body, part.header("content-transfer-encoding").unwrap_or_default());
+
+    let is_attachment = disposition.eq_ignore_ascii_case("attachment") || filename.is_some() || !mime_type.starts_with("text/");
+    match mime_type.as_str() {
+        "text/plain" if !is_attachment && plain.is_none() => *plain = Some(decode_charset(&content, parameters.get("charset"))),
+        "text/html" if !is_attachment && html.is_none() => *html = Some(decode_charset(&content, parameters.get("charset"))),
+        _ if is_attachment && !content.is_empty() => {
+            let extension = if mime_type == "message/rfc822" { "eml" } else { "bin" };
+            attachments.push(EmailAttachment {
+                filename: filename.unwrap_or_else(|| format!("attachment-{}.{}", attachments.len() + 1, extension)),
+                mime_type,
+                content,
+            });
+        }
+        _ => {}
+    }
+}
+
+fn part(raw: &[u8]) -> Part<'_> {
+    let (head, body) = match find(raw, b"\r\n\r\n") {
+        Some(i) => (&raw[..i], &raw[i + 4..]),
+        None => match find(raw, b"\n\n") {
+            Some(i) => (&raw[..i], &raw[i + 2..]),
+            None => (raw, &raw[raw.len()..]),
+        },
+    };
+
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in String::from_utf8_lossy(head).lines() {
+        // Folded continuation of the previous header
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+    Part { headers, body }
+}
+
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut position = 0;
+    while position < body.len() {
+        let end = body[position..].iter().position(|b| *b == b'\n').map_or(body.len(), |i| position + i + 1);
+        let line = String::from_utf8_lossy(&body[position..end]);
+        let line = line.trim_end();
+        if line == delimiter || line == format!("{}--", delimiter) {
+            if let Some(start) = start {
+                // The line break before a delimiter belongs to the delimiter
+                let mut part_end = position;
+                if body[..part_end].ends_with(b"\r\n") {
+                    part_end -= 2;
+                } else if body[..part_end].ends_with(b"\n") {
+                    part_end -= 1;
+                }
+                parts.push(&body[start..part_end.max(start)]);
+            }
+            if line != delimiter {
+                break;
+            }
+            start = Some(end);
+        }
+        position = end;
+    }
+    parts
+}
+
+fn decode_body(body: &[u8], encoding: &str) -> Vec<u8> {
+    match encoding.trim().to_lowercase().as_str() {
+        "base64" => {
+            let cleaned: Vec<u8> = body.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect();
+            BASE64.decode(cleaned).unwrap_or_else(|_| body.to_vec())
+        }
+        "quoted-printable" => decode_quoted_printable(body, false),
+        _ => body.to_vec(),
+    }
+}
+
+// `in_header` is the Q encoding of encoded words, where _ stands for a space
+fn decode_quoted_printable(input: &[u8], in_header: bool) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            b'=' if input[i + 1..].starts_with(b"\r\n") => i += 3,
+            b'=' if input[i + 1..].starts_with(b"\n") => i += 2,
+            b'=' if i + 2 < input.len() => {
+                match std::str::from_utf8(&input[i + 1..i + 3]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        output.push(byte);
+                        i += 3;
+                    }
+                    None => {
+                        output.push(b'=');
+                        i += 1;
+                    }
+                }
+            }
+            b'_' if in_header => {
+                output.push(b' ');
+                i += 1;
+            }
+            byte => {
+                output.push(byte);
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+// UTF-8 and ASCII as such; Latin-1 and its Windows variant byte by byte; anything else is read as UTF-8
+fn decode_charset(bytes: &[u8], charset: Option<&String>) -> String {
+    match charset.map(|c| c.to_lowercase()).as_deref() {
+        Some("iso-8859-1" | "latin1" | "windows-1252" | "cp1252") => bytes.iter().map(|b| *b as char).collect(),
+        _ => String::from_utf8_lossy(bytes).to_string(),
+    }
+}
+
+// Encoded words (RFC 2047) as in "=?UTF-8?B?SGVsbG8=?=", used for non-ASCII headers
+fn decode_words(value: &str) -> String {
+    let mut result = String::new();
+    let mut rest = value;
+    let mut previous_was_word = false;
+    while let Some(start) = rest.find("=?") {
+        let Some(word) = encoded_word(&rest[start..]) else {
+            result.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            previous_was_word = false;
+            continue;
+        };
+        // Whitespace between two encoded words is dropped
+        let between = &rest[..start];
+        if !(previous_was_word && between.trim().is_empty()) {
+            result.push_str(between);
+        }
+        result.push_str(&word.0);
+        rest = &rest[start + word.1..];
+        previous_was_word = true;
+    }
+    result.push_str(rest);
+    result
+}
+
+// The decoded text and the length of the encoded word
+fn encoded_word(input: &str) -> Option<(String, usize)> {
+    let mut fields = input.strip_prefix("=?")?.splitn(3, '?');
+    let charset = fields.next()?;
+    let encoding = fields.next()?;
+    let rest = fields.next()?;
+    let text = &rest[..rest.find("?=")?];
+    if charset.contains(char::is_whitespace) || text.contains(char::is_whitespace) {
+        return None;
+    }
+    let bytes = match encoding {
+        "B" | "b" => BASE64.decode(text).ok()?,
+        "Q" | "q" => decode_quoted_printable(text.as_bytes(), true),
+        _ => return None,
+    };
+    let length = "=?".len() + charset.len() + 1 + encoding.len() + 1 + text.len() + "?=".len();
+    // RFC 2231 allows a language after the charset, e.g. "UTF-8*en"
+    let charset = charset.split('*').next().unwrap_or_default().to_string();
+    Some((decode_charset(&bytes, Some(&charset)), length))
+}
+
+// "text/plain; charset=utf-8" -> ("text/plain", {"charset": "utf-8"}); handles quoting and the
+// RFC 2231 form filename*=UTF-8''na%C3%AFve.txt
+fn split_parameters(value: &str) -> (String, HashMap<String, String>) {
+    let mut parts = split_unquoted(value, ';').into_iter();
+    let main = parts.next().unwrap_or_default().trim().to_string();
+    let mut parameters = HashMap::new();
+    for parameter in parts {
+        let Some((name, value)) = parameter.split_once('=') else {
+            continue;
+        };
+        let name = name.trim().to_lowercase();
+        let value = value.trim();
+        match name.strip_suffix('*') {
+            Some(name) => {
+                let encoded = value.splitn(3, '\'').nth(2).unwrap_or(value);
+                let charset = value.split('\'').next().map(str::to_string);
+                parameters.insert(name.to_string(), decode_charset(&percent_decode(encoded), charset.as_ref()));
+            }
+            None => {
+                let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+                parameters.entry(name).or_insert_with(|| value.replace("\\\"", "\"").replace("\\\\", "\\"));
+            }
+        }
+    }
+    (main, parameters)
+}
+
+fn split_unquoted(value: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                parts.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(last) = parts.last_mut() {
+            last.push(c);
+        }
+    }
+    parts
+}
+
+fn percent_decode(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok()).and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                output.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                output.push(byte);
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+// Drops everything after the conventional "-- " signature separator
+fn strip_signature(text: &str) -> &str {
+    match text.find("\n-- \n").or_else(|| text.find("\r\n-- \r\n")) {
+        Some(i) => &text[..i],
+        None => text,
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+// HTML to readable text, keeping line breaks and list bullets; styles and scripts are dropped
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim().to_lowercase();
+        let name = tag.trim_start_matches('/').split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or_default();
+        rest = &rest[start + end + 1..];
+        match name {
+            "style" | "script" | "head" if !tag.starts_with('/') => {
+                let close = format!("</{}", name);
+                rest = match rest.as_bytes().windows(close.len()).position(|w| w.eq_ignore_ascii_case(close.as_bytes())) {
+                    Some(i) => &rest[i..],
+                    None => "",
+                };
+            }
+            "br" | "p" | "div" | "tr" | "h1" | "h2" | "h3" | "h4" if !text.is_empty() && !text.ends_with('\n') => text.push('\n'),
+            "li" if !tag.starts_with('/') => {
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                text.push_str("- ");
+            }
+            _ => {}
+        }
+    }
+    text.push_str(rest);
+
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    text.lines().map(str::trim_end).collect::<Vec<_>>().join("\n").trim().to_string()
+}
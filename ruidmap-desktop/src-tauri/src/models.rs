@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -18,6 +19,59 @@ pub struct Task {
     pub time_spent: u32, // minutes
     pub estimated_time: Option<u32>, // minutes
     pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    pub dependencies: Vec<u32>, // IDs of tasks that must be finished first
+    #[serde(default)]
+    pub description_format: DescriptionFormat,
+    #[serde(default)]
+    pub requires_review: bool, // Done needs an approved review
+    #[serde(default)]
+    pub review_state: ReviewState,
+    #[serde(default)]
+    pub reviewer: Option<String>,
+    #[serde(default)]
+    pub status_changed_at: Option<String>, // Missing on tasks saved before it was tracked
+    #[serde(default)]
+    pub assignee: Option<u32>, // Member id
+    #[serde(default)]
+    pub watchers: Vec<u32>, // Member ids
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, String>, // Field name -> value, e.g. from an imported board
+    #[serde(default)]
+    pub external_id: Option<String>, // "<source>:<id>" for imported tasks, so re-imports update them
+    #[serde(default)]
+    pub size: Option<u32>, // Effort in points, labelled by the project's size scale; separate from estimated_time
+    #[serde(default)]
+    pub reminders: Vec<u32>, // Minutes before the due date
+    #[serde(default)]
+    pub reminders_sent: Vec<u32>, // Reminders already given for the current due date
+    #[serde(default)]
+    pub sync_id: String, // The same on every synced device, see sync.rs; given on the first sync
+}
+
+// Computed on read so boards can fade tasks that haven't moved in a while
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct TaskStaleness {
+    pub days_since_update: i64,
+    pub days_in_status: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReviewState {
+    #[default]
+    NotRequested,
+    Requested,
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DescriptionFormat {
+    #[default]
+    Plaintext,
+    Markdown,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -87,7 +141,7 @@ impl Task {
             status: TaskStatus::Todo,
             priority: TaskPriority::Medium,
             created_at: now.clone(),
-            updated_at: now,
+            updated_at: now.clone(),
             due_date: None,
             tags: Vec::new(),
             subtasks: Vec::new(),
@@ -95,14 +149,50 @@ impl Task {
             time_spent: 0,
             estimated_time: None,
             attachments: Vec::new(),
+            dependencies: Vec::new(),
+            description_format: DescriptionFormat::Plaintext,
+            requires_review: false,
+            review_state: ReviewState::NotRequested,
+            reviewer: None,
+            status_changed_at: Some(now),
+            assignee: None,
+            watchers: Vec::new(),
+            custom_fields: BTreeMap::new(),
+            external_id: None,
+            size: None,
+            reminders: Vec::new(),
+            reminders_sent: Vec::new(),
+            sync_id: String::new(),
         }
     }
 
     pub fn update_status(&mut self, status: TaskStatus) {
-        self.status = status;
+        // Reopening an approved task means it has to be reviewed again
+        if status != TaskStatus::Done && self.review_state == ReviewState::Approved {
+            self.review_state = ReviewState::NotRequested;
+        }
+        self.set_status(status);
         self.updated_at = chrono::Utc::now().to_rfc3339();
     }
 
+    fn set_status(&mut self, status: TaskStatus) {
+        if self.status != status {
+            self.status_changed_at = Some(chrono::Utc::now().to_rfc3339());
+        }
+        self.status = status;
+    }
+
+    // Tasks without a recorded status change count from their last update
+    pub fn staleness(&self, now: chrono::DateTime<chrono::Utc>) -> TaskStaleness {
+        let days_since = |timestamp: &str| chrono::DateTime::parse_from_rfc3339(timestamp)
+            .map(|t| (now - t.with_timezone(&chrono::Utc)).num_days().max(0))
+            .unwrap_or(0);
+        TaskStaleness {
+            days_since_update: days_since(&self.updated_at),
+            days_in_status: days_since(self.status_changed_at.as_deref().unwrap_or(&self.updated_at)),
+        }
+    }
+
     pub fn update_priority(&mut self, priority: TaskPriority) {
         self.priority = priority;
         self.updated_at = chrono::Utc::now().to_rfc3339();
@@ -111,10 +201,49 @@ impl Task {
     pub fn update_content(&mut self, title: String, description: String) {
         self.title = title;
         self.description = description;
+        self.sync_description_metadata();
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+    }
+
+    pub fn set_description_format(&mut self, format: DescriptionFormat) {
+        self.description_format = format;
+        self.sync_description_metadata();
         self.updated_at = chrono::Utc::now().to_rfc3339();
     }
 
+    // Markdown descriptions feed their #tags and `- [ ]` checklist into the task.
+    // Checklist items match subtasks by title; nothing is removed when a line disappears.
+    fn sync_description_metadata(&mut self) {
+        if self.description_format != DescriptionFormat::Markdown {
+            return;
+        }
+
+        let metadata = crate::markdown::extract(&self.description);
+        for tag in metadata.tags {
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+        for item in metadata.checklist {
+            match self.subtasks.iter_mut().find(|s| s.title == item.title) {
+                Some(subtask) => subtask.completed = item.checked,
+                None => {
+                    let id = self.subtasks.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+                    self.subtasks.push(Subtask {
+                        id,
+                        title: item.title,
+                        completed: item.checked,
+                        created_at: chrono::Utc::now().to_rfc3339(),
+                    });
+                }
+            }
+        }
+    }
+
     pub fn set_due_date(&mut self, due_date: Option<String>) {
+        if due_date != self.due_date {
+            self.reminders_sent.clear();
+        }
         self.due_date = due_date;
         self.updated_at = chrono::Utc::now().to_rfc3339();
     }
@@ -142,6 +271,22 @@ impl Task {
         self.updated_at = chrono::Utc::now().to_rfc3339();
     }
 
+    pub fn add_link_attachment(&mut self, url: String, title: String) -> Attachment {
+        let attachment = Attachment {
+            id: self.attachments.iter().map(|a| a.id).max().unwrap_or(0) + 1,
+            filename: title,
+            file_path: String::new(),
+            file_size: 0,
+            mime_type: "text/uri-list".to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            kind: AttachmentKind::Link,
+            url: Some(url),
+        };
+        self.attachments.push(attachment.clone());
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+        attachment
+    }
+
     pub fn toggle_subtask(&mut self, subtask_id: u32) {
         if let Some(subtask) = self.subtasks.iter_mut().find(|s| s.id == subtask_id) {
             subtask.completed = !subtask.completed;
@@ -150,16 +295,72 @@ impl Task {
     }
 
     pub fn add_comment(&mut self, id: u32, text: String, author: String) {
+        self.push_comment(id, text, author, CommentKind::Note);
+    }
+
+    fn push_comment(&mut self, id: u32, text: String, author: String, kind: CommentKind) {
         let comment = Comment {
             id,
             text,
             author,
             created_at: chrono::Utc::now().to_rfc3339(),
+            kind,
         };
         self.comments.push(comment);
         self.updated_at = chrono::Utc::now().to_rfc3339();
     }
 
+    fn next_comment_id(&self) -> u32 {
+        self.comments.iter().map(|c| c.id).max().unwrap_or(0) + 1
+    }
+
+    pub fn request_review(&mut self, requested_by: String, reviewer: Option<String>) {
+        let text = match &reviewer {
+            Some(reviewer) => format!("Review requested from {}", reviewer),
+            None => "Review requested".to_string(),
+        };
+        self.requires_review = true;
+        self.review_state = ReviewState::Requested;
+        self.reviewer = reviewer;
+        self.push_comment(self.next_comment_id(), text, requested_by, CommentKind::ReviewRequested);
+    }
+
+    // Approval is the sign-off that completes the task
+    pub fn approve(&mut self, reviewer: String, note: Option<String>) {
+        let text = match note {
+            Some(note) => format!("Approved: {}", note),
+            None => "Approved".to_string(),
+        };
+        self.review_state = ReviewState::Approved;
+        self.reviewer = Some(reviewer.clone());
+        self.set_status(TaskStatus::Done);
+        self.push_comment(self.next_comment_id(), text, reviewer, CommentKind::Approved);
+    }
+
+    // Sends the task back to In Progress
+    pub fn reject(&mut self, reviewer: String, reason: String) {
+        self.review_state = ReviewState::Rejected;
+        self.reviewer = Some(reviewer.clone());
+        self.set_status(TaskStatus::InProgress);
+        self.push_comment(self.next_comment_id(), format!("Changes requested: {}", reason), reviewer, CommentKind::Rejected);
+    }
+
+    pub fn assign(&mut self, member_id: Option<u32>) {
+        self.assignee = member_id;
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+    }
+
+    // Watching doesn't touch updated_at, so it doesn't make a stale task look active
+    pub fn watch(&mut self, member_id: u32) {
+        if !self.watchers.contains(&member_id) {
+            self.watchers.push(member_id);
+        }
+    }
+
+    pub fn unwatch(&mut self, member_id: u32) {
+        self.watchers.retain(|id| *id != member_id);
+    }
+
     pub fn add_time(&mut self, minutes: u32) {
         self.time_spent += minutes;
         self.updated_at = chrono::Utc::now().to_rfc3339();
@@ -169,6 +370,31 @@ impl Task {
         self.estimated_time = minutes;
         self.updated_at = chrono::Utc::now().to_rfc3339();
     }
+
+    pub fn set_size(&mut self, points: Option<u32>) {
+        self.size = points;
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+    }
+
+    pub fn set_reminders(&mut self, mut minutes_before: Vec<u32>) {
+        minutes_before.sort_unstable_by(|a, b| b.cmp(a));
+        minutes_before.dedup();
+        self.reminders_sent.retain(|m| minutes_before.contains(m));
+        self.reminders = minutes_before;
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+    }
+
+    pub fn add_dependency(&mut self, task_id: u32) {
+        if !self.dependencies.contains(&task_id) {
+            self.dependencies.push(task_id);
+            self.updated_at = chrono::Utc::now().to_rfc3339();
+        }
+    }
+
+    pub fn remove_dependency(&mut self, task_id: u32) {
+        self.dependencies.retain(|d| *d != task_id);
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -183,6 +409,8 @@ pub struct Project {
     pub is_active: bool,
     pub task_count: u32,
     pub settings: ProjectSettings,
+    #[serde(default)]
+    pub sync_id: String, // The same on every synced device, see sync.rs; given on the first sync
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -192,6 +420,52 @@ pub struct ProjectSettings {
     pub auto_archive_done: bool,
     pub show_completed_tasks: bool,
     pub default_tags: Vec<String>,
+    #[serde(default)]
+    pub notifications: ProjectNotificationSettings,
+    #[serde(default = "default_size_scale")]
+    pub size_scale: Vec<SizeOption>, // Empty allows any number of points
+    #[serde(default)]
+    pub status_mapping: BTreeMap<String, TaskStatus>, // Other trackers' workflow states -> status, for imports
+}
+
+// One step of a project's size scale, e.g. "M" worth 3 points
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SizeOption {
+    pub label: String,
+    pub points: u32,
+}
+
+// T-shirt sizes, weighted like the common Fibonacci points scale
+pub fn default_size_scale() -> Vec<SizeOption> {
+    [("XS", 1), ("S", 2), ("M", 3), ("L", 5), ("XL", 8)].into_iter()
+        .map(|(label, points)| SizeOption { label: label.to_string(), points })
+        .collect()
+}
+
+// Per-project overrides for the webhook notifications; unset toggles follow the global settings
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct ProjectNotificationSettings {
+    pub muted: bool,
+    pub on_task_completed: Option<bool>,
+    pub on_task_overdue: Option<bool>,
+    pub on_project_completed: Option<bool>,
+    pub due_lead_minutes: u32, // Announce tasks this long before they are due; 0 waits until overdue
+    pub reminder_offsets: Vec<u32>, // Minutes before the due date; given to tasks without reminders when they get one
+}
+
+impl ProjectNotificationSettings {
+    pub fn task_completed(&self, global: &WebhookSettings) -> bool {
+        !self.muted && self.on_task_completed.unwrap_or(global.on_task_completed)
+    }
+
+    pub fn task_overdue(&self, global: &WebhookSettings) -> bool {
+        !self.muted && self.on_task_overdue.unwrap_or(global.on_task_overdue)
+    }
+
+    pub fn project_completed(&self, global: &WebhookSettings) -> bool {
+        !self.muted && self.on_project_completed.unwrap_or(global.on_project_completed)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -216,6 +490,7 @@ impl Project {
             is_active: true,
             task_count: 0,
             settings: ProjectSettings::default(),
+            sync_id: String::new(),
         }
     }
 
@@ -232,6 +507,7 @@ impl Project {
             is_active: true,
             task_count: 0,
             settings: ProjectSettings::default(),
+            sync_id: String::new(),
         }
     }
 
@@ -270,6 +546,9 @@ impl Default for ProjectSettings {
             auto_archive_done: false,
             show_completed_tasks: true,
             default_tags: Vec::new(),
+            notifications: ProjectNotificationSettings::default(),
+            size_scale: default_size_scale(),
+            status_mapping: BTreeMap::new(),
         }
     }
 }
@@ -281,6 +560,93 @@ pub struct RoadmapData {
     pub current_project_id: Option<u32>,
     pub theme: Option<String>,
     pub version: String,
+    #[serde(default)]
+    pub settings: AppSettings,
+    #[serde(default)]
+    pub time_log: Vec<TimeSession>,
+    #[serde(default)]
+    pub members: Vec<Member>,
+    #[serde(default)]
+    pub notifications: Vec<Notification>,
+    #[serde(default)]
+    pub last_selection: LastSelection,
+    #[serde(default)]
+    pub onboarding: OnboardingState,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct OnboardingState {
+    pub completed: bool,
+    pub completed_at: Option<String>,
+    pub sample_project_id: Option<u32>, // Project created by create_sample_workspace, if it was used
+}
+
+// Where the user was when they last used this workspace, so the app can reopen there
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct LastSelection {
+    pub project_id: Option<u32>,
+    pub task_id: Option<u32>,
+    pub updated_at: Option<String>,
+}
+
+// A person who can be @mentioned in comments
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Member {
+    pub id: u32,
+    pub name: String,
+    pub handle: String, // Written as @handle; unique, compared case-insensitively
+}
+
+impl Member {
+    pub fn matches(&self, user: &str) -> bool {
+        self.handle.eq_ignore_ascii_case(user) || self.name.eq_ignore_ascii_case(user)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Notification {
+    pub id: u32,
+    pub member_id: u32,
+    pub task_id: u32,
+    pub comment_id: u32,
+    pub author: String,
+    pub excerpt: String,
+    pub created_at: String,
+    pub read: bool,
+}
+
+// One stretch of tracked work on a task, recorded when the timer is stopped
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimeSession {
+    pub id: u32,
+    pub task_id: u32,
+    pub started_at: String,
+    pub ended_at: String,
+    pub minutes: u32,
+}
+
+impl RoadmapData {
+    // True if `task_id` depending on `depends_on` would close a loop in the dependency graph
+    pub fn dependency_creates_cycle(&self, task_id: u32, depends_on: u32) -> bool {
+        let mut stack = vec![depends_on];
+        let mut visited = Vec::new();
+
+        while let Some(current) = stack.pop() {
+            if current == task_id {
+                return true;
+            }
+            if visited.contains(&current) {
+                continue;
+            }
+            visited.push(current);
+
+            if let Some(task) = self.tasks.iter().find(|t| t.id == current) {
+                stack.extend(task.dependencies.iter().copied());
+            }
+        }
+
+        false
+    }
 }
 
 impl Default for RoadmapData {
@@ -293,7 +659,351 @@ impl Default for RoadmapData {
             projects: vec![default_project],
             current_project_id: Some(1),
             theme: Some("light".to_string()),
-            version: "1.0.0".to_string(),
+            version: crate::migrations::CURRENT_VERSION.to_string(),
+            settings: AppSettings::default(),
+            time_log: Vec::new(),
+            members: Vec::new(),
+            notifications: Vec::new(),
+            last_selection: LastSelection::default(),
+            onboarding: OnboardingState::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct AppSettings {
+    #[serde(default)]
+    pub ical_feed: IcalFeedSettings,
+    #[serde(default)]
+    pub webhooks: WebhookSettings,
+    #[serde(default)]
+    pub escalation: EscalationSettings,
+    #[serde(default)]
+    pub locale: LocaleSettings,
+    #[serde(default)]
+    pub capacity: CapacitySettings,
+    #[serde(default)]
+    pub share_board: ShareBoardSettings,
+    #[serde(default)]
+    pub working_days: WorkingDaysSettings,
+    #[serde(default)]
+    pub weekly_review: WeeklyReviewSettings,
+    #[serde(default)]
+    pub inbox: InboxSettings,
+    #[serde(default)]
+    pub email_inbox: EmailInboxSettings,
+    #[serde(default)]
+    pub remote_backup: RemoteBackupSettings,
+    #[serde(default)]
+    pub sync: SyncSettings,
+}
+
+// Recurring reminder to walk through the review packet, e.g. every Friday at 16:00 local time
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct WeeklyReviewSettings {
+    pub enabled: bool,
+    pub weekday: Weekday,
+    pub time: String, // HH:MM
+    pub stale_days: u32, // Open tasks untouched this long are listed as stale
+    pub notify_webhooks: bool, // Also post the reminder to Slack/Discord
+    pub last_reminded_at: Option<String>, // Kept by the scheduler
+}
+
+impl Default for WeeklyReviewSettings {
+    fn default() -> Self {
+        WeeklyReviewSettings {
+            enabled: false,
+            weekday: Weekday::Friday,
+            time: "16:00".to_string(),
+            stale_days: 14,
+            notify_webhooks: false,
+            last_reminded_at: None,
+        }
+    }
+}
+
+// Days nobody works on: deadlines falling on them move to the next working day
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct WorkingDaysSettings {
+    pub weekend: Vec<Weekday>,
+    pub holidays: Vec<Holiday>,
+}
+
+impl Default for WorkingDaysSettings {
+    fn default() -> Self {
+        WorkingDaysSettings {
+            weekend: vec![Weekday::Saturday, Weekday::Sunday],
+            holidays: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Holiday {
+    pub date: String, // YYYY-MM-DD
+    #[serde(default)]
+    pub name: String,
+}
+
+// Local endpoint that automations post new tasks to
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct InboxSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: Option<String>, // Required as a bearer token or ?token= on every request
+    pub project_id: Option<u32>, // None uses a project named "Inbox", created when needed
+}
+
+impl Default for InboxSettings {
+    fn default() -> Self {
+        InboxSettings {
+            enabled: false,
+            port: 8767,
+            token: None,
+            project_id: None,
+        }
+    }
+}
+
+// IMAP folder whose new emails are turned into tasks
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct EmailInboxSettings {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16, // IMAP over TLS
+    pub username: String,
+    pub password: Option<String>, // Kept in the data file; prefer an app password where offered
+    pub folder: String,
+    pub project_id: Option<u32>, // None uses the "Inbox" project
+    pub poll_minutes: u32,
+    pub uid_validity: Option<u32>, // Where the last poll left off in the folder
+    pub last_uid: Option<u32>,
+    pub last_checked_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+impl Default for EmailInboxSettings {
+    fn default() -> Self {
+        EmailInboxSettings {
+            enabled: false,
+            host: String::new(),
+            port: 993,
+            username: String::new(),
+            password: None,
+            folder: "RuidMap".to_string(),
+            project_id: None,
+            poll_minutes: 5,
+            uid_validity: None,
+            last_uid: None,
+            last_checked_at: None,
+            last_error: None,
+        }
+    }
+}
+
+// Backups uploaded to a WebDAV folder or S3-compatible bucket on a schedule
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct RemoteBackupSettings {
+    pub enabled: bool,
+    pub target: Option<RemoteBackupTarget>,
+    pub interval_hours: u32,
+    pub keep: u32, // Older uploads are deleted; 0 keeps them all
+    pub passphrase: Option<String>, // Uploads are encrypted with it when set; without it they can't be restored
+    pub last_backup_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+impl Default for RemoteBackupSettings {
+    fn default() -> Self {
+        RemoteBackupSettings {
+            enabled: false,
+            target: None,
+            interval_hours: 24,
+            keep: 14,
+            passphrase: None,
+            last_backup_at: None,
+            last_error: None,
+        }
+    }
+}
+
+// This device's place in the sync through the remote backup folder, see sync.rs
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct SyncSettings {
+    pub device_id: String, // Made on the first sync
+    pub sequence: u64, // Of the last batch this device uploaded
+    pub last_pushed_at: Option<String>,
+    pub pushed_projects: Vec<String>, // Sync ids as of the last upload, to tell which were deleted since
+    pub pushed_tasks: Vec<String>,
+    pub applied: BTreeMap<String, u64>, // Device id -> last batch applied from it
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RemoteBackupTarget {
+    // A folder URL, e.g. https://cloud.example.com/remote.php/dav/files/me/RuidMap/
+    Webdav {
+        url: String,
+        username: String,
+        password: Option<String>,
+    },
+    S3 {
+        endpoint: String, // e.g. https://s3.eu-central-1.amazonaws.com or http://localhost:9000
+        region: String,
+        bucket: String,
+        #[serde(default)]
+        prefix: String, // Folder inside the bucket
+        access_key_id: String,
+        secret_access_key: Option<String>,
+        #[serde(default)]
+        path_style: bool, // endpoint/bucket/key rather than bucket.endpoint/key, as MinIO expects
+    },
+}
+
+// Read-only live board served over HTTP, for wall displays and standups
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ShareBoardSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub lan: bool, // Listen on all interfaces instead of localhost only
+    pub token: Option<String>, // Required as ?token= on every request
+    pub project_id: Option<u32>, // None follows the current project
+}
+
+impl Default for ShareBoardSettings {
+    fn default() -> Self {
+        ShareBoardSettings {
+            enabled: false,
+            port: 8766,
+            lan: false,
+            token: None,
+            project_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct CapacitySettings {
+    pub weekly_hours: f64,
+    pub weekly_points: f64, // Sizes a week can take; 0 plans hours only
+    pub overrides: Vec<WeekCapacity>, // Holidays, part-time weeks and the like
+}
+
+impl Default for CapacitySettings {
+    fn default() -> Self {
+        CapacitySettings {
+            weekly_hours: 40.0,
+            weekly_points: 0.0,
+            overrides: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WeekCapacity {
+    pub week_start: String, // YYYY-MM-DD, first day of the week per the locale settings
+    pub hours: f64,
+}
+
+// How weeks, fiscal years and dates are shown in reports and notifications
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct LocaleSettings {
+    pub week_start: Weekday,
+    pub date_format: String, // strftime pattern, e.g. "%d.%m.%Y"
+    pub fiscal_year_start_month: u32, // 1 = January
+}
+
+impl Default for LocaleSettings {
+    fn default() -> Self {
+        LocaleSettings {
+            week_start: Weekday::Monday,
+            date_format: "%Y-%m-%d".to_string(),
+            fiscal_year_start_month: 1,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    #[default]
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub fn to_chrono(self) -> chrono::Weekday {
+        match self {
+            Weekday::Monday => chrono::Weekday::Mon,
+            Weekday::Tuesday => chrono::Weekday::Tue,
+            Weekday::Wednesday => chrono::Weekday::Wed,
+            Weekday::Thursday => chrono::Weekday::Thu,
+            Weekday::Friday => chrono::Weekday::Fri,
+            Weekday::Saturday => chrono::Weekday::Sat,
+            Weekday::Sunday => chrono::Weekday::Sun,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct EscalationSettings {
+    pub enabled: bool,
+    pub rules: Vec<EscalationRule>,
+    pub applied: Vec<AppliedEscalation>, // Cleared once a task is no longer overdue
+}
+
+// Fires once per task when it has been overdue for more than `overdue_days`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EscalationRule {
+    #[serde(default)]
+    pub id: u32,
+    pub overdue_days: u32,
+    pub action: EscalationAction,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EscalationAction {
+    BumpPriority,
+    AddTag { tag: String },
+    // Sent through the configured webhooks; same placeholders as the webhook templates plus {days_overdue}
+    Notify { message: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AppliedEscalation {
+    pub rule_id: u32,
+    pub task_id: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IcalFeedSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: Option<String>, // Required as ?token= on every feed request
+}
+
+impl Default for IcalFeedSettings {
+    fn default() -> Self {
+        IcalFeedSettings {
+            enabled: false,
+            port: 8765,
+            token: None,
         }
     }
 }
@@ -336,8 +1046,22 @@ pub struct Comment {
     pub text: String,
     pub author: String, // For future user system
     pub created_at: String,
+    #[serde(default)]
+    pub kind: CommentKind,
 }
 
+// Review comments are added by the review commands, everything else is a note
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommentKind {
+    #[default]
+    Note,
+    ReviewRequested,
+    Approved,
+    Rejected,
+}
+
+// Links keep their title in `filename` and have no file; `file_path` is empty and `file_size` 0
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Attachment {
     pub id: u32,
@@ -346,6 +1070,67 @@ pub struct Attachment {
     pub file_size: u64,
     pub mime_type: String,
     pub created_at: String,
+    #[serde(default)]
+    pub kind: AttachmentKind,
+    #[serde(default)]
+    pub url: Option<String>, // Set for links only
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentKind {
+    #[default]
+    File,
+    Link,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub slack_url: Option<String>,
+    pub discord_url: Option<String>,
+    pub on_task_completed: bool,
+    pub on_task_overdue: bool,
+    pub on_project_completed: bool,
+    pub templates: WebhookTemplates,
+    pub notified_overdue: Vec<u32>, // Tasks already announced as overdue
+    pub notified_due_soon: Vec<u32>, // Tasks already announced as due soon, within a project's lead time
+}
+
+// Placeholders: {task}, {task_id}, {project}, {due_date}, {priority}
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct WebhookTemplates {
+    pub task_completed: String,
+    pub task_overdue: String,
+    pub task_due_soon: String,
+    pub project_completed: String,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        WebhookSettings {
+            slack_url: None,
+            discord_url: None,
+            on_task_completed: true,
+            on_task_overdue: true,
+            on_project_completed: true,
+            templates: WebhookTemplates::default(),
+            notified_overdue: Vec::new(),
+            notified_due_soon: Vec::new(),
+        }
+    }
+}
+
+impl Default for WebhookTemplates {
+    fn default() -> Self {
+        WebhookTemplates {
+            task_completed: "✅ Task completed: {task} ({project})".to_string(),
+            task_overdue: "⏰ Task overdue: {task} ({project}) was due {due_date}".to_string(),
+            task_due_soon: "⏳ Task due soon: {task} ({project}) is due {due_date}".to_string(),
+            project_completed: "🎉 Project {project} is 100% complete!".to_string(),
+        }
+    }
 }
 
 // Project Request Models
@@ -0,0 +1,272 @@
+use crate::csv_import;
+use crate::duration;
+use crate::models::{Project, RoadmapData, Task, TaskStatus};
+use crate::palette;
+use crate::status_mapping;
+use anyhow::{anyhow, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Serialize;
+use std::collections::HashMap;
+
+// OPML outlines for outliners such as Workflowy and OmniOutliner: projects, their tasks and the
+// tasks' subtasks as three levels of <outline>. Notes and completion use the common `_note` and
+// `_complete` attributes; the other task fields get attributes of their own, which outliners keep
+// but ignore.
+
+#[derive(Debug, Serialize, Default)]
+pub struct OpmlImportResult {
+    pub created_projects: usize,
+    pub imported_tasks: usize,
+    pub imported_subtasks: usize,
+}
+
+#[derive(Debug, Default)]
+struct Outline {
+    attributes: HashMap<String, String>,
+    children: Vec<Outline>,
+}
+
+impl Outline {
+    fn text(&self) -> String {
+        match self.attributes.get("text").map(|t| t.trim()) {
+            Some(text) if !text.is_empty() => text.to_string(),
+            _ => "Untitled".to_string(),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(|v| v.trim()).filter(|v| !v.is_empty())
+    }
+}
+
+// Every project, or just `project_id`
+pub fn export(data: &RoadmapData, project_id: Option<u32>) -> Result<String> {
+    let projects: Vec<&Project> = match project_id {
+        Some(id) => vec![data.projects.iter().find(|p| p.id == id).ok_or_else(|| anyhow!("Project with id {} not found", id))?],
+        None => data.projects.iter().collect(),
+    };
+    let title = match projects.as_slice() {
+        [project] => project.name.as_str(),
+        _ => "RuidMap",
+    };
+
+    let mut opml = String::new();
+    opml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    opml.push_str("<opml version=\"2.0\">\n");
+    opml.push_str(&format!("  <head>\n    <title>{}</title>\n    <dateCreated>{}</dateCreated>\n  </head>\n", attribute(title), chrono::Utc::now().to_rfc2822()));
+    opml.push_str("  <body>\n");
+
+    for project in projects {
+        let mut attributes = vec![("text", project.name.clone())];
+        if let Some(description) = project.description.as_ref().filter(|d| !d.is_empty()) {
+            attributes.push(("_note", description.clone()));
+        }
+        opml.push_str(&format!("    <outline{}>\n", attributes_list(&attributes)));
+
+        for task in data.tasks.iter().filter(|t| t.project_id == project.id) {
+            let mut attributes = vec![("text", task.title.clone())];
+            if !task.description.is_empty() {
+                attributes.push(("_note", task.description.clone()));
+            }
+            if task.status == TaskStatus::Done {
+                attributes.push(("_complete", "true".to_string()));
+            }
+            attributes.push(("_status", status_key(&task.status).to_string()));
+            attributes.push(("_priority", task.priority.to_string()));
+            if let Some(due_date) = &task.due_date {
+                attributes.push(("_due_date", due_date.clone()));
+            }
+            if !task.tags.is_empty() {
+                attributes.push(("_tags", task.tags.join(", ")));
+            }
+            if let Some(minutes) = task.estimated_time {
+                attributes.push(("_estimated_time", minutes.to_string()));
+            }
+
+            if task.subtasks.is_empty() {
+                opml.push_str(&format!("      <outline{}/>\n", attributes_list(&attributes)));
+                continue;
+            }
+            opml.push_str(&format!("      <outline{}>\n", attributes_list(&attributes)));
+            for subtask in &task.subtasks {
+                let mut attributes = vec![("text", subtask.title.clone())];
+                if subtask.completed {
+                    attributes.push(("_complete", "true".to_string()));
+                }
+                opml.push_str(&format!("        <outline{}/>\n", attributes_list(&attributes)));
+            }
+            opml.push_str("      </outline>\n");
+        }
+
+        opml.push_str("    </outline>\n");
+    }
+
+    opml.push_str("  </body>\n</opml>\n");
+    Ok(opml)
+}
+
+// Top-level outlines become projects (joining an existing project of the same name) and their
+// children tasks. With `project_id`, the top level is imported as tasks of that project instead.
+// Outlines nested below subtasks are added as further subtasks, since subtasks don't nest.
+pub fn import(data: &mut RoadmapData, content: &str, project_id: Option<u32>) -> Result<OpmlImportResult> {
+    let outlines = parse(content)?;
+    let mut result = OpmlImportResult::default();
+
+    match project_id {
+        Some(id) => {
+            data.projects.iter().find(|p| p.id == id).ok_or_else(|| anyhow!("Project with id {} not found", id))?;
+            for outline in &outlines {
+                add_task(data, id, outline, &mut result)?;
+            }
+        }
+        None => {
+            for outline in &outlines {
+                let name = outline.text();
+                let id = match data.projects.iter().find(|p| p.name.eq_ignore_ascii_case(&name)) {
+                    Some(existing) => existing.id,
+                    None => {
+                        let id = data.projects.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+                        let color = palette::next_color(&data.projects);
+                        data.projects.push(Project::new_with_details(
+                            id,
+                            name,
+                            outline.get("_note").unwrap_or_default().to_string(),
+                            Some(color),
+                            Some(palette::DEFAULT_ICON.to_string()),
+                        ));
+                        result.created_projects += 1;
+                        id
+                    }
+                };
+                for child in &outline.children {
+                    add_task(data, id, child, &mut result)?;
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn add_task(data: &mut RoadmapData, project_id: u32, outline: &Outline, result: &mut OpmlImportResult) -> Result<()> {
+    let id = data.tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    let mut task = Task::new(id, project_id, outline.text(), outline.get("_note").unwrap_or_default().to_string());
+
+    let complete = outline.get("_complete") == Some("true");
+    let status = match outline.get("_status").and_then(|s| status_mapping::status(s, [])) {
+        Some(status) => status,
+        None if complete => TaskStatus::Done,
+        None => TaskStatus::Todo,
+    };
+    if status != task.status {
+        task.update_status(status);
+    }
+    if let Some(priority) = outline.get("_priority").and_then(|p| csv_import::parse_priority(p).ok()) {
+        task.priority = priority;
+    }
+    if let Some(due_date) = outline.get("_due_date") {
+        task.due_date = Some(csv_import::parse_due_date(due_date).map_err(|e| anyhow!("{}: {}", task.title, e))?);
+    }
+    if let Some(tags) = outline.get("_tags") {
+        task.tags = tags.split(',').map(str::trim).filter(|t| !t.is_empty()).map(str::to_string).collect();
+    }
+    if let Some(estimate) = outline.get("_estimated_time") {
+        task.estimated_time = Some(duration::parse_minutes(estimate).map_err(|e| anyhow!("{}: {}", task.title, e))?);
+    }
+
+    let mut subtasks = Vec::new();
+    flatten(&outline.children, &mut subtasks);
+    for (i, subtask) in subtasks.into_iter().enumerate() {
+        task.add_subtask(i as u32 + 1, subtask.text());
+        if subtask.get("_complete") == Some("true") {
+            task.toggle_subtask(i as u32 + 1);
+        }
+        result.imported_subtasks += 1;
+    }
+
+    data.tasks.push(task);
+    result.imported_tasks += 1;
+    Ok(())
+}
+
+fn flatten<'a>(outlines: &'a [Outline], into: &mut Vec<&'a Outline>) {
+    for outline in outlines {
+        into.push(outline);
+        flatten(&outline.children, into);
+    }
+}
+
+// The outlines in <body>, nested
+fn parse(content: &str) -> Result<Vec<Outline>> {
+    let mut reader = Reader::from_str(content.trim_start_matches('\u{feff}'));
+    let mut roots = Vec::new();
+    let mut stack: Vec<Outline> = Vec::new();
+    let mut seen_opml = false;
+
+    loop {
+        let event = reader.read_event()
+            .map_err(|e| anyhow!("Invalid OPML at byte {}: {}", reader.error_position(), e))?;
+        match event {
+            Event::Start(element) if element.name().as_ref() == b"opml" => seen_opml = true,
+            Event::Start(element) if element.name().as_ref() == b"outline" => stack.push(outline(&element)?),
+            Event::Empty(element) if element.name().as_ref() == b"outline" => {
+                let outline = outline(&element)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(outline),
+                    None => roots.push(outline),
+                }
+            }
+            Event::End(element) if element.name().as_ref() == b"outline" => {
+                let outline = stack.pop().ok_or_else(|| anyhow!("Invalid OPML: unexpected </outline>"))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(outline),
+                    None => roots.push(outline),
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !seen_opml {
+        return Err(anyhow!("Not an OPML file"));
+    }
+    if !stack.is_empty() {
+        return Err(anyhow!("Invalid OPML: the file is truncated"));
+    }
+    Ok(roots)
+}
+
+fn outline(element: &BytesStart) -> Result<Outline> {
+    let mut attributes = HashMap::new();
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|e| anyhow!("Invalid OPML: {}", e))?;
+        let name = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+        let value = attribute.unescape_value().map_err(|e| anyhow!("Invalid OPML: {}", e))?;
+        attributes.insert(name, value.to_string());
+    }
+    Ok(Outline { attributes, children: Vec::new() })
+}
+
+fn status_key(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Todo => "todo",
+        TaskStatus::InProgress => "in-progress",
+        TaskStatus::Done => "done",
+    }
+}
+
+fn attributes_list(attributes: &[(&str, String)]) -> String {
+    attributes.iter()
+        .map(|(name, value)| format!(" {}=\"{}\"", name, attribute(value)))
+        .collect()
+}
+
+// Line breaks are written as character references so they survive attribute normalization
+fn attribute(value: &str) -> String {
+    quick_xml::escape::escape(value)
+        .replace('\r', "")
+        .replace('\n', "&#10;")
+        .replace('\t', "&#9;")
+}
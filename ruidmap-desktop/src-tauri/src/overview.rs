@@ -0,0 +1,159 @@
+use crate::calendar::{self, Period};
+use crate::models::{RoadmapData, TaskPriority, TaskStatus};
+use crate::workdays;
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+// Workspace-wide dashboard numbers, built in a single pass over the tasks and one over the time log
+
+const UPCOMING_DAYS: i64 = 14;
+const UPCOMING_LIMIT: usize = 10;
+const ACTIVE_PROJECTS_LIMIT: usize = 5;
+const RECENT_ACTIVITY_DAYS: i64 = 7;
+
+#[derive(Debug, Serialize)]
+pub struct WorkspaceOverview {
+    pub total_tasks: usize,
+    pub total_projects: usize,
+    pub by_status: StatusCounts,
+    pub by_priority: PriorityCounts, // Open tasks only
+    pub overdue_tasks: usize,
+    pub minutes_logged_this_week: u32,
+    pub week_start: String, // YYYY-MM-DD, per the locale settings
+    pub upcoming_deadlines: Vec<UpcomingDeadline>,
+    pub most_active_projects: Vec<ProjectActivity>,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct StatusCounts {
+    pub todo: usize,
+    pub in_progress: usize,
+    pub done: usize,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct PriorityCounts {
+    pub low: usize,
+    pub medium: usize,
+    pub high: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpcomingDeadline {
+    pub task_id: u32,
+    pub title: String,
+    pub project_id: u32,
+    pub project_name: String,
+    pub due_date: String,
+    pub priority: TaskPriority,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectActivity {
+    pub project_id: u32,
+    pub name: String,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub open_tasks: usize,
+    pub recently_updated_tasks: usize, // Updated in the last 7 days
+    pub minutes_logged_this_week: u32,
+}
+
+pub fn overview(data: &RoadmapData, now: DateTime<Utc>) -> WorkspaceOverview {
+    let today = now.with_timezone(&Local).date_naive();
+    let (week_start, _) = calendar::period_bounds(Period::Week, today, &data.settings.locale);
+    let recent_since = now - Duration::days(RECENT_ACTIVITY_DAYS);
+    let upcoming_until = now + Duration::days(UPCOMING_DAYS);
+    let parse = |timestamp: &str| DateTime::parse_from_rfc3339(timestamp).ok().map(|d| d.with_timezone(&Utc));
+
+    let mut projects: Vec<ProjectActivity> = data.projects.iter().map(|p| ProjectActivity {
+        project_id: p.id,
+        name: p.name.clone(),
+        color: p.color.clone(),
+        icon: p.icon.clone(),
+        open_tasks: 0,
+        recently_updated_tasks: 0,
+        minutes_logged_this_week: 0,
+    }).collect();
+    let positions: HashMap<u32, usize> = data.projects.iter().enumerate().map(|(i, p)| (p.id, i)).collect();
+    let mut task_projects: HashMap<u32, u32> = HashMap::with_capacity(data.tasks.len());
+
+    let mut by_status = StatusCounts::default();
+    let mut by_priority = PriorityCounts::default();
+    let mut overdue_tasks = 0;
+    let mut upcoming: Vec<(DateTime<Utc>, UpcomingDeadline)> = Vec::new();
+
+    for task in &data.tasks {
+        task_projects.insert(task.id, task.project_id);
+        if let Some(&i) = positions.get(&task.project_id) {
+            if task.status != TaskStatus::Done {
+                projects[i].open_tasks += 1;
+            }
+            if parse(&task.updated_at).is_some_and(|updated| updated >= recent_since) {
+                projects[i].recently_updated_tasks += 1;
+            }
+        }
+
+        match task.status {
+            TaskStatus::Todo => by_status.todo += 1,
+            TaskStatus::InProgress => by_status.in_progress += 1,
+            TaskStatus::Done => by_status.done += 1,
+        }
+        if task.status == TaskStatus::Done {
+            continue;
+        }
+
+        match task.priority {
+            TaskPriority::Low => by_priority.low += 1,
+            TaskPriority::Medium => by_priority.medium += 1,
+            TaskPriority::High => by_priority.high += 1,
+        }
+        if workdays::is_overdue(task, now, &data.settings.working_days) {
+            overdue_tasks += 1;
+            continue;
+        }
+        if let Some(due) = task.due_date.as_deref().and_then(parse).filter(|due| *due <= upcoming_until) {
+            upcoming.push((due, UpcomingDeadline {
+                task_id: task.id,
+                title: task.title.clone(),
+                project_id: task.project_id,
+                project_name: positions.get(&task.project_id).map(|&i| projects[i].name.clone()).unwrap_or_default(),
+                due_date: task.due_date.clone().unwrap_or_default(),
+                priority: task.priority.clone(),
+            }));
+        }
+    }
+
+    let mut minutes_logged_this_week = 0;
+    for session in &data.time_log {
+        if calendar::local_date(&session.started_at).is_none_or(|day| day < week_start) {
+            continue;
+        }
+        minutes_logged_this_week += session.minutes;
+        let position = task_projects.get(&session.task_id).and_then(|id| positions.get(id));
+        if let Some(&i) = position {
+            projects[i].minutes_logged_this_week += session.minutes;
+        }
+    }
+
+    upcoming.sort_by_key(|(due, _)| *due);
+    upcoming.truncate(UPCOMING_LIMIT);
+
+    projects.retain(|p| p.recently_updated_tasks > 0 || p.minutes_logged_this_week > 0);
+    projects.sort_by_key(|p| (Reverse(p.minutes_logged_this_week), Reverse(p.recently_updated_tasks), p.project_id));
+    projects.truncate(ACTIVE_PROJECTS_LIMIT);
+
+    WorkspaceOverview {
+        total_tasks: data.tasks.len(),
+        total_projects: data.projects.len(),
+        by_status,
+        by_priority,
+        overdue_tasks,
+        minutes_logged_this_week,
+        week_start: week_start.format("%Y-%m-%d").to_string(),
+        upcoming_deadlines: upcoming.into_iter().map(|(_, deadline)| deadline).collect(),
+        most_active_projects: projects,
+    }
+}
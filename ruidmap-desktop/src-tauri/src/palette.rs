@@ -0,0 +1,94 @@
+use crate::models::Project;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+// Project colors and icons offered by the UI, and validation of user-provided ones
+
+#[derive(Debug, Serialize, Clone, Copy)]
+pub struct PaletteColor {
+    pub hex: &'static str,
+    pub name: &'static str,
+}
+
+pub const COLORS: &[PaletteColor] = &[
+    PaletteColor { hex: "#6366f1", name: "Indigo" },
+    PaletteColor { hex: "#8b5cf6", name: "Violet" },
+    PaletteColor { hex: "#ec4899", name: "Pink" },
+    PaletteColor { hex: "#ef4444", name: "Red" },
+    PaletteColor { hex: "#f97316", name: "Orange" },
+    PaletteColor { hex: "#eab308", name: "Yellow" },
+    PaletteColor { hex: "#22c55e", name: "Green" },
+    PaletteColor { hex: "#14b8a6", name: "Teal" },
+    PaletteColor { hex: "#06b6d4", name: "Cyan" },
+    PaletteColor { hex: "#3b82f6", name: "Blue" },
+];
+
+pub const ICONS: &[&str] = &["📁", "💼", "🎯", "⚡", "🔥", "🌟", "🚀", "💡", "🎨", "🔧"];
+
+pub const DEFAULT_ICON: &str = "📁";
+
+// Longest icon accepted, enough for ZWJ sequences like 👩‍💻 and flags
+const MAX_ICON_CHARS: usize = 8;
+
+#[derive(Debug, Serialize)]
+pub struct ColorPalette {
+    pub colors: Vec<PaletteColor>,
+    pub icons: Vec<&'static str>,
+    pub suggested_color: String, // What a new project would get if no color is picked
+}
+
+pub fn palette(projects: &[Project]) -> ColorPalette {
+    ColorPalette {
+        colors: COLORS.to_vec(),
+        icons: ICONS.to_vec(),
+        suggested_color: next_color(projects),
+    }
+}
+
+// `#rgb` or `#rrggbb`, returned as lowercase `#rrggbb`
+pub fn normalize_color(color: &str) -> Result<String> {
+    let hex = color.trim().strip_prefix('#')
+        .filter(|h| h.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| anyhow!("Invalid color \"{}\", expected a hex color like #6366f1", color))?
+        .to_ascii_lowercase();
+
+    match hex.len() {
+        6 => Ok(format!("#{}", hex)),
+        3 => Ok(format!("#{}", hex.chars().flat_map(|c| [c, c]).collect::<String>())),
+        _ => Err(anyhow!("Invalid color \"{}\", expected a hex color like #6366f1", color)),
+    }
+}
+
+// Icons are a single emoji; there's no grapheme segmentation here, so this checks
+// for a short run of non-ASCII characters containing at least one pictograph
+pub fn validate_icon(icon: &str) -> Result<String> {
+    let icon = icon.trim();
+    let chars: Vec<char> = icon.chars().collect();
+    let valid = !chars.is_empty()
+        && chars.len() <= MAX_ICON_CHARS
+        && chars.iter().all(|c| !c.is_ascii() && !c.is_whitespace())
+        && chars.iter().any(|c| is_pictograph(*c));
+    if !valid {
+        return Err(anyhow!("Invalid icon \"{}\", expected a single emoji", icon));
+    }
+    Ok(icon.to_string())
+}
+
+fn is_pictograph(c: char) -> bool {
+    matches!(c as u32,
+        0x2190..=0x2BFF     // Arrows, technical symbols, dingbats
+        | 0x1F000..=0x1FAFF // Emoji blocks, including regional indicator flags
+    )
+}
+
+// First palette color no project uses yet, otherwise the least used one
+pub fn next_color(projects: &[Project]) -> String {
+    let uses = |hex: &str| projects.iter()
+        .filter(|p| p.color.as_deref().map(|c| c.eq_ignore_ascii_case(hex)).unwrap_or(false))
+        .count();
+
+    COLORS.iter()
+        .min_by_key(|color| uses(color.hex))
+        .map(|color| color.hex.to_string())
+        .unwrap_or_else(|| COLORS[0].hex.to_string())
+}
@@ -0,0 +1,210 @@
+use crate::models::RoadmapData;
+use crate::storage::{self, StorageFormat};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Background writer for the data file. Saves only hand over a snapshot, so commands no longer do
+// disk I/O while holding the storage lock. Snapshots arriving in quick succession are coalesced
+// into a single write of the newest one, and failed writes are retried with exponential backoff.
+// A save therefore succeeds before the data is on disk: callers that must know it got there call
+// `flush`, and everyone else learns of failures through the listener.
+
+// A write waits this long for newer snapshots...
+const COALESCE_WINDOW: Duration = Duration::from_millis(200);
+// ...but is never held back longer than this, however busy things get
+const MAX_WRITE_DELAY: Duration = Duration::from_secs(2);
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+// Attempts made for the last snapshot when the worker shuts down
+const FINAL_ATTEMPTS: u32 = 3;
+
+enum Job {
+    Write(Box<RoadmapData>, StorageFormat),
+    Flush(Sender<Result<(), String>>),
+}
+
+// Called with the new status after every failed write and after the write that ends a run of failures
+pub type Listener = Box<dyn Fn(&PersistenceStatus) + Send>;
+// Shared by the storage and its workers, so a listener set once survives a restarted worker
+pub type ListenerSlot = Arc<Mutex<Option<Listener>>>;
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct PersistenceStatus {
+    pub pending: bool, // A snapshot is waiting to be written
+    pub last_saved_at: Option<String>,
+    pub last_error: Option<String>, // Cleared by the next successful write
+    pub failed_attempts: u32, // Consecutive failures
+    pub saves: u64,
+    pub coalesced: u64, // Snapshots replaced by a newer one before they reached the disk
+}
+
+pub struct PersistenceWorker {
+    sender: Option<Sender<Job>>,
+    status: Arc<Mutex<PersistenceStatus>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl PersistenceWorker {
+    pub fn start(path: PathBuf, listener: ListenerSlot) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let status = Arc::new(Mutex::new(PersistenceStatus::default()));
+        let worker_status = Arc::clone(&status);
+        let handle = thread::spawn(move || run(path, receiver, worker_status, listener));
+
+        PersistenceWorker {
+            sender: Some(sender),
+            status,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn enqueue(&self, data: RoadmapData, format: StorageFormat) -> Result<()> {
+        self.send(Job::Write(Box::new(data), format))
+    }
+
+    // Blocks until everything queued so far is on disk, or the attempt to write it failed
+    pub fn flush(&self) -> Result<()> {
+        let (reply, done) = mpsc::channel();
+        self.send(Job::Flush(reply))?;
+        done.recv()
+            .map_err(|_| anyhow!("Persistence worker stopped"))?
+            .map_err(|e| anyhow!(e))
+    }
+
+    pub fn status(&self) -> PersistenceStatus {
+        self.status.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    fn send(&self, job: Job) -> Result<()> {
+        self.sender.as_ref()
+            .and_then(|sender| sender.send(job).ok())
+            .ok_or_else(|| anyhow!("Persistence worker stopped"))
+    }
+}
+
+impl Drop for PersistenceWorker {
+    // Closing the channel makes the worker write what is left and exit
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn run(path: PathBuf, receiver: Receiver<Job>, status: Arc<Mutex<PersistenceStatus>>, listener: ListenerSlot) {
+    let update = |change: &dyn Fn(&mut PersistenceStatus)| {
+        if let Ok(mut status) = status.lock() {
+            change(&mut status);
+        }
+    };
+    let notify = || {
+        let current = status.lock().map(|s| s.clone()).unwrap_or_default();
+        if let Ok(listener) = listener.lock() {
+            if let Some(listener) = listener.as_ref() {
+                listener(&current);
+            }
+        }
+    };
+
+    let mut pending: Option<(Box<RoadmapData>, StorageFormat)> = None;
+    let mut flushes: Vec<Sender<Result<(), String>>> = Vec::new();
+    let mut first_queued = Instant::now();
+    let mut last_queued = Instant::now();
+    let mut retry_at: Option<Instant> = None;
+    let mut backoff = INITIAL_BACKOFF;
+
+    loop {
+        let deadline = pending.as_ref().map(|_| {
+            retry_at.unwrap_or_else(|| (last_queued + COALESCE_WINDOW).min(first_queued + MAX_WRITE_DELAY))
+        });
+        let job = match deadline {
+            None => match receiver.recv() {
+                Ok(job) => Some(job),
+                Err(_) => break,
+            },
+            Some(deadline) => match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(job) => Some(job),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            },
+        };
+
+        match job {
+            Some(Job::Write(data, format)) => {
+                let now = Instant::now();
+                if pending.replace((data, format)).is_some() {
+                    update(&|s| s.coalesced += 1);
+                } else {
+                    first_queued = now;
+                }
+                last_queued = now;
+                update(&|s| s.pending = true);
+                continue;
+            }
+            Some(Job::Flush(reply)) => {
+                if pending.is_none() {
+                    let _ = reply.send(Ok(()));
+                    continue;
+                }
+                flushes.push(reply);
+            }
+            // Quiet for long enough, or time to retry
+            None => {}
+        }
+
+        let Some((data, format)) = pending.as_ref() else {
+            continue;
+        };
+        match storage::write_file(&path, *format, data) {
+            Ok(()) => {
+                pending = None;
+                let recovered = retry_at.take().is_some();
+                backoff = INITIAL_BACKOFF;
+                let saved_at = chrono::Utc::now().to_rfc3339();
+                update(&|s| {
+                    s.pending = false;
+                    s.last_saved_at = Some(saved_at.clone());
+                    s.last_error = None;
+                    s.failed_attempts = 0;
+                    s.saves += 1;
+                });
+                for reply in flushes.drain(..) {
+                    let _ = reply.send(Ok(()));
+                }
+                if recovered {
+                    notify();
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to save data, retrying in {:?}: {}", backoff, e);
+                let message = e.to_string();
+                update(&|s| {
+                    s.last_error = Some(message.clone());
+                    s.failed_attempts += 1;
+                });
+                for reply in flushes.drain(..) {
+                    let _ = reply.send(Err(message.clone()));
+                }
+                notify();
+                retry_at = Some(Instant::now() + backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+
+    if let Some((data, format)) = pending {
+        for attempt in 1..=FINAL_ATTEMPTS {
+            match storage::write_file(&path, format, &data) {
+                Ok(()) => break,
+                Err(e) => eprintln!("Failed to save data on shutdown (attempt {}): {}", attempt, e),
+            }
+            thread::sleep(INITIAL_BACKOFF);
+        }
+    }
+}
@@ -0,0 +1,546 @@
+use crate::commands::AppState;
+use crate::envelope;
+use crate::models::{RemoteBackupSettings, RemoteBackupTarget};
+use crate::secrets;
+use crate::storage::Storage;
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDateTime, Utc};
+use hmac::{Hmac, Mac};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+// Backups uploaded to a WebDAV folder or an S3-compatible bucket, in the same format as local
+// backups, or sealed in an encrypted envelope when a passphrase is set. Each kind of target
+// implements `RemoteStore`. Uploads are named after the time they
+// were made; only files named that way are listed, pruned or restored, so the folder or bucket
+// can hold other files too.
+
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+const MAX_BACKUP_BYTES: u64 = 512 * 1024 * 1024;
+const NAME_PREFIX: &str = "ruidmap-backup-";
+const NAME_SUFFIX: &str = ".json";
+const NAME_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+// Keeps the scheduled upload and backup_to_remote_now from running at the same time
+static UPLOADING: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RemoteBackup {
+    pub id: String, // The file name, as restore_from_remote takes it
+    pub size: u64,
+    pub created_at: Option<String>,
+}
+
+// A folder of files somewhere; names are relative to it
+pub trait RemoteStore {
+    fn upload(&self, name: &str, content: &[u8]) -> Result<()>;
+    fn download(&self, name: &str) -> Result<Vec<u8>>;
+    // Names and sizes of the files starting with `prefix`
+    fn list(&self, prefix: &str) -> Result<Vec<(String, u64)>>;
+    fn delete(&self, name: &str) -> Result<()>;
+}
+
+pub fn store(target: &RemoteBackupTarget) -> Result<Box<dyn RemoteStore>> {
+    match target {
+        RemoteBackupTarget::Webdav { url, username, password } => {
+            Ok(Box::new(Webdav::new(url, username, password.as_deref())?))
+        }
+        RemoteBackupTarget::S3 { endpoint, region, bucket, prefix, access_key_id, secret_access_key, path_style } => {
+            let secret = secret_access_key.as_deref().ok_or_else(|| anyhow!("No secret access key set"))?;
+            Ok(Box::new(S3::new(endpoint, region, bucket, prefix, access_key_id, secret, *path_style)?))
+        }
+    }
+}
+
+pub fn validate(settings: &RemoteBackupSettings) -> Result<()> {
+    if settings.interval_hours == 0 {
+        return Err(anyhow!("The backup interval must be at least one hour"));
+    }
+    match &settings.target {
+        Some(target) => {
+            store(target)?;
+        }
+        None if settings.enabled => return Err(anyhow!("Choose where to upload backups")),
+        None => {}
+    }
+    Ok(())
+}
+
+pub fn spawn_backup_scheduler(app: AppHandle) {
+    thread::spawn(move || loop {
+        if let Err(e) = check(&app) {
+            eprintln!("Scheduled remote backup failed: {}", e);
+        }
+        thread::sleep(SCHEDULE_CHECK_INTERVAL);
+    });
+}
+
+// Uploads when enabled and due; tells the frontend through remote-backup-completed or remote-backup-failed
+fn check(app: &AppHandle) -> Result<()> {
+    let state = app.state::<AppState>();
+    let settings = {
+        let storage = state.0.read().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+        if storage.is_read_only() {
+            return Ok(());
+        }
+        storage.data().settings.remote_backup.clone()
+    };
+    if !settings.enabled || !is_due(&settings, Utc::now()) {
+        return Ok(());
+    }
+
+    match backup_now(app) {
+        Ok(backup) => app.emit("remote-backup-completed", backup).map_err(|e| anyhow!("Failed to send the event: {}", e)),
+        Err(e) => {
+            let _ = app.emit("remote-backup-failed", e.to_string());
+            Err(e)
+        }
+    }
+}
+
+fn is_due(settings: &RemoteBackupSettings, now: DateTime<Utc>) -> bool {
+    let last = settings.last_backup_at.as_deref().and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+    match last {
+        Some(last) => now - last.with_timezone(&Utc) >= ChronoDuration::hours(settings.interval_hours as i64),
+        None => true,
+    }
+}
+
+// Uploads a backup of the current data and deletes the ones beyond `keep`. The storage lock is
+// not held during the upload.
+pub fn backup_now(app: &AppHandle) -> Result<RemoteBackup> {
+    let _uploading = UPLOADING.lock().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    let state = app.state::<AppState>();
+    let (settings, content) = {
+        let storage = state.0.read().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+        // The upload couldn't be recorded
+        storage.check_writable()?;
+        (storage.data().settings.remote_backup.clone(), storage.backup_bytes()?)
+    };
+
+    let uploaded = upload(&settings, &content);
+
+    let mut storage = state.0.write().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    let mut data = storage.load_data()?;
+    let remote_backup = &mut data.settings.remote_backup;
+    match &uploaded {
+        Ok(backup) => {
+            remote_backup.last_backup_at = backup.created_at.clone();
+            remote_backup.last_error = None;
+        }
+        Err(e) => remote_backup.last_error = Some(e.to_string()),
+    }
+    storage.save_data(&data)?;
+    uploaded
+}
+
+fn upload(settings: &RemoteBackupSettings, content: &[u8]) -> Result<RemoteBackup> {
+    let target = settings.target.as_ref().ok_or_else(|| anyhow!("No remote backup target is set"))?;
+    let store = store(target)?;
+    let content = match settings.passphrase.as_deref().filter(|p| !p.is_empty()) {
+        Some(passphrase) => envelope::seal(content, passphrase)?,
+        None => content.to_vec(),
+    };
+    let now = Utc::now();
+    let name = format!("{}{}{}", NAME_PREFIX, now.format(NAME_TIME_FORMAT), NAME_SUFFIX);
+    store.upload(&name, &content)?;
+
+    if settings.keep > 0 {
+        // The upload went through either way, so a failed cleanup is only logged
+        let pruned = list_in(store.as_ref()).and_then(|backups| {
+            backups.iter()
+                .skip(settings.keep as usize)
+                .try_for_each(|old| store.delete(&old.id))
+        });
+        if let Err(e) = pruned {
+            eprintln!("Failed to delete old remote backups: {}", e);
+        }
+    }
+
+    Ok(RemoteBackup {
+        id: name,
+        size: content.len() as u64,
+        created_at: Some(now.to_rfc3339()),
+    })
+}
+
+// Newest first
+pub fn list(settings: &RemoteBackupSettings) -> Result<Vec<RemoteBackup>> {
+    let target = settings.target.as_ref().ok_or_else(|| anyhow!("No remote backup target is set"))?;
+    list_in(store(target)?.as_ref())
+}
+
+fn list_in(store: &dyn RemoteStore) -> Result<Vec<RemoteBackup>> {
+    let mut backups: Vec<RemoteBackup> = store.list(NAME_PREFIX)?
+        .into_iter()
+        .filter_map(|(name, size)| {
+            let time = name.strip_prefix(NAME_PREFIX)?.strip_suffix(NAME_SUFFIX)?;
+            let created_at = NaiveDateTime::parse_from_str(time, NAME_TIME_FORMAT).ok()?.and_utc();
+            Some(RemoteBackup { id: name, size, created_at: Some(created_at.to_rfc3339()) })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(backups)
+}
+
+// Replaces the data with an uploaded backup. The remote backup settings are kept, since the
+// backup may predate the target or its credentials.
+pub fn restore(app: &AppHandle, backup_id: &str) -> Result<()> {
+    let state = app.state::<AppState>();
+    let settings = {
+        let storage = state.0.read().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+        storage.data().settings.remote_backup.clone()
+    };
+    let target = settings.target.as_ref().ok_or_else(|| anyhow!("No remote backup target is set"))?;
+    let store = store(target)?;
+    if !list_in(store.as_ref())?.iter().any(|b| b.id == backup_id) {
+        return Err(anyhow!("Backup \"{}\" not found", backup_id));
+    }
+    let mut content = store.download(backup_id)?;
+    if envelope::is_sealed(&content) {
+        let passphrase = settings.passphrase.as_deref().filter(|p| !p.is_empty())
+            .ok_or_else(|| anyhow!("The backup is encrypted; set the passphrase it was made with"))?;
+        content = envelope::open(&content, passphrase)?;
+    }
+    let mut backup = Storage::parse_backup(&content)?;
+
+    let mut storage = state.0.write().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    secrets::carry_over(storage.data(), &mut backup);
+    backup.settings.remote_backup = storage.data().settings.remote_backup.clone();
+    storage.save_data(&backup)?;
+    storage.flush()
+}
+
+// WebDAV (Nextcloud, ownCloud, Synology, Box, ...)
+
+struct Webdav {
+    agent: ureq::Agent,
+    url: String, // Ends with a slash
+    auth: Option<String>,
+}
+
+impl Webdav {
+    fn new(url: &str, username: &str, password: Option<&str>) -> Result<Self> {
+        let url = url.trim();
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(anyhow!("The WebDAV URL must start with https:// or http://"));
+        }
+        let auth = (!username.trim().is_empty()).then(|| {
+            let credentials = format!("{}:{}", username.trim(), password.unwrap_or_default());
+            format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(credentials))
+        });
+        Ok(Webdav {
+            agent: agent(),
+            url: format!("{}/", url.trim_end_matches('/')),
+            auth,
+        })
+    }
+
+    fn request(&self, method: &str, name: &str) -> ureq::Request {
+        let request = self.agent.request(method, &format!("{}{}", self.url, encode(name)));
+        match &self.auth {
+            Some(auth) => request.set("Authorization", auth),
+            None => request,
+        }
+    }
+}
+
+impl RemoteStore for Webdav {
+    fn upload(&self, name: &str, content: &[u8]) -> Result<()> {
+        match self.request("PUT", name).set("Content-Type", "application/json").send_bytes(content) {
+            // The folder doesn't exist yet
+            Err(ureq::Error::Status(404 | 409, _)) => {
+                self.request("MKCOL", "").call().map_err(request_error)?;
+                self.request("PUT", name).set("Content-Type", "application/json").send_bytes(content).map_err(request_error)?;
+            }
+            result => {
+                result.map_err(request_error)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn download(&self, name: &str) -> Result<Vec<u8>> {
+        read_body(self.request("GET", name).call().map_err(request_error)?)
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<(String, u64)>> {
+        let body = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+            <d:propfind xmlns:d=\"DAV:\"><d:prop><d:getcontentlength/></d:prop></d:propfind>";
+        let response = self.request("PROPFIND", "")
+            .set("Depth", "1")
+            .set("Content-Type", "application/xml")
+            .send_string(body);
+        let response = match response {
+            // Nothing uploaded yet
+            Err(ureq::Error::Status(404, _)) => return Ok(Vec::new()),
+            response => response.map_err(request_error)?,
+        };
+        let xml = String::from_utf8_lossy(&read_body(response)?).to_string();
+
+        Ok(xml_records(&xml, "response", &["href", "getcontentlength"])?
+            .into_iter()
+            .filter_map(|record| {
+                // The folder itself and subfolders end with a slash
+                let href = record.get("href")?.trim();
+                let name = href.rsplit('/').next().filter(|n| n.starts_with(prefix))?;
+                let size = record.get("getcontentlength").and_then(|s| s.trim().parse().ok()).unwrap_or(0);
+                Some((name.to_string(), size))
+            })
+            .collect())
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        self.request("DELETE", name).call().map_err(request_error)?;
+        Ok(())
+    }
+}
+
+// S3 and S3-compatible storage (MinIO, Backblaze B2, Wasabi, Cloudflare R2, ...), with
+// Signature Version 4
+
+struct S3 {
+    agent: ureq::Agent,
+    scheme: String,
+    host: String, // Including the bucket unless path style is used
+    region: String,
+    bucket: String,
+    prefix: String, // Empty, or ends with a slash
+    access_key_id: String,
+    secret_access_key: String,
+    path_style: bool,
+}
+
+impl S3 {
+    fn new(endpoint: &str, region: &str, bucket: &str, prefix: &str, access_key_id: &str, secret_access_key: &str, path_style: bool) -> Result<Self> {
+        let endpoint = endpoint.trim().trim_end_matches('/');
+        let (scheme, host) = endpoint.split_once("://")
+            .filter(|(scheme, _)| *scheme == "https" || *scheme == "http")
+            .ok_or_else(|| anyhow!("The S3 endpoint must start with https:// or http://"))?;
+        if host.is_empty() || host.contains('/') {
+            return Err(anyhow!("The S3 endpoint must be just the server, e.g. https://s3.eu-central-1.amazonaws.com"));
+        }
+        if bucket.trim().is_empty() || region.trim().is_empty() || access_key_id.trim().is_empty() {
+            return Err(anyhow!("Set the bucket, region and access key"));
+        }
+        // Requests are signed with the Host header ureq sends, which leaves out default ports
+        let host = match scheme {
+            "https" => host.strip_suffix(":443").unwrap_or(host),
+            _ => host.strip_suffix(":80").unwrap_or(host),
+        };
+        let prefix = prefix.trim().trim_matches('/');
+
+        Ok(S3 {
+            agent: agent(),
+            scheme: scheme.to_string(),
+            host: if path_style { host.to_string() } else { format!("{}.{}", bucket.trim(), host) },
+            region: region.trim().to_string(),
+            bucket: bucket.trim().to_string(),
+            prefix: if prefix.is_empty() { String::new() } else { format!("{}/", prefix) },
+            access_key_id: access_key_id.trim().to_string(),
+            secret_access_key: secret_access_key.trim().to_string(),
+            path_style,
+        })
+    }
+
+    // A signed request for `key`, or for the bucket itself
+    fn request(&self, method: &str, key: Option<&str>, query: &[(&str, &str)], payload: &[u8]) -> ureq::Request {
+        let mut path = if self.path_style { format!("/{}", encode(&self.bucket)) } else { String::new() };
+        match key {
+            Some(key) => {
+                let key: Vec<String> = key.split('/').map(encode).collect();
+                path.push('/');
+                path.push_str(&key.join("/"));
+            }
+            None if path.is_empty() => path.push('/'),
+            None => {}
+        }
+        let mut query: Vec<(String, String)> = query.iter().map(|(k, v)| (encode(k), encode(v))).collect();
+        query.sort();
+        let query: Vec<String> = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        let query = query.join("&");
+
+        let now = Utc::now();
+        let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex(&Sha256::digest(payload));
+        let canonical_request = format!(
+            "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method, path, query, self.host, payload_hash, timestamp, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            timestamp, scope, hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let mut signing_key = hmac(format!("AWS4{}", self.secret_access_key).as_bytes(), date.as_bytes());
+        for part in [self.region.as_str(), "s3", "aws4_request"] {
+            signing_key = hmac(&signing_key, part.as_bytes());
+        }
+        let signature = hex(&hmac(&signing_key, string_to_sign.as_bytes()));
+
+        let url = match query.as_str() {
+            "" => format!("{}://{}{}", self.scheme, self.host, path),
+            query => format!("{}://{}{}?{}", self.scheme, self.host, path, query),
+        };
+        self.agent.request(method, &url)
+            .set("x-amz-date", &timestamp)
+            .set("x-amz-content-sha256", &payload_hash)
+            .set("Authorization", &format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+                self.access_key_id, scope, signature
+            ))
+    }
+
+    fn key(&self, name: &str) -> String {
+        format!("{}{}", self.prefix, name)
+    }
+}
+
+impl RemoteStore for S3 {
+    fn upload(&self, name: &str, content: &[u8]) -> Result<()> {
+        self.request("PUT", Some(&self.key(name)), &[], content)
+            .set("Content-Type", "application/json")
+            .send_bytes(content)
+            .map_err(request_error)?;
+        Ok(())
+    }
+
+    fn download(&self, name: &str) -> Result<Vec<u8>> {
+        read_body(self.request("GET", Some(&self.key(name)), &[], &[]).call().map_err(request_error)?)
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<(String, u64)>> {
+        let prefix = self.key(prefix);
+        let mut objects = Vec::new();
+        let mut continuation: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", prefix.as_str())];
+            if let Some(token) = &continuation {
+                query.push(("continuation-token", token.as_str()));
+            }
+            let response = self.request("GET", None, &query, &[]).call().map_err(request_error)?;
+            let xml = String::from_utf8_lossy(&read_body(response)?).to_string();
+
+            for object in xml_records(&xml, "Contents", &["Key", "Size"])? {
+                let Some(name) = object.get("Key").and_then(|key| key.strip_prefix(&self.prefix)) else {
+                    continue;
+                };
+                let size = object.get("Size").and_then(|s| s.trim().parse().ok()).unwrap_or(0);
+                objects.push((name.to_string(), size));
+            }
+
+            let page = xml_records(&xml, "ListBucketResult", &["IsTruncated", "NextContinuationToken"])?;
+            let page = page.first();
+            continuation = match page.and_then(|p| p.get("NextContinuationToken")) {
+                Some(token) if page.and_then(|p| p.get("IsTruncated")).map(|t| t.trim()) == Some("true") => Some(token.trim().to_string()),
+                _ => break,
+            };
+        }
+        Ok(objects)
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        self.request("DELETE", Some(&self.key(name)), &[], &[]).call().map_err(request_error)?;
+        Ok(())
+    }
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+}
+
+fn request_error(e: ureq::Error) -> anyhow::Error {
+    match e {
+        ureq::Error::Status(401 | 403, _) => anyhow!("The server rejected the credentials"),
+        ureq::Error::Status(code, response) => {
+            let status = response.status_text().to_string();
+            // S3 explains errors in <Message>
+            let body = response.into_string().unwrap_or_default();
+            let message = body.split_once("<Message>")
+                .and_then(|(_, rest)| rest.split_once("</Message>"))
+                .map(|(message, _)| message.to_string())
+                .unwrap_or(status);
+            anyhow!("The server answered {}: {}", code, message)
+        }
+        e => anyhow!("Request failed: {}", e),
+    }
+}
+
+fn read_body(response: ureq::Response) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    response.into_reader().take(MAX_BACKUP_BYTES + 1).read_to_end(&mut body)?;
+    if body.len() as u64 > MAX_BACKUP_BYTES {
+        return Err(anyhow!("The response is larger than {} MB", MAX_BACKUP_BYTES / 1024 / 1024));
+    }
+    Ok(body)
+}
+
+// The text of `fields` inside each `record` element, matched by local name so any namespace
+// prefix (d:, D:, lp1:) works
+fn xml_records(xml: &str, record: &str, fields: &[&str]) -> Result<Vec<HashMap<String, String>>> {
+    let mut reader = Reader::from_str(xml);
+    let mut records = Vec::new();
+    let mut current: Option<HashMap<String, String>> = None;
+    let mut field: Option<String> = None;
+
+    loop {
+        let event = reader.read_event()
+            .map_err(|e| anyhow!("Unexpected response from the server: {}", e))?;
+        match event {
+            Event::Start(element) => {
+                let name = String::from_utf8_lossy(element.local_name().as_ref()).to_string();
+                if name == record {
+                    current = Some(HashMap::new());
+                } else if current.is_some() && fields.contains(&name.as_str()) {
+                    field = Some(name);
+                }
+            }
+            Event::Text(text) => {
+                if let (Some(current), Some(field)) = (current.as_mut(), &field) {
+                    let text = text.unescape().map_err(|e| anyhow!("Unexpected response from the server: {}", e))?;
+                    current.entry(field.clone()).or_default().push_str(&text);
+                }
+            }
+            Event::End(element) => {
+                if element.local_name().as_ref() == record.as_bytes() {
+                    records.extend(current.take());
+                }
+                field = None;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(records)
+}
+
+fn hmac(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Percent-encodes everything but unreserved characters, as both WebDAV paths and SigV4 expect
+fn encode(segment: &str) -> String {
+    segment.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
@@ -0,0 +1,649 @@
+use serde_json::{json, Map, Value};
+
+// JSON Schema (draft 2020-12) describing the ExportData file format.
+// Keep in sync with models.rs: optional and #[serde(default)] fields are not listed under `required`.
+
+pub fn export_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": "https://github.com/reicalasso/ruidmap/schemas/export.json",
+        "title": "RuidMap export",
+        "type": "object",
+        "required": ["version", "export_date", "data"],
+        "additionalProperties": false,
+        "properties": {
+            "version": { "type": "string" },
+            "export_date": { "type": "string", "format": "date-time" },
+            "checksum": { "type": "string", "pattern": "^sha256:[0-9a-f]{64}$" },
+            "data": { "$ref": "#/$defs/RoadmapData" }
+        },
+        "$defs": definitions()
+    })
+}
+
+// One json! call per definition keeps each macro expansion under the recursion limit
+fn definitions() -> Value {
+    let mut defs = Map::new();
+    defs.insert("RoadmapData".to_string(), json!({
+        "type": "object",
+        "required": ["tasks", "projects", "version"],
+        "additionalProperties": false,
+        "properties": {
+            "tasks": { "type": "array", "items": { "$ref": "#/$defs/Task" } },
+            "projects": { "type": "array", "items": { "$ref": "#/$defs/Project" } },
+            "current_project_id": { "type": ["integer", "null"], "minimum": 0 },
+            "theme": { "type": ["string", "null"] },
+            "version": { "type": "string" },
+            "settings": { "$ref": "#/$defs/AppSettings" },
+            "time_log": { "type": "array", "items": { "$ref": "#/$defs/TimeSession" } },
+            "members": { "type": "array", "items": { "$ref": "#/$defs/Member" } },
+            "notifications": { "type": "array", "items": { "$ref": "#/$defs/Notification" } },
+            "last_selection": { "$ref": "#/$defs/LastSelection" },
+            "onboarding": { "$ref": "#/$defs/OnboardingState" }
+        }
+    }));
+    defs.insert("OnboardingState".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "completed": { "type": "boolean" },
+            "completed_at": { "type": ["string", "null"], "format": "date-time" },
+            "sample_project_id": { "type": ["integer", "null"], "minimum": 0 }
+        }
+    }));
+    defs.insert("LastSelection".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "project_id": { "type": ["integer", "null"], "minimum": 0 },
+            "task_id": { "type": ["integer", "null"], "minimum": 0 },
+            "updated_at": { "type": ["string", "null"], "format": "date-time" }
+        }
+    }));
+    defs.insert("Member".to_string(), json!({
+        "type": "object",
+        "required": ["id", "name", "handle"],
+        "additionalProperties": false,
+        "properties": {
+            "id": { "type": "integer", "minimum": 0 },
+            "name": { "type": "string" },
+            "handle": { "type": "string" }
+        }
+    }));
+    defs.insert("Notification".to_string(), json!({
+        "type": "object",
+        "required": ["id", "member_id", "task_id", "comment_id", "author", "excerpt", "created_at", "read"],
+        "additionalProperties": false,
+        "properties": {
+            "id": { "type": "integer", "minimum": 0 },
+            "member_id": { "type": "integer", "minimum": 0 },
+            "task_id": { "type": "integer", "minimum": 0 },
+            "comment_id": { "type": "integer", "minimum": 0 },
+            "author": { "type": "string" },
+            "excerpt": { "type": "string" },
+            "created_at": { "type": "string", "format": "date-time" },
+            "read": { "type": "boolean" }
+        }
+    }));
+    defs.insert("TimeSession".to_string(), json!({
+        "type": "object",
+        "required": ["id", "task_id", "started_at", "ended_at", "minutes"],
+        "additionalProperties": false,
+        "properties": {
+            "id": { "type": "integer", "minimum": 0 },
+            "task_id": { "type": "integer", "minimum": 0 },
+            "started_at": { "type": "string", "format": "date-time" },
+            "ended_at": { "type": "string", "format": "date-time" },
+            "minutes": { "type": "integer", "minimum": 0 }
+        }
+    }));
+    defs.insert("Task".to_string(), json!({
+        "type": "object",
+        "required": [
+            "id", "project_id", "title", "description", "status", "priority",
+            "created_at", "updated_at", "tags", "subtasks", "comments", "time_spent", "attachments"
+        ],
+        "additionalProperties": false,
+        "properties": {
+            "id": { "type": "integer", "minimum": 0 },
+            "project_id": { "type": "integer", "minimum": 0 },
+            "title": { "type": "string" },
+            "description": { "type": "string" },
+            "status": { "$ref": "#/$defs/TaskStatus" },
+            "priority": { "$ref": "#/$defs/TaskPriority" },
+            "created_at": { "type": "string", "format": "date-time" },
+            "updated_at": { "type": "string", "format": "date-time" },
+            "due_date": { "type": ["string", "null"], "format": "date-time" },
+            "tags": { "type": "array", "items": { "type": "string" } },
+            "subtasks": { "type": "array", "items": { "$ref": "#/$defs/Subtask" } },
+            "comments": { "type": "array", "items": { "$ref": "#/$defs/Comment" } },
+            "time_spent": { "type": "integer", "minimum": 0 },
+            "estimated_time": { "type": ["integer", "null"], "minimum": 0 },
+            "size": { "type": ["integer", "null"], "minimum": 0 },
+            "attachments": { "type": "array", "items": { "$ref": "#/$defs/Attachment" } },
+            "dependencies": { "type": "array", "items": { "type": "integer", "minimum": 0 } },
+            "description_format": { "type": "string", "enum": ["plaintext", "markdown"] },
+            "requires_review": { "type": "boolean" },
+            "review_state": { "type": "string", "enum": ["not-requested", "requested", "approved", "rejected"] },
+            "reviewer": { "type": ["string", "null"] },
+            "status_changed_at": { "type": ["string", "null"], "format": "date-time" },
+            "assignee": { "type": ["integer", "null"], "minimum": 0 },
+            "watchers": { "type": "array", "items": { "type": "integer", "minimum": 0 } },
+            "custom_fields": { "type": "object", "additionalProperties": { "type": "string" } },
+            "external_id": { "type": ["string", "null"] },
+            "reminders": { "type": "array", "items": { "type": "integer", "minimum": 0 } },
+            "reminders_sent": { "type": "array", "items": { "type": "integer", "minimum": 0 } },
+            "sync_id": { "type": "string" }
+        }
+    }));
+    defs.insert("TaskStatus".to_string(), json!({ "type": "string", "enum": ["todo", "in-progress", "done"] }));
+    defs.insert("TaskPriority".to_string(), json!({ "type": "string", "enum": ["low", "medium", "high"] }));
+    defs.insert("Subtask".to_string(), json!({
+        "type": "object",
+        "required": ["id", "title", "completed", "created_at"],
+        "additionalProperties": false,
+        "properties": {
+            "id": { "type": "integer", "minimum": 0 },
+            "title": { "type": "string" },
+            "completed": { "type": "boolean" },
+            "created_at": { "type": "string", "format": "date-time" }
+        }
+    }));
+    defs.insert("Comment".to_string(), json!({
+        "type": "object",
+        "required": ["id", "text", "author", "created_at"],
+        "additionalProperties": false,
+        "properties": {
+            "id": { "type": "integer", "minimum": 0 },
+            "text": { "type": "string" },
+            "author": { "type": "string" },
+            "created_at": { "type": "string", "format": "date-time" },
+            "kind": { "type": "string", "enum": ["note", "review-requested", "approved", "rejected"] }
+        }
+    }));
+    defs.insert("Attachment".to_string(), json!({
+        "type": "object",
+        "required": ["id", "filename", "file_path", "file_size", "mime_type", "created_at"],
+        "additionalProperties": false,
+        "properties": {
+            "id": { "type": "integer", "minimum": 0 },
+            "filename": { "type": "string" },
+            "file_path": { "type": "string" },
+            "file_size": { "type": "integer", "minimum": 0 },
+            "mime_type": { "type": "string" },
+            "created_at": { "type": "string", "format": "date-time" },
+            "kind": { "type": "string", "enum": ["file", "link"] },
+            "url": { "type": ["string", "null"], "format": "uri" }
+        }
+    }));
+    defs.insert("Project".to_string(), json!({
+        "type": "object",
+        "required": ["id", "name", "created_at", "updated_at", "is_active", "task_count", "settings"],
+        "additionalProperties": false,
+        "properties": {
+            "id": { "type": "integer", "minimum": 0 },
+            "name": { "type": "string" },
+            "description": { "type": ["string", "null"] },
+            "color": { "type": ["string", "null"] },
+            "icon": { "type": ["string", "null"] },
+            "created_at": { "type": "string", "format": "date-time" },
+            "updated_at": { "type": "string", "format": "date-time" },
+            "is_active": { "type": "boolean" },
+            "task_count": { "type": "integer", "minimum": 0 },
+            "settings": { "$ref": "#/$defs/ProjectSettings" },
+            "sync_id": { "type": "string" }
+        }
+    }));
+    defs.insert("ProjectSettings".to_string(), json!({
+        "type": "object",
+        "required": ["default_priority", "auto_archive_done", "show_completed_tasks", "default_tags"],
+        "additionalProperties": false,
+        "properties": {
+            "task_template": {
+                "anyOf": [{ "$ref": "#/$defs/TaskTemplate" }, { "type": "null" }]
+            },
+            "default_priority": { "$ref": "#/$defs/TaskPriority" },
+            "auto_archive_done": { "type": "boolean" },
+            "show_completed_tasks": { "type": "boolean" },
+            "default_tags": { "type": "array", "items": { "type": "string" } },
+            "notifications": { "$ref": "#/$defs/ProjectNotificationSettings" },
+            "size_scale": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["label", "points"],
+                    "additionalProperties": false,
+                    "properties": {
+                        "label": { "type": "string" },
+                        "points": { "type": "integer", "minimum": 0 }
+                    }
+                }
+            },
+            "status_mapping": { "type": "object", "additionalProperties": { "$ref": "#/$defs/TaskStatus" } }
+        }
+    }));
+    defs.insert("ProjectNotificationSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "muted": { "type": "boolean" },
+            "on_task_completed": { "type": ["boolean", "null"] },
+            "on_task_overdue": { "type": ["boolean", "null"] },
+            "on_project_completed": { "type": ["boolean", "null"] },
+            "due_lead_minutes": { "type": "integer", "minimum": 0 },
+            "reminder_offsets": { "type": "array", "items": { "type": "integer", "minimum": 0 } }
+        }
+    }));
+    defs.insert("TaskTemplate".to_string(), json!({
+        "type": "object",
+        "required": ["default_tags"],
+        "additionalProperties": false,
+        "properties": {
+            "title_prefix": { "type": ["string", "null"] },
+            "default_description": { "type": ["string", "null"] },
+            "default_tags": { "type": "array", "items": { "type": "string" } },
+            "default_estimated_time": { "type": ["integer", "null"], "minimum": 0 }
+        }
+    }));
+    defs.insert("AppSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "ical_feed": { "$ref": "#/$defs/IcalFeedSettings" },
+            "webhooks": { "$ref": "#/$defs/WebhookSettings" },
+            "escalation": { "$ref": "#/$defs/EscalationSettings" },
+            "locale": { "$ref": "#/$defs/LocaleSettings" },
+            "capacity": { "$ref": "#/$defs/CapacitySettings" },
+            "working_days": { "$ref": "#/$defs/WorkingDaysSettings" },
+            "share_board": { "$ref": "#/$defs/ShareBoardSettings" },
+            "weekly_review": { "$ref": "#/$defs/WeeklyReviewSettings" },
+            "inbox": { "$ref": "#/$defs/InboxSettings" },
+            "email_inbox": { "$ref": "#/$defs/EmailInboxSettings" },
+            "remote_backup": { "$ref": "#/$defs/RemoteBackupSettings" },
+            "sync": { "$ref": "#/$defs/SyncSettings" }
+        }
+    }));
+    defs.insert("WeeklyReviewSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "enabled": { "type": "boolean" },
+            "weekday": { "$ref": "#/$defs/Weekday" },
+            "time": { "type": "string", "pattern": "^[0-2][0-9]:[0-5][0-9]$" },
+            "stale_days": { "type": "integer", "minimum": 0 },
+            "notify_webhooks": { "type": "boolean" },
+            "last_reminded_at": { "type": ["string", "null"], "format": "date-time" }
+        }
+    }));
+    defs.insert("EmailInboxSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "enabled": { "type": "boolean" },
+            "host": { "type": "string" },
+            "port": { "type": "integer", "minimum": 0, "maximum": 65535 },
+            "username": { "type": "string" },
+            "password": { "type": ["string", "null"] },
+            "folder": { "type": "string" },
+            "project_id": { "type": ["integer", "null"], "minimum": 0 },
+            "poll_minutes": { "type": "integer", "minimum": 1 },
+            "uid_validity": { "type": ["integer", "null"], "minimum": 0 },
+            "last_uid": { "type": ["integer", "null"], "minimum": 0 },
+            "last_checked_at": { "type": ["string", "null"], "format": "date-time" },
+            "last_error": { "type": ["string", "null"] }
+        }
+    }));
+    defs.insert("RemoteBackupSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "enabled": { "type": "boolean" },
+            "target": {
+                "anyOf": [
+                    { "type": "null" },
+                    {
+                        "type": "object",
+                        "required": ["type", "url", "username"],
+                        "additionalProperties": false,
+                        "properties": {
+                            "type": { "enum": ["webdav"] },
+                            "url": { "type": "string" },
+                            "username": { "type": "string" },
+                            "password": { "type": ["string", "null"] }
+                        }
+                    },
+                    {
+                        "type": "object",
+                        "required": ["type", "endpoint", "region", "bucket", "access_key_id"],
+                        "additionalProperties": false,
+                        "properties": {
+                            "type": { "enum": ["s3"] },
+                            "endpoint": { "type": "string" },
+                            "region": { "type": "string" },
+                            "bucket": { "type": "string" },
+                            "prefix": { "type": "string" },
+                            "access_key_id": { "type": "string" },
+                            "secret_access_key": { "type": ["string", "null"] },
+                            "path_style": { "type": "boolean" }
+                        }
+                    }
+                ]
+            },
+            "interval_hours": { "type": "integer", "minimum": 1 },
+            "keep": { "type": "integer", "minimum": 0 },
+            "passphrase": { "type": ["string", "null"] },
+            "last_backup_at": { "type": ["string", "null"], "format": "date-time" },
+            "last_error": { "type": ["string", "null"] }
+        }
+    }));
+    defs.insert("SyncSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "device_id": { "type": "string" },
+            "sequence": { "type": "integer", "minimum": 0 },
+            "last_pushed_at": { "type": ["string", "null"], "format": "date-time" },
+            "pushed_projects": { "type": "array", "items": { "type": "string" } },
+            "pushed_tasks": { "type": "array", "items": { "type": "string" } },
+            "applied": { "type": "object", "additionalProperties": { "type": "integer", "minimum": 0 } }
+        }
+    }));
+    defs.insert("InboxSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "enabled": { "type": "boolean" },
+            "port": { "type": "integer", "minimum": 0, "maximum": 65535 },
+            "token": { "type": ["string", "null"] },
+            "project_id": { "type": ["integer", "null"], "minimum": 0 }
+        }
+    }));
+    defs.insert("ShareBoardSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "enabled": { "type": "boolean" },
+            "port": { "type": "integer", "minimum": 0, "maximum": 65535 },
+            "lan": { "type": "boolean" },
+            "token": { "type": ["string", "null"] },
+            "project_id": { "type": ["integer", "null"], "minimum": 0 }
+        }
+    }));
+    defs.insert("CapacitySettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "weekly_hours": { "type": "number", "minimum": 0 },
+            "weekly_points": { "type": "number", "minimum": 0 },
+            "overrides": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["week_start", "hours"],
+                    "additionalProperties": false,
+                    "properties": {
+                        "week_start": { "type": "string", "format": "date" },
+                        "hours": { "type": "number", "minimum": 0 }
+                    }
+                }
+            }
+        }
+    }));
+    defs.insert("WorkingDaysSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "weekend": {
+                "type": "array",
+                "items": { "$ref": "#/$defs/Weekday" },
+                "uniqueItems": true,
+                "maxItems": 6
+            },
+            "holidays": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["date"],
+                    "additionalProperties": false,
+                    "properties": {
+                        "date": { "type": "string", "format": "date" },
+                        "name": { "type": "string" }
+                    }
+                }
+            }
+        }
+    }));
+    defs.insert("Weekday".to_string(), json!({
+        "type": "string",
+        "enum": ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"]
+    }));
+    defs.insert("LocaleSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "week_start": { "$ref": "#/$defs/Weekday" },
+            "date_format": { "type": "string" },
+            "fiscal_year_start_month": { "type": "integer", "minimum": 1, "maximum": 12 }
+        }
+    }));
+    defs.insert("IcalFeedSettings".to_string(), json!({
+        "type": "object",
+        "required": ["enabled", "port"],
+        "additionalProperties": false,
+        "properties": {
+            "enabled": { "type": "boolean" },
+            "port": { "type": "integer", "minimum": 0, "maximum": 65535 },
+            "token": { "type": ["string", "null"] }
+        }
+    }));
+    defs.insert("WebhookSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "slack_url": { "type": ["string", "null"] },
+            "discord_url": { "type": ["string", "null"] },
+            "on_task_completed": { "type": "boolean" },
+            "on_task_overdue": { "type": "boolean" },
+            "on_project_completed": { "type": "boolean" },
+            "templates": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "task_completed": { "type": "string" },
+                    "task_overdue": { "type": "string" },
+                    "task_due_soon": { "type": "string" },
+                    "project_completed": { "type": "string" }
+                }
+            },
+            "notified_overdue": { "type": "array", "items": { "type": "integer", "minimum": 0 } },
+            "notified_due_soon": { "type": "array", "items": { "type": "integer", "minimum": 0 } }
+        }
+    }));
+    defs.insert("EscalationSettings".to_string(), json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "enabled": { "type": "boolean" },
+            "rules": { "type": "array", "items": { "$ref": "#/$defs/EscalationRule" } },
+            "applied": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["rule_id", "task_id"],
+                    "additionalProperties": false,
+                    "properties": {
+                        "rule_id": { "type": "integer", "minimum": 0 },
+                        "task_id": { "type": "integer", "minimum": 0 }
+                    }
+                }
+            }
+        }
+    }));
+    defs.insert("EscalationRule".to_string(), json!({
+        "type": "object",
+        "required": ["overdue_days", "action"],
+        "additionalProperties": false,
+        "properties": {
+            "id": { "type": "integer", "minimum": 0 },
+            "overdue_days": { "type": "integer", "minimum": 0 },
+            "action": {
+                "anyOf": [
+                    {
+                        "type": "object",
+                        "required": ["type"],
+                        "additionalProperties": false,
+                        "properties": { "type": { "enum": ["bump_priority"] } }
+                    },
+                    {
+                        "type": "object",
+                        "required": ["type", "tag"],
+                        "additionalProperties": false,
+                        "properties": { "type": { "enum": ["add_tag"] }, "tag": { "type": "string" } }
+                    },
+                    {
+                        "type": "object",
+                        "required": ["type", "message"],
+                        "additionalProperties": false,
+                        "properties": { "type": { "enum": ["notify"] }, "message": { "type": "string" } }
+                    }
+                ]
+            }
+        }
+    }));
+    Value::Object(defs)
+}
+
+// Validates `instance` against the subset of JSON Schema used by export_schema().
+// Errors are reported as "<path>: <message>" with paths like `$.data.tasks[3].status`.
+pub fn validate(schema: &Value, instance: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_node(schema, schema, instance, "$", &mut errors);
+    errors
+}
+
+// Validates against a named definition, e.g. a bare RoadmapData file
+pub fn validate_definition(schema: &Value, definition: &str, instance: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    match schema.pointer(&format!("/$defs/{}", definition)) {
+        Some(node) => validate_node(schema, node, instance, "$", &mut errors),
+        None => errors.push(format!("$: unknown schema definition `{}`", definition)),
+    }
+    errors
+}
+
+fn validate_node(root: &Value, node: &Value, instance: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(reference) = node.get("$ref").and_then(Value::as_str) {
+        match reference.strip_prefix('#').and_then(|pointer| root.pointer(pointer)) {
+            Some(target) => validate_node(root, target, instance, path, errors),
+            None => errors.push(format!("{}: unresolvable schema reference `{}`", path, reference)),
+        }
+        return;
+    }
+
+    if let Some(options) = node.get("anyOf").and_then(Value::as_array) {
+        let matched = options.iter().any(|option| {
+            let mut option_errors = Vec::new();
+            validate_node(root, option, instance, path, &mut option_errors);
+            option_errors.is_empty()
+        });
+        if !matched {
+            errors.push(format!("{}: value does not match any allowed shape", path));
+        }
+        return;
+    }
+
+    if let Some(expected) = node.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.iter().any(|t| matches_type(t, instance)) {
+            errors.push(format!("{}: expected {}, found {}", path, allowed.join(" or "), type_name(instance)));
+            return;
+        }
+    }
+
+    if let Some(options) = node.get("enum").and_then(Value::as_array) {
+        if !options.contains(instance) {
+            let allowed: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+            errors.push(format!("{}: {} is not one of {}", path, instance, allowed.join(", ")));
+        }
+    }
+
+    if let Some(number) = instance.as_f64() {
+        if let Some(minimum) = node.get("minimum").and_then(Value::as_f64) {
+            if number < minimum {
+                errors.push(format!("{}: {} is less than the minimum of {}", path, number, minimum));
+            }
+        }
+        if let Some(maximum) = node.get("maximum").and_then(Value::as_f64) {
+            if number > maximum {
+                errors.push(format!("{}: {} is greater than the maximum of {}", path, number, maximum));
+            }
+        }
+    }
+
+    if let (Some("date-time"), Some(text)) = (node.get("format").and_then(Value::as_str), instance.as_str()) {
+        if chrono::DateTime::parse_from_rfc3339(text).is_err() {
+            errors.push(format!("{}: \"{}\" is not an RFC 3339 date-time", path, text));
+        }
+    }
+
+    if let Value::Object(object) = instance {
+        validate_object(root, node, object, path, errors);
+    }
+
+    if let (Some(items), Value::Array(array)) = (node.get("items"), instance) {
+        for (index, item) in array.iter().enumerate() {
+            validate_node(root, items, item, &format!("{}[{}]", path, index), errors);
+        }
+    }
+}
+
+fn validate_object(root: &Value, node: &Value, object: &Map<String, Value>, path: &str, errors: &mut Vec<String>) {
+    if let Some(required) = node.get("required").and_then(Value::as_array) {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                errors.push(format!("{}: missing required property `{}`", path, key));
+            }
+        }
+    }
+
+    let properties = node.get("properties").and_then(Value::as_object);
+    let closed = node.get("additionalProperties") == Some(&Value::Bool(false));
+
+    for (key, value) in object {
+        let child_path = format!("{}.{}", path, key);
+        match properties.and_then(|p| p.get(key)) {
+            Some(child) => validate_node(root, child, value, &child_path, errors),
+            None if closed => errors.push(format!("{}: unknown property", child_path)),
+            None => {}
+        }
+    }
+}
+
+fn matches_type(expected: &str, instance: &Value) -> bool {
+    match expected {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        "number" => instance.is_number(),
+        "integer" => instance.is_i64() || instance.is_u64(),
+        _ => false,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
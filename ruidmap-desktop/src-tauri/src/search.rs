@@ -0,0 +1,222 @@
+use crate::models::{Project, RoadmapData, Task, TaskPriority, TaskStatus};
+use serde::Serialize;
+use std::cmp::Reverse;
+
+// Search across every project for the omnibox. Every word of the query has to match
+// somewhere; matches in titles outrank tags, which outrank descriptions and comments.
+
+const TITLE_WEIGHT: u32 = 10;
+const NAME_WEIGHT: u32 = 10;
+const TAG_WEIGHT: u32 = 6;
+const COMMENT_WEIGHT: u32 = 4;
+const DESCRIPTION_WEIGHT: u32 = 2;
+const EXACT_TITLE_BONUS: u32 = 50;
+const EXCERPT_CONTEXT: usize = 40;
+const EXCERPT_LENGTH: usize = 120;
+
+#[derive(Debug, Serialize, Default)]
+pub struct SearchResults {
+    pub query: String,
+    pub tasks: Vec<TaskHit>,
+    pub projects: Vec<ProjectHit>,
+    pub comments: Vec<CommentHit>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectContext {
+    pub project_id: u32,
+    pub project_name: String,
+    pub project_color: Option<String>,
+    pub project_icon: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskHit {
+    pub task_id: u32,
+    pub title: String,
+    pub status: TaskStatus,
+    pub priority: TaskPriority,
+    pub tags: Vec<String>,
+    pub score: u32,
+    #[serde(flatten)]
+    pub project: Option<ProjectContext>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectHit {
+    pub project_id: u32,
+    pub name: String,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub score: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommentHit {
+    pub task_id: u32,
+    pub task_title: String,
+    pub comment_id: u32,
+    pub author: String,
+    pub excerpt: String,
+    pub created_at: String,
+    pub score: u32,
+    #[serde(flatten)]
+    pub project: Option<ProjectContext>,
+}
+
+pub fn search(data: &RoadmapData, query: &str, limit: usize) -> SearchResults {
+    let query = query.trim();
+    let mut results = SearchResults { query: query.to_string(), ..Default::default() };
+    let terms: Vec<String> = query.to_lowercase().split_whitespace().map(str::to_string).collect();
+    if terms.is_empty() {
+        return results;
+    }
+
+    let context = |project_id: u32| data.projects.iter()
+        .find(|p| p.id == project_id)
+        .map(project_context);
+
+    let mut tasks: Vec<(&Task, u32)> = data.tasks.iter()
+        .filter_map(|t| task_score(t, &terms, query).map(|score| (t, score)))
+        .collect();
+    tasks.sort_by_cached_key(|(t, score)| (Reverse(*score), t.status == TaskStatus::Done, Reverse(t.updated_at.clone())));
+    results.tasks = tasks.into_iter()
+        .take(limit)
+        .map(|(t, score)| TaskHit {
+            task_id: t.id,
+            title: t.title.clone(),
+            status: t.status.clone(),
+            priority: t.priority.clone(),
+            tags: t.tags.clone(),
+            score,
+            project: context(t.project_id),
+        })
+        .collect();
+
+    let mut projects: Vec<(&Project, u32)> = data.projects.iter()
+        .filter_map(|p| project_score(p, &terms).map(|score| (p, score)))
+        .collect();
+    projects.sort_by_key(|(p, score)| (Reverse(*score), p.id));
+    results.projects = projects.into_iter()
+        .take(limit)
+        .map(|(p, score)| ProjectHit {
+            project_id: p.id,
+            name: p.name.clone(),
+            description: p.description.clone(),
+            color: p.color.clone(),
+            icon: p.icon.clone(),
+            score,
+        })
+        .collect();
+
+    let mut comments: Vec<CommentHit> = Vec::new();
+    for task in &data.tasks {
+        for comment in &task.comments {
+            let text = comment.text.to_lowercase();
+            if !terms.iter().all(|term| text.contains(term.as_str())) {
+                continue;
+            }
+            let score = terms.iter().map(|term| COMMENT_WEIGHT * match_strength(&text, term)).sum();
+            comments.push(CommentHit {
+                task_id: task.id,
+                task_title: task.title.clone(),
+                comment_id: comment.id,
+                author: comment.author.clone(),
+                excerpt: excerpt(&comment.text, &terms[0]),
+                created_at: comment.created_at.clone(),
+                score,
+                project: context(task.project_id),
+            });
+        }
+    }
+    comments.sort_by_cached_key(|c| (Reverse(c.score), Reverse(c.created_at.clone())));
+    comments.truncate(limit);
+    results.comments = comments;
+
+    results
+}
+
+fn project_context(project: &Project) -> ProjectContext {
+    ProjectContext {
+        project_id: project.id,
+        project_name: project.name.clone(),
+        project_color: project.color.clone(),
+        project_icon: project.icon.clone(),
+    }
+}
+
+// None unless every term matches the title, a tag or the description
+fn task_score(task: &Task, terms: &[String], query: &str) -> Option<u32> {
+    let title = task.title.to_lowercase();
+    let description = task.description.to_lowercase();
+    let tags: Vec<String> = task.tags.iter().map(|t| t.to_lowercase()).collect();
+
+    let mut score = 0;
+    for term in terms {
+        let best = [
+            TITLE_WEIGHT * match_strength(&title, term),
+            tags.iter().map(|t| TAG_WEIGHT * match_strength(t, term)).max().unwrap_or(0),
+            DESCRIPTION_WEIGHT * match_strength(&description, term),
+        ].into_iter().max().unwrap_or(0);
+        if best == 0 {
+            return None;
+        }
+        score += best;
+    }
+
+    if title == query.to_lowercase() {
+        score += EXACT_TITLE_BONUS;
+    }
+    Some(score)
+}
+
+fn project_score(project: &Project, terms: &[String]) -> Option<u32> {
+    let name = project.name.to_lowercase();
+    let description = project.description.as_deref().unwrap_or("").to_lowercase();
+
+    let mut score = 0;
+    for term in terms {
+        let best = (NAME_WEIGHT * match_strength(&name, term))
+            .max(DESCRIPTION_WEIGHT * match_strength(&description, term));
+        if best == 0 {
+            return None;
+        }
+        score += best;
+    }
+    Some(score)
+}
+
+// 3 for a prefix of the text, 2 for the start of a word, 1 anywhere else, 0 for no match
+fn match_strength(text: &str, term: &str) -> u32 {
+    if text.starts_with(term) {
+        return 3;
+    }
+    let mut strength = 0;
+    for (i, _) in text.match_indices(term) {
+        let word_start = text[..i].chars().next_back().is_some_and(|c| !c.is_alphanumeric());
+        if word_start {
+            return 2;
+        }
+        strength = 1;
+    }
+    strength
+}
+
+// A window of the text around the first match, so the omnibox can show why a comment matched
+fn excerpt(text: &str, term: &str) -> String {
+    let lower = text.to_lowercase();
+    let position = lower.find(term).map(|i| lower[..i].chars().count()).unwrap_or(0);
+    let start = position.saturating_sub(EXCERPT_CONTEXT);
+    let total = text.chars().count();
+
+    let mut excerpt: String = text.chars().skip(start).take(EXCERPT_LENGTH).collect();
+    excerpt = excerpt.replace(['\r', '\n'], " ");
+    if start > 0 {
+        excerpt = format!("…{}", excerpt.trim_start());
+    }
+    if start + EXCERPT_LENGTH < total {
+        excerpt = format!("{}…", excerpt.trim_end());
+    }
+    excerpt
+}
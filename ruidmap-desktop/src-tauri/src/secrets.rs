@@ -0,0 +1,53 @@
+use crate::models::{RemoteBackupTarget, RoadmapData};
+
+// Credentials kept in the settings, including the Slack and Discord webhook URLs and the tokens
+// of the local servers, which work for anyone who has them. They stay in the data file but are
+// left out of every export and backup, which may be shared or stored elsewhere. When a backup or
+// import replaces the data, the live credentials are kept wherever the incoming data has none, so
+// restoring doesn't disconnect every integration. The server tokens are always the live ones, so
+// a file can't hand out access to this device's servers.
+
+pub fn strip(data: &mut RoadmapData) {
+    let settings = &mut data.settings;
+    settings.webhooks.slack_url = None;
+    settings.webhooks.discord_url = None;
+    settings.ical_feed.token = None;
+    settings.share_board.token = None;
+    settings.inbox.token = None;
+    settings.email_inbox.password = None;
+    settings.remote_backup.passphrase = None;
+    match &mut settings.remote_backup.target {
+        Some(RemoteBackupTarget::Webdav { password, .. }) => *password = None,
+        Some(RemoteBackupTarget::S3 { secret_access_key, .. }) => *secret_access_key = None,
+        None => {}
+    }
+}
+
+pub fn carry_over(live: &RoadmapData, restored: &mut RoadmapData) {
+    let (live, settings) = (&live.settings, &mut restored.settings);
+    keep(&mut settings.webhooks.slack_url, &live.webhooks.slack_url);
+    keep(&mut settings.webhooks.discord_url, &live.webhooks.discord_url);
+    settings.ical_feed.token.clone_from(&live.ical_feed.token);
+    settings.share_board.token.clone_from(&live.share_board.token);
+    settings.inbox.token.clone_from(&live.inbox.token);
+    keep(&mut settings.email_inbox.password, &live.email_inbox.password);
+    keep(&mut settings.remote_backup.passphrase, &live.remote_backup.passphrase);
+    // Only for the same destination; credentials for one host are never sent to another
+    match (&mut settings.remote_backup.target, &live.remote_backup.target) {
+        (
+            Some(RemoteBackupTarget::Webdav { url, username, password }),
+            Some(RemoteBackupTarget::Webdav { url: live_url, username: live_username, password: live_password }),
+        ) if url == live_url && username == live_username => keep(password, live_password),
+        (
+            Some(RemoteBackupTarget::S3 { endpoint, bucket, access_key_id, secret_access_key, .. }),
+            Some(RemoteBackupTarget::S3 { endpoint: live_endpoint, bucket: live_bucket, access_key_id: live_key_id, secret_access_key: live_secret, .. }),
+        ) if endpoint == live_endpoint && bucket == live_bucket && access_key_id == live_key_id => keep(secret_access_key, live_secret),
+        _ => {}
+    }
+}
+
+fn keep(restored: &mut Option<String>, live: &Option<String>) {
+    if restored.as_deref().unwrap_or_default().is_empty() {
+        restored.clone_from(live);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::models::{Project, RoadmapData, Task, TaskPriority, TaskStatus};
+use crate::palette;
+use chrono::{Duration, Utc};
+
+// Synthetic data for development and benchmarks (deterministic for a given size), and the
+// sample project offered on first run.
+
+const TAGS: [&str; 8] = ["frontend", "backend", "bug", "feature", "docs", "design", "infra", "research"];
+const VERBS: [&str; 6] = ["Implement", "Refactor", "Review", "Document", "Fix", "Design"];
+const NOUNS: [&str; 8] = ["login flow", "search index", "export dialog", "sync engine", "settings page", "API client", "kanban board", "onboarding"];
+
+#[derive(serde::Serialize)]
+pub struct SeedResult {
+    pub projects_created: u32,
+    pub tasks_created: u32,
+}
+
+// Small LCG so seeding doesn't pull in a random number crate
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound.max(1)
+    }
+}
+
+pub fn seed_demo_data(data: &mut RoadmapData, task_count: u32, project_count: u32) -> SeedResult {
+    let mut rng = Lcg(0x5eed);
+    let now = Utc::now();
+
+    let first_project_id = data.projects.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+    let mut project_ids: Vec<u32> = (first_project_id..first_project_id + project_count).collect();
+    for (i, project_id) in project_ids.iter().enumerate() {
+        let mut project = Project::new(*project_id, format!("Demo Project {}", i + 1));
+        project.description = Some("Generated by seed_demo_data".to_string());
+        data.projects.push(project);
+    }
+
+    // Without new projects, spread tasks over the existing ones
+    if project_ids.is_empty() {
+        project_ids = data.projects.iter().map(|p| p.id).collect();
+    }
+    if project_ids.is_empty() {
+        return SeedResult { projects_created: 0, tasks_created: 0 };
+    }
+
+    let first_task_id = data.tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    for task_id in first_task_id..first_task_id + task_count {
+        let project_id = project_ids[rng.below(project_ids.len() as u64) as usize];
+        let title = format!(
+            "{} {}",
+            VERBS[rng.below(VERBS.len() as u64) as usize],
+            NOUNS[rng.below(NOUNS.len() as u64) as usize]
+        );
+        let mut task = Task::new(task_id, project_id, title, format!("Synthetic task #{}", task_id));
+
+        task.status = match rng.below(3) {
+            0 => TaskStatus::Todo,
+            1 => TaskStatus::InProgress,
+            _ => TaskStatus::Done,
+        };
+        task.priority = match rng.below(3) {
+            0 => TaskPriority::Low,
+            1 => TaskPriority::Medium,
+            _ => TaskPriority::High,
+        };
+
+        for _ in 0..rng.below(3) {
+            let tag = TAGS[rng.below(TAGS.len() as u64) as usize].to_string();
+            if !task.tags.contains(&tag) {
+                task.tags.push(tag);
+            }
+        }
+
+        if rng.below(2) == 0 {
+            let offset_days = rng.below(60) as i64 - 20;
+            task.due_date = Some((now + Duration::days(offset_days)).to_rfc3339());
+        }
+        if rng.below(3) != 0 {
+            task.estimated_time = Some(((rng.below(16) + 1) * 30) as u32);
+        }
+        if task.status != TaskStatus::Todo {
+            task.time_spent = (rng.below(10) * 15) as u32;
+        }
+
+        for subtask_id in 1..=rng.below(4) as u32 {
+            task.add_subtask(subtask_id, format!("Step {}", subtask_id));
+        }
+
+        data.tasks.push(task);
+    }
+
+    SeedResult {
+        projects_created: project_count,
+        tasks_created: task_count,
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct SampleWorkspace {
+    pub project: Project,
+    pub tasks_created: u32,
+}
+
+// (title, description, status, priority, due in days, tags, estimate in minutes, subtasks with done flags)
+type SampleTask = (&'static str, &'static str, TaskStatus, TaskPriority, Option<i64>, &'static [&'static str], Option<u32>, &'static [(&'static str, bool)]);
+
+const SAMPLE_TASKS: &[SampleTask] = &[
+    ("Agree on launch scope", "List the pages that must ship on day one and what can wait.", TaskStatus::Done, TaskPriority::High, Some(-10), &["planning"], Some(60), &[("Collect wishes from sales and support", true), ("Sign off with stakeholders", true)]),
+    ("Audit the current site", "Check traffic, broken links and which pages nobody visits.", TaskStatus::Done, TaskPriority::Medium, Some(-7), &["research"], Some(180), &[("Export analytics", true), ("Run a link checker", true), ("Write up findings", true)]),
+    ("Design the new homepage", "Wireframes first, then a high-fidelity mockup for review.", TaskStatus::InProgress, TaskPriority::High, Some(3), &["design"], Some(480), &[("Wireframes", true), ("Mockup", false), ("Review with the team", false)]),
+    ("Set up the component library", "Buttons, forms and cards shared by every page.", TaskStatus::InProgress, TaskPriority::Medium, Some(6), &["frontend"], Some(360), &[("Buttons and links", true), ("Form inputs", false)]),
+    ("Fix the contact form spam", "The old form gets dozens of spam messages a day. Add a honeypot field and rate limiting.", TaskStatus::Todo, TaskPriority::High, Some(-2), &["bug", "backend"], Some(90), &[]),
+    ("Write copy for the pricing page", "Short, plain language. Link to the FAQ for details.", TaskStatus::Todo, TaskPriority::Medium, Some(9), &["content"], Some(120), &[("First draft", false), ("Proofread", false)]),
+    ("Migrate blog posts", "Move the posts worth keeping and set up redirects for the rest.", TaskStatus::Todo, TaskPriority::Low, Some(14), &["content", "backend"], Some(240), &[]),
+    ("Plan the launch announcement", "Newsletter, social posts and a short note for customers.", TaskStatus::Todo, TaskPriority::Low, Some(21), &["marketing"], None, &[("Newsletter draft", false), ("Schedule social posts", false)]),
+    ("Try keyboard shortcuts and filters", "This is a sample task. Edit it, move it between columns, or delete the whole sample project when you are ready to start your own.", TaskStatus::Todo, TaskPriority::Medium, None, &["getting-started"], None, &[("Open this task", true), ("Change its status", false), ("Add a tag", false)]),
+];
+
+// Hand-written demo project so a new workspace doesn't open on an empty board
+pub fn create_sample_workspace(data: &mut RoadmapData) -> SampleWorkspace {
+    let now = Utc::now();
+    let project_id = data.projects.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+    let project = Project::new_with_details(
+        project_id,
+        "Website Relaunch (sample)".to_string(),
+        "A sample project to explore RuidMap. Delete it whenever you like.".to_string(),
+        Some(palette::next_color(&data.projects)),
+        Some("🚀".to_string()),
+    );
+    data.projects.push(project.clone());
+
+    let first_task_id = data.tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    for ((title, description, status, priority, due_in, tags, estimate, subtasks), task_id) in SAMPLE_TASKS.iter().zip(first_task_id..) {
+        let mut task = Task::new(task_id, project_id, title.to_string(), description.to_string());
+        task.status = status.clone();
+        task.priority = priority.clone();
+        task.due_date = due_in.map(|days| (now + Duration::days(days)).to_rfc3339());
+        task.tags = tags.iter().map(|t| t.to_string()).collect();
+        task.estimated_time = *estimate;
+        if let Some(estimate) = estimate.filter(|_| *status != TaskStatus::Todo) {
+            task.time_spent = if *status == TaskStatus::Done { estimate } else { estimate / 2 };
+        }
+        for ((subtask, done), subtask_id) in subtasks.iter().zip(1..) {
+            task.add_subtask(subtask_id, subtask.to_string());
+            if *done {
+                task.toggle_subtask(subtask_id);
+            }
+        }
+        data.tasks.push(task);
+    }
+
+    // The component library builds on the homepage design
+    if let Some(task) = data.tasks.iter_mut().find(|t| t.id == first_task_id + 3) {
+        task.dependencies.push(first_task_id + 2);
+    }
+    if let Some(task) = data.tasks.iter_mut().find(|t| t.id == first_task_id + 2) {
+        task.add_comment(1, "Wireframes are in the shared folder, feedback welcome before Friday.".to_string(), "RuidMap".to_string());
+    }
+
+    SampleWorkspace {
+        project,
+        tasks_created: SAMPLE_TASKS.len() as u32,
+    }
+}
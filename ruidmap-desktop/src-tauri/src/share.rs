@@ -0,0 +1,289 @@
+use crate::models::{RoadmapData, TaskPriority, TaskStatus};
+use crate::storage::Storage;
+use crate::tokens;
+use crate::workdays;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::net::UdpSocket;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+use tiny_http::{Header, Request, Response, Server};
+
+// Read-only live board for the current project. The page receives board snapshots over
+// server-sent events; each stream watches the data file and pushes a new snapshot when it changes.
+// The file is only ever opened read-only, so the board never writes behind the app's back.
+
+const BOARD_PAGE: &str = include_str!("share_board.html");
+const CHANGE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+// Each stream holds a thread, and on the LAN anyone can connect
+const MAX_EVENT_STREAMS: usize = 16;
+
+pub struct ShareBoardState(pub Mutex<Option<ShareBoardServer>>);
+
+impl ShareBoardState {
+    // Restart the board on launch if it was left enabled. Not for read-only files, whose settings
+    // and token may come from someone else's export.
+    pub fn resume(storage: &Storage) -> Self {
+        if storage.is_read_only() {
+            return ShareBoardState(Mutex::new(None));
+        }
+        ShareBoardState(Mutex::new(start_from_settings(storage)))
+    }
+
+    // Rebinds a running board to the storage's current data file, e.g. after it was moved
+    pub fn restart(&self, storage: &Storage) {
+        if let Ok(mut board) = self.0.lock() {
+            if board.take().is_some() {
+                *board = start_from_settings(storage);
+            }
+        }
+    }
+}
+
+fn start_from_settings(storage: &Storage) -> Option<ShareBoardServer> {
+    let settings = &storage.data().settings.share_board;
+    let (true, Some(token)) = (settings.enabled, &settings.token) else {
+        return None;
+    };
+    let path = storage.get_data_file_path().to_path_buf();
+    match ShareBoardServer::start(path, settings.port, settings.lan, token.clone(), settings.project_id) {
+        Ok(started) => Some(started),
+        Err(e) => {
+            eprintln!("Failed to resume share board: {}", e);
+            None
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct ShareBoardInfo {
+    pub running: bool,
+    pub port: u16,
+    pub lan: bool,
+    pub token: Option<String>,
+    pub project_id: Option<u32>,
+    pub url: Option<String>,
+}
+
+pub struct ShareBoardServer {
+    server: Arc<Server>,
+    handle: Option<JoinHandle<()>>,
+    running: Arc<AtomicBool>,
+    port: u16,
+    lan: bool,
+    token: String,
+    project_id: Option<u32>,
+}
+
+impl ShareBoardServer {
+    pub fn start(data_file_path: PathBuf, port: u16, lan: bool, token: String, project_id: Option<u32>) -> Result<Self> {
+        let host = if lan { "0.0.0.0" } else { "127.0.0.1" };
+        let server = Server::http((host, port))
+            .map_err(|e| anyhow!("Failed to bind {}:{}: {}", host, port, e))?;
+        let server = Arc::new(server);
+        let data_file_path = Arc::new(data_file_path);
+        let running = Arc::new(AtomicBool::new(true));
+        let streams = Arc::new(AtomicUsize::new(0));
+
+        let worker = Arc::clone(&server);
+        let board_token = token.clone();
+        let worker_running = Arc::clone(&running);
+        let handle = thread::spawn(move || {
+            for request in worker.incoming_requests() {
+                let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+                let path = path.to_string();
+                if !tokens::in_query(query, &board_token) {
+                    let _ = request.respond(Response::from_string("Forbidden").with_status_code(403));
+                    continue;
+                }
+
+                match path.as_str() {
+                    "/" | "/board" => {
+                        let _ = request.respond(Response::from_string(BOARD_PAGE).with_header(content_type("text/html; charset=utf-8")));
+                    }
+                    "/board.json" => {
+                        let response = match Storage::open_read_only(data_file_path.to_path_buf()) {
+                            Ok(storage) => Response::from_string(snapshot(storage.data(), project_id).to_string())
+                                .with_header(content_type("application/json")),
+                            Err(e) => Response::from_string(format!("Failed to load data: {}", e)).with_status_code(500),
+                        };
+                        let _ = request.respond(response);
+                    }
+                    // Streams are long-lived, so each gets its own thread
+                    "/events" => {
+                        let Some(slot) = StreamSlot::take(&streams) else {
+                            let _ = request.respond(Response::from_string("Too many viewers").with_status_code(503));
+                            continue;
+                        };
+                        let data_file_path = Arc::clone(&data_file_path);
+                        let running = Arc::clone(&worker_running);
+                        thread::spawn(move || {
+                            stream_events(request, &data_file_path, project_id, &running);
+                            drop(slot);
+                        });
+                    }
+                    _ => {
+                        let _ = request.respond(Response::from_string("Not found").with_status_code(404));
+                    }
+                }
+            }
+        });
+
+        Ok(ShareBoardServer {
+            server,
+            handle: Some(handle),
+            running,
+            port,
+            lan,
+            token,
+            project_id,
+        })
+    }
+
+    pub fn info(&self) -> ShareBoardInfo {
+        ShareBoardInfo {
+            running: true,
+            port: self.port,
+            lan: self.lan,
+            token: Some(self.token.clone()),
+            project_id: self.project_id,
+            url: Some(board_url(self.port, self.lan, &self.token)),
+        }
+    }
+}
+
+impl Drop for ShareBoardServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// On the LAN the board is reached through this machine's address rather than localhost
+pub fn board_url(port: u16, lan: bool, token: &str) -> String {
+    let host = if lan { lan_address().unwrap_or_else(|| "127.0.0.1".to_string()) } else { "127.0.0.1".to_string() };
+    format!("http://{}:{}/board?token={}", host, port, token)
+}
+
+// Connecting a UDP socket sends nothing but tells us which interface routes outwards
+fn lan_address() -> Option<String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    Some(socket.local_addr().ok()?.ip().to_string())
+}
+
+// One of the MAX_EVENT_STREAMS places, given back when the stream ends
+struct StreamSlot(Arc<AtomicUsize>);
+
+impl StreamSlot {
+    fn take(streams: &Arc<AtomicUsize>) -> Option<Self> {
+        streams.fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < MAX_EVENT_STREAMS).then_some(n + 1)).ok()?;
+        Some(StreamSlot(Arc::clone(streams)))
+    }
+}
+
+impl Drop for StreamSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes(&b"Content-Type"[..], value.as_bytes()).expect("static header is valid")
+}
+
+fn stream_events(request: Request, data_file_path: &Path, project_id: Option<u32>, running: &AtomicBool) {
+    let mut writer = request.into_writer();
+    let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+    if writer.write_all(head.as_bytes()).and_then(|_| writer.flush()).is_err() {
+        return;
+    }
+
+    // None until the first snapshot has been sent
+    let mut last_modified: Option<Option<SystemTime>> = None;
+    let mut last_write = Instant::now();
+    while running.load(Ordering::Relaxed) {
+        let modified = std::fs::metadata(data_file_path).and_then(|m| m.modified()).ok();
+        let message = if last_modified != Some(modified) {
+            last_modified = Some(modified);
+            match Storage::open_read_only(data_file_path.to_path_buf()) {
+                Ok(storage) => Some(format!("data: {}\n\n", snapshot(storage.data(), project_id))),
+                Err(e) => Some(format!(": failed to load data: {}\n\n", e)),
+            }
+        } else if last_write.elapsed() >= KEEPALIVE_INTERVAL {
+            Some(": keepalive\n\n".to_string())
+        } else {
+            None
+        };
+
+        if let Some(message) = message {
+            // A failed write means the viewer went away
+            if writer.write_all(message.as_bytes()).and_then(|_| writer.flush()).is_err() {
+                return;
+            }
+            last_write = Instant::now();
+        }
+        thread::sleep(CHANGE_POLL_INTERVAL);
+    }
+}
+
+pub fn snapshot(data: &RoadmapData, project_id: Option<u32>) -> Value {
+    let project_id = project_id.or(data.current_project_id).unwrap_or(1);
+    let project = data.projects.iter().find(|p| p.id == project_id);
+    let now = Utc::now();
+
+    let columns: Vec<Value> = [
+        (TaskStatus::Todo, "To Do"),
+        (TaskStatus::InProgress, "In Progress"),
+        (TaskStatus::Done, "Done"),
+    ].into_iter().map(|(status, title)| {
+        let mut tasks: Vec<_> = data.tasks.iter()
+            .filter(|t| t.project_id == project_id && t.status == status)
+            .collect();
+        tasks.sort_by_key(|t| (priority_rank(&t.priority), t.due_date.clone().unwrap_or_else(|| "~".to_string())));
+
+        let tasks: Vec<Value> = tasks.into_iter().map(|t| {
+            let overdue = workdays::is_overdue(t, now, &data.settings.working_days);
+            json!({
+                "id": t.id,
+                "title": t.title,
+                "priority": t.priority,
+                "due_date": t.due_date,
+                "overdue": overdue,
+                "tags": t.tags,
+                "subtasks_done": t.subtasks.iter().filter(|s| s.completed).count(),
+                "subtasks_total": t.subtasks.len(),
+            })
+        }).collect();
+
+        json!({ "status": status, "title": title, "tasks": tasks })
+    }).collect();
+
+    json!({
+        "project": {
+            "id": project_id,
+            "name": project.map(|p| p.name.as_str()).unwrap_or("Unknown project"),
+            "color": project.and_then(|p| p.color.clone()),
+            "icon": project.and_then(|p| p.icon.clone()),
+        },
+        "columns": columns,
+        "generated_at": now.to_rfc3339(),
+    })
+}
+
+fn priority_rank(priority: &TaskPriority) -> u8 {
+    match priority {
+        TaskPriority::High => 0,
+        TaskPriority::Medium => 1,
+        TaskPriority::Low => 2,
+    }
+}
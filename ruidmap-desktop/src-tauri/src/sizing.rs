@@ -0,0 +1,39 @@
+use crate::models::SizeOption;
+use anyhow::{anyhow, Result};
+
+// Effort sizes for teams that estimate in points rather than hours. Each project has a scale
+// of labels worth some points (XS–XL by default, or e.g. 1, 2, 3, 5, 8); tasks store the points,
+// so totals, velocity and the capacity planner can add them up whatever the labels.
+
+pub fn validate_scale(scale: &[SizeOption]) -> Result<()> {
+    for (i, option) in scale.iter().enumerate() {
+        let label = option.label.trim();
+        if label.is_empty() {
+            return Err(anyhow!("Every size needs a label"));
+        }
+        if scale[..i].iter().any(|o| o.label.trim().eq_ignore_ascii_case(label)) {
+            return Err(anyhow!("The size \"{}\" is listed twice", label));
+        }
+        if scale[..i].iter().any(|o| o.points == option.points) {
+            return Err(anyhow!("\"{}\" is worth {} points like another size; points must differ", label, option.points));
+        }
+    }
+    Ok(())
+}
+
+// A label of the scale (any case), or the points of one of its sizes. A project without a scale
+// takes any whole number of points.
+pub fn parse(scale: &[SizeOption], input: &str) -> Result<u32> {
+    let input = input.trim();
+    if let Some(option) = scale.iter().find(|o| o.label.trim().eq_ignore_ascii_case(input)) {
+        return Ok(option.points);
+    }
+    match input.parse::<u32>() {
+        Ok(points) if scale.is_empty() || scale.iter().any(|o| o.points == points) => Ok(points),
+        _ if scale.is_empty() => Err(anyhow!("\"{}\" is not a number of points", input)),
+        _ => {
+            let labels: Vec<&str> = scale.iter().map(|o| o.label.trim()).collect();
+            Err(anyhow!("\"{}\" is not on the size scale ({})", input, labels.join(", ")))
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::models::{Comment, CommentKind, DescriptionFormat, Subtask, Task, TaskPriority, TaskStatus};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+// Single-task snippets for passing tasks around outside of full workspace exports.
+// Ids, project, dependencies, time tracking and attachments are local to a workspace and left out.
+
+const SNIPPET_FORMAT: &str = "ruidmap-task";
+const SNIPPET_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskSnippet {
+    pub format: String,
+    pub version: u32,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default)]
+    pub description_format: DescriptionFormat,
+    pub status: TaskStatus,
+    pub priority: TaskPriority,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_time: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<SnippetSubtask>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<SnippetComment>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnippetSubtask {
+    pub title: String,
+    #[serde(default)]
+    pub completed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnippetComment {
+    pub text: String,
+    pub author: String,
+    pub created_at: String,
+}
+
+pub fn to_snippet(task: &Task) -> Result<String> {
+    let snippet = TaskSnippet {
+        format: SNIPPET_FORMAT.to_string(),
+        version: SNIPPET_VERSION,
+        title: task.title.clone(),
+        description: task.description.clone(),
+        description_format: task.description_format,
+        status: task.status.clone(),
+        priority: task.priority.clone(),
+        due_date: task.due_date.clone(),
+        estimated_time: task.estimated_time,
+        tags: task.tags.clone(),
+        subtasks: task.subtasks.iter()
+            .map(|s| SnippetSubtask { title: s.title.clone(), completed: s.completed })
+            .collect(),
+        comments: task.comments.iter()
+            .map(|c| SnippetComment { text: c.text.clone(), author: c.author.clone(), created_at: c.created_at.clone() })
+            .collect(),
+    };
+
+    Ok(serde_json::to_string(&snippet)?)
+}
+
+pub fn parse(snippet: &str) -> Result<TaskSnippet> {
+    let snippet: TaskSnippet = serde_json::from_str(snippet.trim())
+        .map_err(|e| anyhow!("Not a valid task snippet: {}", e))?;
+    if snippet.format != SNIPPET_FORMAT {
+        return Err(anyhow!("Not a task snippet (format \"{}\")", snippet.format));
+    }
+    if snippet.version > SNIPPET_VERSION {
+        return Err(anyhow!("Task snippet version {} is newer than this version of RuidMap supports", snippet.version));
+    }
+    if snippet.title.trim().is_empty() {
+        return Err(anyhow!("Task snippet has no title"));
+    }
+    Ok(snippet)
+}
+
+// Builds a fresh task; ids are numbered from 1 within the task like the add commands do
+pub fn into_task(snippet: TaskSnippet, id: u32, project_id: u32) -> Task {
+    let mut task = Task::new(id, project_id, snippet.title, snippet.description);
+    let now = task.created_at.clone();
+
+    task.description_format = snippet.description_format;
+    task.status = snippet.status;
+    task.priority = snippet.priority;
+    task.due_date = snippet.due_date;
+    task.estimated_time = snippet.estimated_time;
+    for tag in snippet.tags {
+        if !task.tags.contains(&tag) {
+            task.tags.push(tag);
+        }
+    }
+    task.subtasks = snippet.subtasks.into_iter()
+        .zip(1..)
+        .map(|(s, id)| Subtask { id, title: s.title, completed: s.completed, created_at: now.clone() })
+        .collect();
+    task.comments = snippet.comments.into_iter()
+        .zip(1..)
+        .map(|(c, id)| Comment { id, text: c.text, author: c.author, created_at: c.created_at, kind: CommentKind::Note })
+        .collect();
+
+    task
+}
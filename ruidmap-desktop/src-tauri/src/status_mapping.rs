@@ -0,0 +1,61 @@
+use crate::models::TaskStatus;
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+
+// Which status a workflow state from another tracker ("In Review", "Committed", "Won't Do")
+// becomes on import. Every importer asks `status`: the mappings it is given come first (usually
+// one passed with the import, then the target project's `status_mapping`), then the common names
+// below. Names match ignoring case, with `_` and `-` read as spaces.
+
+const COMMON_STATES: [(&str, TaskStatus); 23] = [
+    ("todo", TaskStatus::Todo),
+    ("to do", TaskStatus::Todo),
+    ("open", TaskStatus::Todo),
+    ("new", TaskStatus::Todo),
+    ("backlog", TaskStatus::Todo),
+    ("not started", TaskStatus::Todo),
+    ("proposed", TaskStatus::Todo),
+    ("approved", TaskStatus::Todo),
+    ("design", TaskStatus::Todo),
+    ("ready", TaskStatus::Todo),
+    ("in progress", TaskStatus::InProgress),
+    ("doing", TaskStatus::InProgress),
+    ("active", TaskStatus::InProgress),
+    ("started", TaskStatus::InProgress),
+    ("committed", TaskStatus::InProgress),
+    ("in review", TaskStatus::InProgress),
+    ("review", TaskStatus::InProgress),
+    ("done", TaskStatus::Done),
+    ("closed", TaskStatus::Done),
+    ("complete", TaskStatus::Done),
+    ("completed", TaskStatus::Done),
+    ("resolved", TaskStatus::Done),
+    ("finished", TaskStatus::Done),
+];
+
+pub fn status<'a>(name: &str, mappings: impl IntoIterator<Item = (&'a String, &'a TaskStatus)>) -> Option<TaskStatus> {
+    let name = normalize(name);
+    mappings.into_iter()
+        .find(|(state, _)| normalize(state) == name)
+        .map(|(_, status)| status.clone())
+        .or_else(|| COMMON_STATES.iter().find(|(state, _)| normalize(state) == name).map(|(_, status)| status.clone()))
+}
+
+pub fn validate(mapping: &BTreeMap<String, TaskStatus>) -> Result<()> {
+    let mut seen: Vec<String> = Vec::new();
+    for state in mapping.keys() {
+        let normalized = normalize(state);
+        if normalized.is_empty() {
+            return Err(anyhow!("Every mapped state needs a name"));
+        }
+        if seen.contains(&normalized) {
+            return Err(anyhow!("The state \"{}\" is mapped twice", state.trim()));
+        }
+        seen.push(normalized);
+    }
+    Ok(())
+}
+
+fn normalize(state: &str) -> String {
+    state.trim().to_lowercase().replace(['_', '-'], " ")
+}
@@ -1,100 +1,330 @@
-use crate::models::{RoadmapData, Task, TaskStatus, TaskPriority, Project};
+use crate::commands::ExportData;
+use crate::envelope;
+use crate::integrity;
+use crate::location;
+use crate::migrations;
+use crate::persistence::{Listener, ListenerSlot, PersistenceStatus, PersistenceWorker};
+use crate::secrets;
+use crate::models::{RoadmapData, Task, TaskStatus, TaskPriority, Project, ReviewState, TimeSession};
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json;
+use std::collections::HashMap;
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-// Legacy data structures for migration
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct LegacyTask {
-    pub id: u32,
-    pub title: String,
-    pub description: String,
-    pub status: TaskStatus,
-    pub priority: TaskPriority,
-    pub created_at: String,
-    pub updated_at: String,
-    pub due_date: Option<String>,
-    pub tags: Vec<String>,
-    pub subtasks: Vec<crate::models::Subtask>,
-    pub comments: Vec<crate::models::Comment>,
-    pub time_spent: u32,
-    pub estimated_time: Option<u32>,
-    pub attachments: Vec<crate::models::Attachment>,
-    // Missing project_id
+// On-disk encoding of the data file. Detected on load, so switching formats keeps the same path.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageFormat {
+    Json,
+    MessagePack,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct LegacyRoadmapData {
-    pub tasks: Vec<LegacyTask>,
-    pub theme: Option<String>,
-    pub version: Option<String>,
+impl StorageFormat {
+    // JSON data files always start with an object; MessagePack maps never start with `{`
+    fn detect(bytes: &[u8]) -> Self {
+        match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') | None => StorageFormat::Json,
+            Some(_) => StorageFormat::MessagePack,
+        }
+    }
+}
+
+// Returned by every save while the data is open read-only
+#[derive(Debug)]
+pub struct PermissionDenied;
+
+impl fmt::Display for PermissionDenied {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Permission denied: the data is open read-only")
+    }
 }
 
+impl std::error::Error for PermissionDenied {}
+
 pub struct Storage {
     data_file_path: PathBuf,
+    format: StorageFormat,
+    // In-memory copy of the data file; every save goes through `save_data` to keep it current
+    cache: RoadmapData,
+    // id -> position in `cache.tasks` / `cache.projects`, rebuilt whenever the cache is replaced
+    task_index: HashMap<u32, usize>,
+    project_index: HashMap<u32, usize>,
+    // Set for the app's own storage; without it saves write the file directly
+    writer: Option<PersistenceWorker>,
+    listener: ListenerSlot,
+    // Saves fail with PermissionDenied and nothing is written, not even migrations
+    read_only: bool,
 }
 
 impl Storage {
+    // roadmap.json in the working directory, next to the executable in portable mode, or
+    // wherever `relocate_data` moved it
     pub fn new() -> Result<Self> {
-        // For now, use current directory. In a real app, we'd use the proper app data directory
-        let data_file_path = PathBuf::from("roadmap.json");
-        
-        Ok(Storage { data_file_path })
+        Self::new_with_path(location::resolve_data_path())
     }
 
-    pub fn new_with_path(file_path: PathBuf) -> Self {
-        Storage {
+    pub fn new_with_path(file_path: PathBuf) -> Result<Self> {
+        let mut storage = Storage {
             data_file_path: file_path,
+            format: StorageFormat::Json,
+            cache: RoadmapData::default(),
+            task_index: HashMap::new(),
+            project_index: HashMap::new(),
+            writer: None,
+            listener: ListenerSlot::default(),
+            read_only: false,
+        };
+        if storage.data_file_path.exists() {
+            storage.format = StorageFormat::detect(&fs::read(&storage.data_file_path)?);
+            storage.read_only = fs::metadata(&storage.data_file_path)?.permissions().readonly();
+        }
+        // E.g. on a read-only mount, which the permission bits don't show; opening it read-only
+        // beats failing on every save
+        if !can_write_next_to(&storage.data_file_path) {
+            storage.read_only = true;
         }
+        storage.cache = storage.read_from_disk()?;
+        storage.reindex();
+        Ok(storage)
     }
 
-    pub fn load_data(&self) -> Result<RoadmapData> {
+    // Opens a data file, backup or export for viewing. Nothing is written to it, so older
+    // versions are migrated in memory only.
+    pub fn open_read_only(file_path: PathBuf) -> Result<Self> {
+        let (format, cache) = match fs::read(&file_path) {
+            Ok(bytes) => (StorageFormat::detect(&bytes), Self::parse_backup(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (StorageFormat::Json, RoadmapData::default()),
+            Err(e) => return Err(anyhow!("Failed to read {}: {}", file_path.display(), e)),
+        };
+        let mut storage = Storage {
+            data_file_path: file_path,
+            format,
+            cache,
+            task_index: HashMap::new(),
+            project_index: HashMap::new(),
+            writer: None,
+            listener: ListenerSlot::default(),
+            read_only: true,
+        };
+        storage.reindex();
+        Ok(storage)
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    // Leaving read-only mode is only possible for the app's data file, and only when it is writable
+    pub fn set_read_only(&mut self, read_only: bool) -> Result<()> {
+        if !read_only && self.read_only {
+            if !location::is_data_file(&self.data_file_path) {
+                return Err(anyhow!("{} is not the data file; it can only be viewed", self.data_file_path.display()));
+            }
+            if fs::metadata(&self.data_file_path).map(|m| m.permissions().readonly()).unwrap_or(false) {
+                return Err(anyhow!("{} is not writable", self.data_file_path.display()));
+            }
+        }
+        self.read_only = read_only;
+        Ok(())
+    }
+
+    pub fn check_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(PermissionDenied.into());
+        }
+        Ok(())
+    }
+
+    // Reads (and migrates) the data file, bypassing the cache
+    pub fn read_from_disk(&self) -> Result<RoadmapData> {
         if !self.data_file_path.exists() {
             // Create default file if it doesn't exist
             let default_data = RoadmapData::default();
-            self.save_data(&default_data)?;
+            if !self.read_only {
+                self.write_to_disk(&default_data)?;
+            }
             return Ok(default_data);
         }
 
-        let contents = fs::read_to_string(&self.data_file_path)?;
+        let bytes = fs::read(&self.data_file_path)?;
+        let mut value: serde_json::Value = match StorageFormat::detect(&bytes) {
+            StorageFormat::Json => serde_json::from_slice(&bytes)
+                .map_err(|e| anyhow!("Failed to parse JSON: {}", e))?,
+            StorageFormat::MessagePack => rmp_serde::from_slice(&bytes)
+                .map_err(|e| anyhow!("Failed to parse MessagePack: {}", e))?,
+        };
+        
+        if !migrations::needs_migration(&value)? {
+            return serde_json::from_value(value).map_err(|e| anyhow!("Failed to read data: {}", e));
+        }
+        if self.read_only {
+            migrations::migrate(&mut value)?;
+            return serde_json::from_value(value).map_err(|e| anyhow!("Failed to read migrated data: {}", e));
+        }
         
-        // Try to parse as current format first
-        match serde_json::from_str::<RoadmapData>(&contents) {
-            Ok(mut data) => {
-                // Migrate data if needed
-                self.migrate_data(&mut data)?;
-                Ok(data)
-            }
-            Err(_) => {
-                // Try to parse as legacy format (without projects)
-                match serde_json::from_str::<LegacyRoadmapData>(&contents) {
-                    Ok(legacy_data) => {
-                        let migrated_data = self.migrate_from_legacy(legacy_data)?;
-                        self.save_data(&migrated_data)?;
-                        Ok(migrated_data)
-                    }
-                    Err(e) => Err(anyhow!("Failed to parse JSON: {}", e))
-                }
-            }
+        // Keep the original file next to the data file before rewriting it
+        let mut backup_path = self.data_file_path.clone().into_os_string();
+        backup_path.push(format!(".v{}.bak", migrations::data_version(&value)));
+        fs::write(&backup_path, &bytes)?;
+        
+        migrations::migrate(&mut value)?;
+        let data: RoadmapData = serde_json::from_value(value)
+            .map_err(|e| anyhow!("Failed to read migrated data: {}", e))?;
+        self.write_to_disk(&data)?;
+        Ok(data)
+    }
+
+    // Borrow the cached data; preferred for read-only commands
+    pub fn data(&self) -> &RoadmapData {
+        &self.cache
+    }
+
+    // Owned copy of the cached data for read-modify-save flows
+    pub fn load_data(&self) -> Result<RoadmapData> {
+        Ok(self.cache.clone())
+    }
+
+    // With the persistence worker running this only queues the write; see `flush`
+    pub fn save_data(&mut self, data: &RoadmapData) -> Result<()> {
+        self.write_to_disk(data)?;
+        self.cache = data.clone();
+        self.reindex();
+        Ok(())
+    }
+
+    fn reindex(&mut self) {
+        self.task_index = self.cache.tasks.iter().enumerate().map(|(i, t)| (t.id, i)).collect();
+        self.project_index = self.cache.projects.iter().enumerate().map(|(i, p)| (p.id, i)).collect();
+    }
+
+    pub fn task(&self, id: u32) -> Option<&Task> {
+        self.task_index.get(&id).map(|i| &self.cache.tasks[*i])
+    }
+
+    pub fn project(&self, id: u32) -> Option<&Project> {
+        self.project_index.get(&id).map(|i| &self.cache.projects[*i])
+    }
+
+    // Edits a single task in place and writes the file, without cloning the whole data set.
+    // The closure must not change the task's id.
+    pub fn modify_task<F>(&mut self, id: u32, modify: F) -> Result<Task>
+    where
+        F: FnOnce(&mut Task),
+    {
+        let index = *self.task_index.get(&id)
+            .ok_or_else(|| anyhow!("Task with id {} not found", id))?;
+        
+        let previous = self.cache.tasks[index].clone();
+        modify(&mut self.cache.tasks[index]);
+        
+        if let Err(e) = self.write_to_disk(&self.cache) {
+            // A refused save (read-only, no worker) leaves the cache as it was; failures of a
+            // queued write are reported by the worker instead
+            self.cache.tasks[index] = previous;
+            return Err(e);
+        }
+        
+        Ok(self.cache.tasks[index].clone())
+    }
+
+    pub fn modify_project<F>(&mut self, id: u32, modify: F) -> Result<Project>
+    where
+        F: FnOnce(&mut Project),
+    {
+        let index = *self.project_index.get(&id)
+            .ok_or_else(|| anyhow!("Project with id {} not found", id))?;
+        
+        let previous = self.cache.projects[index].clone();
+        modify(&mut self.cache.projects[index]);
+        
+        if let Err(e) = self.write_to_disk(&self.cache) {
+            self.cache.projects[index] = previous;
+            return Err(e);
+        }
+        
+        Ok(self.cache.projects[index].clone())
+    }
+
+    // Hands saves to a background worker from now on, see `persistence`
+    pub fn start_persistence_worker(&mut self) {
+        if self.writer.is_none() {
+            self.writer = Some(PersistenceWorker::start(self.data_file_path.clone(), self.listener.clone()));
+        }
+    }
+
+    // Told when background writes start failing and when they recover
+    pub fn set_persistence_listener(&self, listener: Listener) {
+        if let Ok(mut slot) = self.listener.lock() {
+            *slot = Some(listener);
         }
     }
 
-    pub fn save_data(&self, data: &RoadmapData) -> Result<()> {
-        let json_content = serde_json::to_string_pretty(data)
-            .map_err(|e| anyhow!("Failed to serialize data: {}", e))?;
+    // Switches to a new data file, written from `data` before anything else changes.
+    // The old file is left for the caller to remove.
+    pub fn relocate(&mut self, new_path: PathBuf, data: RoadmapData) -> Result<()> {
+        self.check_writable()?;
+        self.flush()?;
+        write_file(&new_path, self.format, &data)?;
+        
+        // The old worker is idle after the flush; dropping it stops its thread
+        let had_writer = self.writer.take().is_some();
+        self.data_file_path = new_path;
+        self.cache = data;
+        self.reindex();
+        if had_writer {
+            self.start_persistence_worker();
+        }
+        Ok(())
+    }
+
+    // Waits for queued saves to reach the disk; a no-op without a worker
+    pub fn flush(&self) -> Result<()> {
+        match &self.writer {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn persistence_status(&self) -> PersistenceStatus {
+        self.writer.as_ref().map(|w| w.status()).unwrap_or_default()
+    }
+
+    fn write_to_disk(&self, data: &RoadmapData) -> Result<()> {
+        self.check_writable()?;
+        match &self.writer {
+            Some(writer) => writer.enqueue(data.clone(), self.format),
+            None => write_file(&self.data_file_path, self.format, data),
+        }
+    }
+
+    pub fn get_format(&self) -> StorageFormat {
+        self.format
+    }
+
+    // Rewrites the data file in the requested format
+    pub fn convert_format(&mut self, format: StorageFormat) -> Result<()> {
+        if self.format == format {
+            return Ok(());
+        }
         
-        fs::write(&self.data_file_path, json_content)?;
+        let previous = self.format;
+        self.format = format;
+        if let Err(e) = self.write_to_disk(&self.cache) {
+            self.format = previous;
+            return Err(e);
+        }
         Ok(())
     }
 
     pub fn get_tasks(&self) -> Result<Vec<Task>> {
-        let data = self.load_data()?;
-        Ok(data.tasks)
+        Ok(self.cache.tasks.clone())
     }
 
-    pub fn add_task(&self, title: String, description: String, priority: Option<TaskPriority>) -> Result<Task> {
+    pub fn add_task(&mut self, title: String, description: String, priority: Option<TaskPriority>) -> Result<Task> {
         let mut data = self.load_data()?;
         
         // Get current project ID or use default
@@ -117,68 +347,92 @@ impl Storage {
         Ok(task)
     }
 
-    pub fn update_task(&self, id: u32, title: Option<String>, description: Option<String>, 
+    pub fn update_task(&mut self, id: u32, title: Option<String>, description: Option<String>, 
                       status: Option<TaskStatus>, priority: Option<TaskPriority>) -> Result<Task> {
-        let mut data = self.load_data()?;
+        if status == Some(TaskStatus::Done) {
+            let task = self.task(id).ok_or_else(|| anyhow!("Task with id {} not found", id))?;
+            if task.requires_review && task.review_state != ReviewState::Approved && task.status != TaskStatus::Done {
+                return Err(anyhow!("Task {} needs an approved review before it can be marked as done", id));
+            }
+        }
         
-        let task_index = data.tasks.iter()
-            .position(|t| t.id == id)
+        self.modify_task(id, |task| {
+            if let (Some(title), Some(description)) = (title, description) {
+                task.update_content(title, description);
+            }
+            
+            if let Some(status) = status {
+                task.update_status(status);
+            }
+            
+            if let Some(priority) = priority {
+                task.update_priority(priority);
+            }
+        })
+    }
+
+    pub fn delete_task(&mut self, id: u32) -> Result<()> {
+        let index = *self.task_index.get(&id)
             .ok_or_else(|| anyhow!("Task with id {} not found", id))?;
         
-        let task = &mut data.tasks[task_index];
-        
-        if let (Some(title), Some(description)) = (title, description) {
-            task.update_content(title, description);
-        }
-        
-        if let Some(status) = status {
-            task.update_status(status);
-        }
+        let mut data = self.load_data()?;
+        data.tasks.remove(index);
         
-        if let Some(priority) = priority {
-            task.update_priority(priority);
+        // Drop references from tasks that depended on the deleted one
+        for task in &mut data.tasks {
+            task.dependencies.retain(|d| *d != id);
         }
         
-        let updated_task = task.clone();
         self.save_data(&data)?;
-        
-        Ok(updated_task)
+        Ok(())
     }
 
-    pub fn delete_task(&self, id: u32) -> Result<()> {
-        let mut data = self.load_data()?;
-        
-        let initial_len = data.tasks.len();
-        data.tasks.retain(|t| t.id != id);
-        
-        if data.tasks.len() == initial_len {
-            return Err(anyhow!("Task with id {} not found", id));
+    // Adds the session's minutes to the task and keeps the session in the time log
+    pub fn log_time_session(&mut self, task_id: u32, started_at: DateTime<Utc>, ended_at: DateTime<Utc>) -> Result<Task> {
+        if ended_at <= started_at {
+            return Err(anyhow!("Session must end after it starts"));
         }
+        let index = *self.task_index.get(&task_id)
+            .ok_or_else(|| anyhow!("Task with id {} not found", task_id))?;
+        
+        let mut data = self.load_data()?;
+        let minutes = ((ended_at - started_at).num_seconds() as f64 / 60.0).round() as u32;
+        
+        let session_id = data.time_log.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+        data.time_log.push(TimeSession {
+            id: session_id,
+            task_id,
+            started_at: started_at.to_rfc3339(),
+            ended_at: ended_at.to_rfc3339(),
+            minutes,
+        });
+        
+        let task = &mut data.tasks[index];
+        task.add_time(minutes);
+        let updated_task = task.clone();
         
         self.save_data(&data)?;
-        Ok(())
+        Ok(updated_task)
     }
 
     pub fn get_task_by_id(&self, id: u32) -> Result<Task> {
-        let data = self.load_data()?;
-        data.tasks.into_iter()
-            .find(|t| t.id == id)
+        self.task(id)
+            .cloned()
             .ok_or_else(|| anyhow!("Task with id {} not found", id))
     }
 
     pub fn get_tasks_by_status(&self, status: TaskStatus) -> Result<Vec<Task>> {
-        let data = self.load_data()?;
-        Ok(data.tasks.into_iter()
+        Ok(self.cache.tasks.iter()
             .filter(|t| t.status == status)
+            .cloned()
             .collect())
     }
 
     pub fn get_theme(&self) -> Result<String> {
-        let data = self.load_data()?;
-        Ok(data.theme.unwrap_or_else(|| "light".to_string()))
+        Ok(self.cache.theme.clone().unwrap_or_else(|| "light".to_string()))
     }
 
-    pub fn set_theme(&self, theme: String) -> Result<()> {
+    pub fn set_theme(&mut self, theme: String) -> Result<()> {
         let mut data = self.load_data()?;
         data.theme = Some(theme);
         self.save_data(&data)?;
@@ -189,22 +443,64 @@ impl Storage {
         &self.data_file_path
     }
 
+    // Written in the export format, so the backup carries a checksum and can be imported too
     pub fn backup_data(&self, backup_path: PathBuf) -> Result<()> {
-        let data = self.load_data()?;
-        let json_content = serde_json::to_string_pretty(&data)?;
-        fs::write(backup_path, json_content)?;
+        fs::write(backup_path, self.backup_bytes()?)?;
         Ok(())
     }
 
-    pub fn restore_data(&self, backup_path: PathBuf) -> Result<()> {
-        let contents = fs::read_to_string(backup_path)?;
-        let data: RoadmapData = serde_json::from_str(&contents)?;
+    pub fn backup_bytes(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec_pretty(&ExportData::new(self.cache.clone())?)?)
+    }
+
+    fn read_backup(backup_path: PathBuf) -> Result<RoadmapData> {
+        Self::parse_backup(&fs::read(backup_path)?)
+    }
+
+    // Backups are always JSON, but a copy of a binary data file restores as well. Older backups
+    // hold the bare data without a checksum. Backups from older versions are migrated in memory;
+    // the backup file is left untouched.
+    pub fn parse_backup(bytes: &[u8]) -> Result<RoadmapData> {
+        if envelope::is_sealed(bytes) {
+            return Err(anyhow!("The backup is encrypted; restore it through the remote backup settings"));
+        }
+        let mut value: serde_json::Value = match StorageFormat::detect(bytes) {
+            StorageFormat::Json => integrity::parse_json(bytes)?,
+            StorageFormat::MessagePack => rmp_serde::from_slice(bytes)
+                .map_err(|e| anyhow!("The file is truncated or not a RuidMap backup: {}", e))?,
+        };
+        if value.get("data").is_some() {
+            integrity::verify(&value)?;
+            value = value["data"].take();
+        }
+        migrations::migrate(&mut value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub fn restore_data(&mut self, backup_path: PathBuf) -> Result<()> {
+        let mut data = Self::read_backup(backup_path)?;
+        secrets::carry_over(&self.cache, &mut data);
         self.save_data(&data)?;
-        Ok(())
+        // A restore only counts once it is on disk
+        self.flush()
+    }
+
+    pub fn preview_partial_restore(&self, backup_path: PathBuf, selection: &RestoreSelection) -> Result<RestorePreview> {
+        let backup = Self::read_backup(backup_path)?;
+        let (_, preview) = merge_from_backup(&self.cache, &backup, selection)?;
+        Ok(preview)
+    }
+
+    pub fn restore_partial(&mut self, backup_path: PathBuf, selection: &RestoreSelection) -> Result<RestorePreview> {
+        let backup = Self::read_backup(backup_path)?;
+        let (data, preview) = merge_from_backup(&self.cache, &backup, selection)?;
+        self.save_data(&data)?;
+        self.flush()?;
+        Ok(preview)
     }
 
     // Project management methods
-    pub fn create_project(&self, name: String, description: String, color: Option<String>, icon: Option<String>) -> Result<crate::models::Project> {
+    pub fn create_project(&mut self, name: String, description: String, color: Option<String>, icon: Option<String>) -> Result<crate::models::Project> {
         let mut data = self.load_data()?;
         
         let new_id = data.projects.iter()
@@ -225,38 +521,32 @@ impl Storage {
     }
 
     pub fn get_projects(&self) -> Result<Vec<crate::models::Project>> {
-        let data = self.load_data()?;
-        Ok(data.projects)
+        Ok(self.cache.projects.clone())
     }
 
     pub fn get_current_project(&self) -> Result<Option<crate::models::Project>> {
-        let data = self.load_data()?;
+        let data = &self.cache;
         
         if let Some(current_id) = data.current_project_id {
-            let project = data.projects.iter()
-                .find(|p| p.id == current_id)
-                .cloned();
-            Ok(project)
+            Ok(self.project(current_id).cloned())
         } else {
             Ok(None)
         }
     }
 
-    pub fn switch_project(&self, project_id: u32) -> Result<crate::models::Project> {
-        let mut data = self.load_data()?;
-        
-        let project = data.projects.iter()
-            .find(|p| p.id == project_id)
+    pub fn switch_project(&mut self, project_id: u32) -> Result<crate::models::Project> {
+        let project = self.project(project_id)
             .ok_or_else(|| anyhow!("Project with id {} not found", project_id))?
             .clone();
         
+        let mut data = self.load_data()?;
         data.current_project_id = Some(project_id);
         self.save_data(&data)?;
         
         Ok(project)
     }
 
-    pub fn delete_project(&self, project_id: u32) -> Result<()> {
+    pub fn delete_project(&mut self, project_id: u32) -> Result<()> {
         let mut data = self.load_data()?;
         
         // Don't allow deleting if it's the only project
@@ -268,8 +558,14 @@ impl Storage {
         data.projects.retain(|p| p.id != project_id);
         
         // Remove all tasks from this project
+        let removed: Vec<u32> = data.tasks.iter().filter(|t| t.project_id == project_id).map(|t| t.id).collect();
         data.tasks.retain(|t| t.project_id != project_id);
         
+        // Drop references from other projects' tasks that depended on the removed ones
+        for task in &mut data.tasks {
+            task.dependencies.retain(|d| !removed.contains(d));
+        }
+        
         // If current project was deleted, switch to first available
         if data.current_project_id == Some(project_id) {
             data.current_project_id = data.projects.first().map(|p| p.id);
@@ -280,72 +576,164 @@ impl Storage {
     }
 
     pub fn get_tasks_by_project(&self, project_id: u32) -> Result<Vec<Task>> {
-        let data = self.load_data()?;
-        
-        let filtered_tasks: Vec<Task> = data.tasks.into_iter()
+        let filtered_tasks: Vec<Task> = self.cache.tasks.iter()
             .filter(|t| t.project_id == project_id)
+            .cloned()
             .collect();
         
         Ok(filtered_tasks)
     }
+}
 
-    // Migration methods
-    fn migrate_data(&self, data: &mut RoadmapData) -> Result<()> {
-        // Check if data needs migration based on version
-        let current_version = "1.0.0";
-        
-        if data.version != current_version {
-            // Add project_id to tasks that don't have it
-            for task in &mut data.tasks {
-                if task.project_id == 0 {
-                    task.project_id = data.current_project_id.unwrap_or(1);
-                }
-            }
+// Saves create a temporary file next to the data file, so that is what is tried. Only refusals
+// count; other errors, such as a folder that doesn't exist yet, show up when saving.
+fn can_write_next_to(path: &Path) -> bool {
+    let mut probe = path.as_os_str().to_owned();
+    probe.push(".tmp");
+    match fs::OpenOptions::new().write(true).create(true).truncate(false).open(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(e) => !matches!(e.kind(), std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::ReadOnlyFilesystem),
+    }
+}
 
-            // Ensure at least one project exists
-            if data.projects.is_empty() {
-                let default_project = Project::new(1, "Default Project".to_string());
-                data.projects.push(default_project);
-                data.current_project_id = Some(1);
-            }
+// Written next to the data file and renamed over it, so a crash never leaves a half-written file
+pub(crate) fn write_file(path: &Path, format: StorageFormat, data: &RoadmapData) -> Result<()> {
+    let content = match format {
+        StorageFormat::Json => serde_json::to_vec_pretty(data)
+            .map_err(|e| anyhow!("Failed to serialize data: {}", e))?,
+        // Named fields keep the file readable after fields are added to the models
+        StorageFormat::MessagePack => rmp_serde::to_vec_named(data)
+            .map_err(|e| anyhow!("Failed to serialize data: {}", e))?,
+    };
+    
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RestoreSelection {
+    // Selecting a project restores it together with all of its tasks
+    #[serde(default)]
+    pub project_ids: Vec<u32>,
+    #[serde(default)]
+    pub task_ids: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct RestorePreview {
+    pub projects_added: Vec<String>,
+    pub projects_replaced: Vec<String>,
+    pub tasks_added: Vec<String>,
+    pub tasks_replaced: Vec<String>,
+    pub dependencies_dropped: Vec<String>, // "task → dependency", for dependencies that weren't restored
+}
+
+// Copies the selected backup items into `live`. An item whose id still belongs to the same
+// record (matching created_at) is overwritten in place; anything else gets a fresh id.
+fn merge_from_backup(live: &RoadmapData, backup: &RoadmapData, selection: &RestoreSelection) -> Result<(RoadmapData, RestorePreview)> {
+    let mut data = live.clone();
+    let mut preview = RestorePreview::default();
+
+    for id in &selection.project_ids {
+        if !backup.projects.iter().any(|p| p.id == *id) {
+            return Err(anyhow!("Project with id {} not found in backup", id));
+        }
+    }
+    for id in &selection.task_ids {
+        if !backup.tasks.iter().any(|t| t.id == *id) {
+            return Err(anyhow!("Task with id {} not found in backup", id));
+        }
+    }
+
+    let tasks: Vec<&Task> = backup.tasks.iter()
+        .filter(|t| selection.task_ids.contains(&t.id) || selection.project_ids.contains(&t.project_id))
+        .collect();
 
-            data.version = current_version.to_string();
+    // Projects that were picked, plus any a restored task needs but the live data no longer has
+    let mut project_ids = selection.project_ids.clone();
+    for task in &tasks {
+        let still_present = live.projects.iter().any(|p| p.id == task.project_id && same_project(p, backup, task.project_id));
+        if !still_present && !project_ids.contains(&task.project_id) {
+            project_ids.push(task.project_id);
         }
+    }
 
-        Ok(())
+    let mut project_map: HashMap<u32, u32> = HashMap::new();
+    let mut next_project_id = data.projects.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+    for project in backup.projects.iter().filter(|p| project_ids.contains(&p.id)) {
+        let restored_id = match data.projects.iter_mut().find(|p| p.id == project.id && p.created_at == project.created_at) {
+            Some(existing) => {
+                *existing = project.clone();
+                preview.projects_replaced.push(project.name.clone());
+                project.id
+            }
+            None => {
+                let mut restored = project.clone();
+                restored.id = next_project_id;
+                next_project_id += 1;
+                data.projects.push(restored);
+                preview.projects_added.push(project.name.clone());
+                next_project_id - 1
+            }
+        };
+        project_map.insert(project.id, restored_id);
     }
 
-    fn migrate_from_legacy(&self, legacy_data: LegacyRoadmapData) -> Result<RoadmapData> {
-        // Create default project
-        let default_project = Project::new(1, "Default Project".to_string());
-        
-        // Convert legacy tasks to new format
-        let tasks: Vec<Task> = legacy_data.tasks.into_iter().map(|legacy_task| {
-            Task {
-                id: legacy_task.id,
-                project_id: 1, // Assign to default project
-                title: legacy_task.title,
-                description: legacy_task.description,
-                status: legacy_task.status,
-                priority: legacy_task.priority,
-                created_at: legacy_task.created_at,
-                updated_at: legacy_task.updated_at,
-                due_date: legacy_task.due_date,
-                tags: legacy_task.tags,
-                subtasks: legacy_task.subtasks,
-                comments: legacy_task.comments,
-                time_spent: legacy_task.time_spent,
-                estimated_time: legacy_task.estimated_time,
-                attachments: legacy_task.attachments,
+    let mut task_map: HashMap<u32, u32> = HashMap::new();
+    let mut next_task_id = data.tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    for task in &tasks {
+        let mut restored = (*task).clone();
+        restored.project_id = project_map.get(&task.project_id).copied().unwrap_or(task.project_id);
+
+        match data.tasks.iter_mut().find(|t| t.id == task.id && t.created_at == task.created_at) {
+            Some(existing) => {
+                *existing = restored;
+                task_map.insert(task.id, task.id);
+                preview.tasks_replaced.push(task.title.clone());
             }
-        }).collect();
-
-        Ok(RoadmapData {
-            tasks,
-            projects: vec![default_project],
-            current_project_id: Some(1),
-            theme: legacy_data.theme,
-            version: "1.0.0".to_string(),
-        })
+            None => {
+                restored.id = next_task_id;
+                task_map.insert(task.id, next_task_id);
+                next_task_id += 1;
+                data.tasks.push(restored);
+                preview.tasks_added.push(task.title.clone());
+            }
+        }
+    }
+
+    // Point restored dependencies at the remapped ids. One that wasn't restored is only kept while
+    // its id still belongs to the same task; otherwise the id may now be an unrelated task's.
+    for task in &tasks {
+        let mut dependencies = Vec::new();
+        for id in &task.dependencies {
+            let original = backup.tasks.iter().find(|t| t.id == *id);
+            let target = task_map.get(id).copied().or_else(|| {
+                let original = original?;
+                live.tasks.iter().any(|t| t.id == *id && t.created_at == original.created_at).then_some(*id)
+            });
+            match target {
+                Some(target) => dependencies.push(target),
+                None => preview.dependencies_dropped.push(format!(
+                    "{} → {}",
+                    task.title,
+                    original.map(|t| t.title.as_str()).unwrap_or("a deleted task"),
+                )),
+            }
+        }
+        if let Some(restored) = data.tasks.iter_mut().find(|t| Some(&t.id) == task_map.get(&task.id)) {
+            restored.dependencies = dependencies;
+        }
     }
-}
\ No newline at end of file
+
+    Ok((data, preview))
+}
+
+fn same_project(live: &Project, backup: &RoadmapData, id: u32) -> bool {
+    backup.projects.iter().any(|p| p.id == id && p.created_at == live.created_at)
+}
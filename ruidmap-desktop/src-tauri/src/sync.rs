@@ -0,0 +1,475 @@
+use crate::commands::AppState;
+use crate::envelope;
+use crate::models::{Project, RoadmapData, Task};
+use crate::remote_backup::{self, RemoteStore};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+// Syncs devices through the remote backup folder, which only relays files and can't read them.
+// Each device uploads the changes it made since its last upload as a batch, sealed in an
+// envelope with the remote backup passphrase and named `ruidmap-sync-<device>-<sequence>.json`,
+// then applies the batches of other devices it hasn't applied yet. Batches hold whole tasks and
+// projects and the deleted ones; of two versions the newer `updated_at` wins. Ids are numbered
+// per device, so records are matched by their `sync_id` instead, and a task's project and
+// dependencies travel as sync ids too. Batches are kept, so a device that starts syncing later
+// catches up from the first.
+
+const NAME_PREFIX: &str = "ruidmap-sync-";
+const NAME_SUFFIX: &str = ".json";
+const BATCH_VERSION: u32 = 1;
+
+// Two syncs at once would upload the same sequence
+static SYNCING: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChangeBatch {
+    pub version: u32,
+    pub device_id: String,
+    pub sequence: u64,
+    pub created_at: String,
+    pub changes: Vec<Change>,
+}
+
+// The ids inside `project` and `task` are the sender's and are ignored
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "op", rename_all = "kebab-case")]
+pub enum Change {
+    UpsertProject { project: Project },
+    DeleteProject { sync_id: String },
+    UpsertTask { task: Task, project: String, dependencies: Vec<String> },
+    DeleteTask { sync_id: String },
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct SyncResult {
+    pub pushed: usize, // Changes uploaded
+    pub applied: usize, // Changes from other devices that were applied
+    pub skipped: Vec<String>,
+}
+
+pub fn seal_batch(batch: &ChangeBatch, passphrase: &str) -> Result<Vec<u8>> {
+    envelope::seal(&serde_json::to_vec(batch)?, passphrase)
+}
+
+// `name` is the file the batch was read from; a batch moved to another device's or sequence's
+// name is rejected, so the host can't replay it
+pub fn open_batch(bytes: &[u8], name: &str, passphrase: &str) -> Result<ChangeBatch> {
+    let batch: ChangeBatch = serde_json::from_slice(&envelope::open(bytes, passphrase)?)
+        .map_err(|e| anyhow!("Invalid change batch {}: {}", name, e))?;
+    if batch.version > BATCH_VERSION {
+        return Err(anyhow!("{} was made by a newer version of RuidMap (batch version {})", name, batch.version));
+    }
+    if parse_name(name) != Some((batch.device_id.as_str(), batch.sequence)) {
+        return Err(anyhow!("{} holds the batch of another device or sequence", name));
+    }
+    Ok(batch)
+}
+
+fn batch_name(device_id: &str, sequence: u64) -> String {
+    format!("{}{}-{}{}", NAME_PREFIX, device_id, sequence, NAME_SUFFIX)
+}
+
+// Device ids contain dashes, so the sequence is after the last one
+fn parse_name(name: &str) -> Option<(&str, u64)> {
+    let (device_id, sequence) = name.strip_prefix(NAME_PREFIX)?.strip_suffix(NAME_SUFFIX)?.rsplit_once('-')?;
+    Some((device_id, sequence.parse().ok()?))
+}
+
+// Gives records without a sync id, or sharing one with an earlier record (e.g. a copied task),
+// a new one. Returns whether any changed.
+pub fn assign_sync_ids(data: &mut RoadmapData) -> bool {
+    let mut seen = HashSet::new();
+    let mut changed = false;
+    let ids = data.projects.iter_mut().map(|p| &mut p.sync_id)
+        .chain(data.tasks.iter_mut().map(|t| &mut t.sync_id));
+    for sync_id in ids {
+        if sync_id.is_empty() || !seen.insert(sync_id.clone()) {
+            *sync_id = uuid::Uuid::new_v4().to_string();
+            seen.insert(sync_id.clone());
+            changed = true;
+        }
+    }
+    changed
+}
+
+// Tasks and projects updated after `since`, and the ones of `previous_*` (sync ids) that are gone
+pub fn changes_since(data: &RoadmapData, since: Option<&str>, previous_projects: &[String], previous_tasks: &[String]) -> Vec<Change> {
+    let since = since.and_then(|s| DateTime::parse_from_rfc3339(s).ok());
+    let updated = |updated_at: &str| match (since, DateTime::parse_from_rfc3339(updated_at)) {
+        (Some(since), Ok(updated_at)) => updated_at > since,
+        _ => true,
+    };
+    let project_sync_id = |id: u32| data.projects.iter().find(|p| p.id == id).map(|p| p.sync_id.clone());
+    let task_sync_id = |id: &u32| data.tasks.iter().find(|t| t.id == *id).map(|t| t.sync_id.clone());
+
+    let mut changes: Vec<Change> = data.projects.iter()
+        .filter(|p| updated(&p.updated_at))
+        .map(|p| Change::UpsertProject { project: p.clone() })
+        .collect();
+    changes.extend(data.tasks.iter()
+        .filter(|t| updated(&t.updated_at))
+        .map(|t| Change::UpsertTask {
+            task: t.clone(),
+            project: project_sync_id(t.project_id).unwrap_or_default(),
+            dependencies: t.dependencies.iter().filter_map(task_sync_id).collect(),
+        }));
+    changes.extend(previous_tasks.iter()
+        .filter(|sync_id| !data.tasks.iter().any(|t| &t.sync_id == *sync_id))
+        .map(|sync_id| Change::DeleteTask { sync_id: sync_id.clone() }));
+    changes.extend(previous_projects.iter()
+        .filter(|sync_id| !data.projects.iter().any(|p| &p.sync_id == *sync_id))
+        .map(|sync_id| Change::DeleteProject { sync_id: sync_id.clone() }));
+    changes
+}
+
+// Returns how many changes were applied; tasks that can't be placed are added to `skipped`
+pub fn apply(data: &mut RoadmapData, batch: &ChangeBatch, skipped: &mut Vec<String>) -> usize {
+    let mut applied = 0;
+    // Set once the batch's tasks are all in place, since they may point at later ones
+    let mut dependencies: Vec<(u32, &[String])> = Vec::new();
+    for change in &batch.changes {
+        match change {
+            Change::UpsertProject { project } => match data.projects.iter_mut().find(|p| p.sync_id == project.sync_id) {
+                Some(local) if newer(&project.updated_at, &local.updated_at) => {
+                    *local = Project { id: local.id, ..project.clone() };
+                    applied += 1;
+                }
+                Some(_) => {}
+                None => {
+                    let id = data.projects.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+                    data.projects.push(Project { id, ..project.clone() });
+                    applied += 1;
+                }
+            },
+            Change::DeleteProject { sync_id } => {
+                let Some(id) = data.projects.iter().find(|p| &p.sync_id == sync_id).map(|p| p.id) else {
+                    continue;
+                };
+                // The last project is kept, as when deleting it here
+                if data.projects.len() > 1 {
+                    data.projects.retain(|p| p.id != id);
+                    let removed: Vec<u32> = data.tasks.iter().filter(|t| t.project_id == id).map(|t| t.id).collect();
+                    data.tasks.retain(|t| t.project_id != id);
+                    for task in &mut data.tasks {
+                        task.dependencies.retain(|d| !removed.contains(d));
+                    }
+                    if data.current_project_id == Some(id) {
+                        data.current_project_id = data.projects.first().map(|p| p.id);
+                    }
+                    applied += 1;
+                }
+            }
+            Change::UpsertTask { task, project, dependencies: task_dependencies } => {
+                let Some(project_id) = data.projects.iter().find(|p| &p.sync_id == project).map(|p| p.id) else {
+                    skipped.push(format!("\"{}\": its project isn't on this device", task.title));
+                    continue;
+                };
+                match data.tasks.iter_mut().find(|t| t.sync_id == task.sync_id) {
+                    Some(local) if newer(&task.updated_at, &local.updated_at) => {
+                        *local = Task { id: local.id, project_id, dependencies: Vec::new(), ..task.clone() };
+                        dependencies.push((local.id, task_dependencies));
+                        applied += 1;
+                    }
+                    Some(_) => {}
+                    None => {
+                        let id = data.tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+                        data.tasks.push(Task { id, project_id, dependencies: Vec::new(), ..task.clone() });
+                        dependencies.push((id, task_dependencies));
+                        applied += 1;
+                    }
+                }
+            }
+            Change::DeleteTask { sync_id } => {
+                let Some(id) = data.tasks.iter().find(|t| &t.sync_id == sync_id).map(|t| t.id) else {
+                    continue;
+                };
+                data.tasks.retain(|t| t.id != id);
+                for task in &mut data.tasks {
+                    task.dependencies.retain(|d| *d != id);
+                }
+                applied += 1;
+            }
+        }
+    }
+
+    for (id, sync_ids) in dependencies {
+        let local_ids: Vec<u32> = sync_ids.iter()
+            .filter_map(|sync_id| data.tasks.iter().find(|t| &t.sync_id == sync_id).map(|t| t.id))
+            .collect();
+        if let Some(task) = data.tasks.iter_mut().find(|t| t.id == id) {
+            task.dependencies = local_ids;
+        }
+    }
+    applied
+}
+
+fn newer(incoming: &str, local: &str) -> bool {
+    match (DateTime::parse_from_rfc3339(incoming), DateTime::parse_from_rfc3339(local)) {
+        (Ok(incoming), Ok(local)) => incoming > local,
+        _ => incoming > local,
+    }
+}
+
+// Uploads this device's changes, then applies the other devices' new batches. The storage lock
+// is not held while talking to the host.
+pub fn sync_now(app: &AppHandle) -> Result<SyncResult> {
+    let _syncing = SYNCING.lock().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    let state = app.state::<AppState>();
+    let (remote, mut sync, snapshot_at, changes, projects, tasks) = {
+        let mut storage = state.0.write().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+        // What was uploaded and applied couldn't be recorded
+        storage.check_writable()?;
+        let mut data = storage.load_data()?;
+        if assign_sync_ids(&mut data) {
+            storage.save_data(&data)?;
+        }
+        let sync = data.settings.sync.clone();
+        let changes = changes_since(&data, sync.last_pushed_at.as_deref(), &sync.pushed_projects, &sync.pushed_tasks);
+        (
+            data.settings.remote_backup.clone(),
+            sync,
+            Utc::now().to_rfc3339(),
+            changes,
+            data.projects.iter().map(|p| p.sync_id.clone()).collect::<Vec<String>>(),
+            data.tasks.iter().map(|t| t.sync_id.clone()).collect::<Vec<String>>(),
+        )
+    };
+    let target = remote.target.as_ref().ok_or_else(|| anyhow!("No remote backup folder is set to sync through"))?;
+    // Never relay readable data
+    let passphrase = remote.passphrase.as_deref().filter(|p| !p.is_empty())
+        .ok_or_else(|| anyhow!("Set a remote backup passphrase to sync; batches are always encrypted"))?;
+    let store = remote_backup::store(target)?;
+    if sync.device_id.is_empty() {
+        sync.device_id = uuid::Uuid::new_v4().to_string();
+    }
+
+    let mut result = SyncResult::default();
+    if !changes.is_empty() {
+        let batch = ChangeBatch {
+            version: BATCH_VERSION,
+            device_id: sync.device_id.clone(),
+            sequence: sync.sequence + 1,
+            created_at: snapshot_at.clone(),
+            changes,
+        };
+        store.upload(&batch_name(&batch.device_id, batch.sequence), &seal_batch(&batch, passphrase)?)?;
+        sync.sequence = batch.sequence;
+        result.pushed = batch.changes.len();
+    }
+    sync.last_pushed_at = Some(snapshot_at);
+    sync.pushed_projects = projects;
+    sync.pushed_tasks = tasks;
+
+    // The upload is recorded even if pulling fails, so the next sync doesn't reuse its sequence
+    let pulled = pull(store.as_ref(), passphrase, &sync.device_id, &sync.applied);
+
+    let mut storage = state.0.write().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    let mut data = storage.load_data()?;
+    for batch in pulled.as_deref().unwrap_or_default() {
+        result.applied += apply(&mut data, batch, &mut result.skipped);
+        sync.applied.insert(batch.device_id.clone(), batch.sequence);
+        // Records that arrived here are this device's to report deleted later
+        for change in &batch.changes {
+            match change {
+                Change::UpsertProject { project } => track(&mut sync.pushed_projects, &project.sync_id, data.projects.iter().any(|p| p.sync_id == project.sync_id)),
+                Change::UpsertTask { task, .. } => track(&mut sync.pushed_tasks, &task.sync_id, data.tasks.iter().any(|t| t.sync_id == task.sync_id)),
+                Change::DeleteProject { sync_id } => track(&mut sync.pushed_projects, sync_id, false),
+                Change::DeleteTask { sync_id } => track(&mut sync.pushed_tasks, sync_id, false),
+            }
+        }
+    }
+    data.settings.sync = sync;
+    storage.save_data(&data)?;
+    pulled.map(|_| result)
+}
+
+fn track(sync_ids: &mut Vec<String>, sync_id: &str, present: bool) {
+    let tracked = sync_ids.iter().any(|s| s == sync_id);
+    if present && !tracked {
+        sync_ids.push(sync_id.to_string());
+    } else if !present && tracked {
+        sync_ids.retain(|s| s != sync_id);
+    }
+}
+
+// The other devices' batches after the last applied ones, in order per device. A device's
+// batches stop at the first missing sequence, so none are skipped if the host hasn't got one yet.
+fn pull(store: &dyn RemoteStore, passphrase: &str, device_id: &str, applied: &BTreeMap<String, u64>) -> Result<Vec<ChangeBatch>> {
+    let mut names: Vec<(String, u64, String)> = store.list(NAME_PREFIX)?
+        .into_iter()
+        .filter_map(|(name, _)| {
+            let (device, sequence) = parse_name(&name)?;
+            (device != device_id).then(|| (device.to_string(), sequence, name.clone()))
+        })
+        .collect();
+    names.sort();
+
+    let mut next: BTreeMap<&str, u64> = BTreeMap::new();
+    let mut batches = Vec::new();
+    for (device, sequence, name) in &names {
+        let expected = next.entry(device).or_insert_with(|| applied.get(device).copied().unwrap_or(0) + 1);
+        if sequence == expected {
+            batches.push(open_batch(&store.download(name)?, name, passphrase)?);
+            *expected += 1;
+        }
+    }
+    Ok(batches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: u32, project_id: u32, sync_id: &str, updated_at: &str) -> Task {
+        let mut task = Task::new(id, project_id, sync_id.to_string(), String::new());
+        task.sync_id = sync_id.to_string();
+        task.updated_at = updated_at.to_string();
+        task
+    }
+
+    // One project with sync id "project", shared by every device
+    fn data(tasks: Vec<Task>) -> RoadmapData {
+        let mut data = RoadmapData::default();
+        data.projects[0].sync_id = "project".to_string();
+        data.tasks = tasks;
+        data
+    }
+
+    fn batch(changes: Vec<Change>) -> ChangeBatch {
+        ChangeBatch {
+            version: BATCH_VERSION,
+            device_id: "other".to_string(),
+            sequence: 1,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            changes,
+        }
+    }
+
+    fn upsert(task: Task, dependencies: &[&str]) -> Change {
+        Change::UpsertTask {
+            task,
+            project: "project".to_string(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn keeps_tasks_with_the_same_local_id_from_both_devices() {
+        let mut local = data(vec![task(1, 1, "mine", "2024-01-01T00:00:00Z")]);
+        let applied = apply(&mut local, &batch(vec![upsert(task(1, 1, "theirs", "2024-01-01T00:00:00Z"), &[])]), &mut Vec::new());
+
+        assert_eq!(applied, 1);
+        let ids: Vec<(u32, &str)> = local.tasks.iter().map(|t| (t.id, t.sync_id.as_str())).collect();
+        assert_eq!(ids, vec![(1, "mine"), (2, "theirs")]);
+    }
+
+    #[test]
+    fn newer_version_wins() {
+        let mut local = data(vec![task(5, 1, "shared", "2024-01-02T00:00:00Z")]);
+
+        let mut older = task(9, 1, "shared", "2024-01-01T00:00:00Z");
+        older.title = "older".to_string();
+        assert_eq!(apply(&mut local, &batch(vec![upsert(older, &[])]), &mut Vec::new()), 0);
+        assert_eq!(local.tasks[0].title, "shared");
+
+        // Compared as instants: 23:30-01:00 on the 1st is half an hour after the local version
+        let mut newer = task(9, 1, "shared", "2024-01-01T23:30:00-01:00");
+        newer.title = "newer".to_string();
+        assert_eq!(apply(&mut local, &batch(vec![upsert(newer, &[])]), &mut Vec::new()), 1);
+        assert_eq!(local.tasks.len(), 1);
+        assert_eq!((local.tasks[0].id, local.tasks[0].title.as_str()), (5, "newer"));
+    }
+
+    #[test]
+    fn skips_tasks_without_their_project() {
+        let mut local = data(Vec::new());
+        let mut skipped = Vec::new();
+        let change = Change::UpsertTask {
+            task: task(1, 7, "orphan", "2024-01-01T00:00:00Z"),
+            project: "unknown".to_string(),
+            dependencies: Vec::new(),
+        };
+
+        assert_eq!(apply(&mut local, &batch(vec![change]), &mut skipped), 0);
+        assert!(local.tasks.is_empty());
+        assert_eq!(skipped.len(), 1);
+    }
+
+    #[test]
+    fn resolves_dependencies_by_sync_id() {
+        let mut local = data(vec![task(1, 1, "mine", "2024-01-01T00:00:00Z")]);
+        // The dependency comes later in the batch, and the sender numbered it differently
+        let changes = vec![
+            upsert(task(1, 1, "first", "2024-01-01T00:00:00Z"), &["second", "mine", "missing"]),
+            upsert(task(2, 1, "second", "2024-01-01T00:00:00Z"), &[]),
+        ];
+        apply(&mut local, &batch(changes), &mut Vec::new());
+
+        let first = local.tasks.iter().find(|t| t.sync_id == "first").unwrap();
+        assert_eq!(first.dependencies, vec![3, 1]);
+    }
+
+    #[test]
+    fn deletes_strip_dependencies() {
+        let mut local = data(vec![
+            task(1, 1, "kept", "2024-01-01T00:00:00Z"),
+            task(2, 1, "deleted", "2024-01-01T00:00:00Z"),
+        ]);
+        local.tasks[0].dependencies = vec![2];
+
+        let applied = apply(&mut local, &batch(vec![Change::DeleteTask { sync_id: "deleted".to_string() }]), &mut Vec::new());
+        assert_eq!(applied, 1);
+        assert_eq!(local.tasks.len(), 1);
+        assert!(local.tasks[0].dependencies.is_empty());
+    }
+
+    #[test]
+    fn deletes_projects_with_their_tasks_but_keeps_the_last() {
+        let mut local = data(vec![task(1, 1, "kept", "2024-01-01T00:00:00Z")]);
+        let mut other = Project::new(2, "Other".to_string());
+        other.sync_id = "other".to_string();
+        local.projects.push(other);
+        local.tasks.push(task(2, 2, "removed", "2024-01-01T00:00:00Z"));
+        local.tasks[0].dependencies = vec![2];
+        local.current_project_id = Some(2);
+
+        let delete = |sync_id: &str| batch(vec![Change::DeleteProject { sync_id: sync_id.to_string() }]);
+        assert_eq!(apply(&mut local, &delete("other"), &mut Vec::new()), 1);
+        assert_eq!(local.projects.len(), 1);
+        assert_eq!(local.tasks.len(), 1);
+        assert!(local.tasks[0].dependencies.is_empty());
+        assert_eq!(local.current_project_id, Some(1));
+
+        assert_eq!(apply(&mut local, &delete("project"), &mut Vec::new()), 0);
+        assert_eq!(local.projects.len(), 1);
+    }
+
+    #[test]
+    fn reports_deleted_records() {
+        let local = data(vec![task(1, 1, "kept", "2024-01-01T00:00:00Z")]);
+        let previous_tasks = vec!["kept".to_string(), "gone".to_string()];
+        let changes = changes_since(&local, Some("2024-06-01T00:00:00Z"), &["project".to_string()], &previous_tasks);
+
+        // The project itself was updated just now, by RoadmapData::default
+        assert!(matches!(&changes[..], [
+            Change::UpsertProject { .. },
+            Change::DeleteTask { sync_id },
+        ] if sync_id == "gone"));
+    }
+
+    #[test]
+    fn assigns_missing_and_duplicate_sync_ids() {
+        let mut local = data(vec![task(1, 1, "copied", "2024-01-01T00:00:00Z"), task(2, 1, "copied", "2024-01-01T00:00:00Z")]);
+        local.tasks.push(task(3, 1, "", "2024-01-01T00:00:00Z"));
+
+        assert!(assign_sync_ids(&mut local));
+        assert_eq!(local.tasks[0].sync_id, "copied");
+        assert_ne!(local.tasks[1].sync_id, "copied");
+        assert!(!local.tasks[2].sync_id.is_empty());
+        assert!(!assign_sync_ids(&mut local));
+    }
+}
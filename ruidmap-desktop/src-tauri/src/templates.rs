@@ -0,0 +1,184 @@
+use crate::models::{RoadmapData, Task, TaskStatus};
+use crate::workdays;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tera::{Context, Tera};
+
+// Template-driven exports. The built-in templates are compiled into the binary; a file with
+// the same name in the `templates` folder next to the data file replaces one, and any other
+// file there becomes an additional template. Templates ending in .html are auto-escaped.
+
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("markdown.md", include_str!("../templates/markdown.md")),
+    ("report.html", include_str!("../templates/report.html")),
+    ("status-report.md", include_str!("../templates/status-report.md")),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportScope {
+    Workspace,
+    Project { project_id: u32 },
+    Tasks { task_ids: Vec<u32> },
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplateInfo {
+    pub name: String,
+    pub builtin: bool,
+    pub overridden: bool, // A user file replaces the built-in template
+}
+
+pub fn templates_dir(data_file: &Path) -> PathBuf {
+    data_file.parent().unwrap_or(Path::new(".")).join("templates")
+}
+
+pub fn list(dir: &Path) -> Vec<TemplateInfo> {
+    let user = user_templates(dir);
+    let mut templates: Vec<TemplateInfo> = BUILTIN_TEMPLATES.iter()
+        .map(|(name, _)| TemplateInfo {
+            name: name.to_string(),
+            builtin: true,
+            overridden: user.iter().any(|(n, _)| n == name),
+        })
+        .collect();
+    for (name, _) in user {
+        if !templates.iter().any(|t| t.name == name) {
+            templates.push(TemplateInfo { name, builtin: false, overridden: false });
+        }
+    }
+    templates
+}
+
+pub fn render(data: &RoadmapData, template: &str, scope: &ExportScope, dir: &Path) -> Result<String> {
+    let mut tera = Tera::default();
+    tera.add_raw_templates(BUILTIN_TEMPLATES.to_vec())?;
+
+    // A broken user template only matters when it's the one being rendered
+    for (name, path) in user_templates(dir) {
+        if let Err(e) = tera.add_template_file(&path, Some(&name)) {
+            if name == template {
+                return Err(anyhow!("Template {} has errors: {}", name, e));
+            }
+            eprintln!("Skipping template {}: {}", path.display(), e);
+        }
+    }
+    if !tera.get_template_names().any(|n| n == template) {
+        return Err(anyhow!("Template {} not found", template));
+    }
+
+    let context = Context::from_value(context(data, scope)?)?;
+    tera.render(template, &context).map_err(|e| {
+        // Tera keeps the useful part of the message in the error's source
+        let mut message = e.to_string();
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            message = format!("{}: {}", message, cause);
+            source = cause.source();
+        }
+        anyhow!(message)
+    })
+}
+
+fn user_templates(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut templates: Vec<(String, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| Some((path.file_name()?.to_str()?.to_string(), path)))
+        .filter(|(name, _)| !name.starts_with('.'))
+        .collect();
+    templates.sort();
+    templates
+}
+
+// Variables available to templates:
+//   title, generated_at, stats, tasks (every task in scope, with project_name and overdue),
+//   projects (one entry per project: project, stats and its tasks)
+fn context(data: &RoadmapData, scope: &ExportScope) -> Result<Value> {
+    let (title, project_ids, tasks): (String, Vec<u32>, Vec<&Task>) = match scope {
+        ExportScope::Workspace => (
+            "Roadmap".to_string(),
+            data.projects.iter().map(|p| p.id).collect(),
+            data.tasks.iter().collect(),
+        ),
+        ExportScope::Project { project_id } => {
+            let project = data.projects.iter()
+                .find(|p| p.id == *project_id)
+                .ok_or_else(|| anyhow!("Project with id {} not found", project_id))?;
+            (
+                project.name.clone(),
+                vec![project.id],
+                data.tasks.iter().filter(|t| t.project_id == *project_id).collect(),
+            )
+        }
+        ExportScope::Tasks { task_ids } => {
+            let tasks: Vec<&Task> = data.tasks.iter().filter(|t| task_ids.contains(&t.id)).collect();
+            let mut project_ids: Vec<u32> = Vec::new();
+            for task in &tasks {
+                if !project_ids.contains(&task.project_id) {
+                    project_ids.push(task.project_id);
+                }
+            }
+            ("Selected tasks".to_string(), project_ids, tasks)
+        }
+    };
+
+    let now = Utc::now();
+    let task_value = |task: &Task| -> Result<Value> {
+        let mut value = serde_json::to_value(task)?;
+        let project_name = data.projects.iter()
+            .find(|p| p.id == task.project_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_default();
+        let overdue = workdays::is_overdue(task, now, &data.settings.working_days);
+        if let Some(object) = value.as_object_mut() {
+            object.insert("project_name".to_string(), json!(project_name));
+            object.insert("overdue".to_string(), json!(overdue));
+        }
+        Ok(value)
+    };
+
+    let mut projects = Vec::new();
+    for project in data.projects.iter().filter(|p| project_ids.contains(&p.id)) {
+        let project_tasks: Vec<&Task> = tasks.iter().copied().filter(|t| t.project_id == project.id).collect();
+        projects.push(json!({
+            "project": project,
+            "stats": stats(&project_tasks),
+            "tasks": project_tasks.iter().map(|t| task_value(t)).collect::<Result<Vec<_>>>()?,
+        }));
+    }
+
+    Ok(json!({
+        "title": title,
+        "generated_at": now.to_rfc3339(),
+        "stats": stats(&tasks),
+        "tasks": tasks.iter().map(|t| task_value(t)).collect::<Result<Vec<_>>>()?,
+        "projects": projects,
+    }))
+}
+
+fn stats(tasks: &[&Task]) -> Value {
+    let count = |status: TaskStatus| tasks.iter().filter(|t| t.status == status).count();
+    let done = count(TaskStatus::Done);
+    let progress_percentage = if tasks.is_empty() {
+        0.0
+    } else {
+        done as f64 / tasks.len() as f64 * 100.0
+    };
+
+    json!({
+        "total": tasks.len(),
+        "todo": count(TaskStatus::Todo),
+        "in_progress": count(TaskStatus::InProgress),
+        "done": done,
+        "progress_percentage": progress_percentage,
+    })
+}
@@ -0,0 +1,44 @@
+// Access token checks for the local servers: the calendar feed, the shared board and the inbox.
+// Tokens are compared in constant time, so how long a rejection takes doesn't tell how much of a
+// guess was right.
+
+pub fn matches(candidate: &str, token: &str) -> bool {
+    let (candidate, token) = (candidate.as_bytes(), token.as_bytes());
+    candidate.len() == token.len()
+        && candidate.iter().zip(token).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+// Whether the `token` parameter of a query string matches; the value is percent-decoded first
+pub fn in_query(query: &str, token: &str) -> bool {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .any(|(key, value)| key == "token" && matches(&percent_decode(value), token))
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], escaped) {
+            (_, Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', None) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, None) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
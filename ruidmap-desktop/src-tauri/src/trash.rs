@@ -0,0 +1,150 @@
+use crate::commands::AppState;
+use crate::models::Task;
+use crate::storage::Storage;
+use anyhow::{anyhow, Result};
+use chrono::{Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+// Deferred deletes behind the "Undo" toast. A task stays in the data until its countdown runs
+// out, so undoing only drops the pending entry. The frontend follows along through the
+// task-delete-scheduled, task-delete-cancelled, task-deleted and task-delete-failed events.
+
+pub const MAX_DELAY_SECONDS: u64 = 300;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PendingDelete {
+    pub task_id: u32,
+    pub title: String,
+    pub delete_at: String, // RFC 3339
+    #[serde(skip)]
+    token: u64, // Tells a rescheduled delete's timer apart from the one it replaced
+    #[serde(skip)]
+    created_at: String, // Ids are reused, so this tells the task apart from a later one with its id
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DeleteFailure {
+    pub task_id: u32,
+    pub error: String,
+}
+
+#[derive(Default)]
+pub struct PendingDeletes(Mutex<PendingState>);
+
+#[derive(Default)]
+struct PendingState {
+    next_token: u64,
+    tasks: HashMap<u32, PendingDelete>,
+}
+
+impl PendingDeletes {
+    // Scheduling a task that is already pending restarts its countdown
+    pub fn schedule(&self, app: &AppHandle, task: &Task, seconds: u64) -> Result<PendingDelete> {
+        let mut state = self.0.lock().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+        state.next_token += 1;
+        let task_id = task.id;
+        let pending = PendingDelete {
+            task_id,
+            title: task.title.clone(),
+            delete_at: (Utc::now() + ChronoDuration::seconds(seconds as i64)).to_rfc3339(),
+            token: state.next_token,
+            created_at: task.created_at.clone(),
+        };
+        state.tasks.insert(task_id, pending.clone());
+        drop(state);
+
+        let handle = app.clone();
+        let token = pending.token;
+        thread::spawn(move || {
+            thread::sleep(Duration::from_secs(seconds));
+            finish(&handle, task_id, token);
+        });
+
+        let _ = app.emit("task-delete-scheduled", pending.clone());
+        Ok(pending)
+    }
+
+    pub fn cancel(&self, app: &AppHandle, task_id: u32) -> Result<Option<PendingDelete>> {
+        let mut state = self.0.lock().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+        let cancelled = state.tasks.remove(&task_id);
+        drop(state);
+
+        if let Some(pending) = &cancelled {
+            let _ = app.emit("task-delete-cancelled", pending.clone());
+        }
+        Ok(cancelled)
+    }
+
+    // For tasks deleted another way while pending; their countdowns have nothing left to do
+    pub fn deleted(&self, app: &AppHandle, task_ids: &[u32]) {
+        let removed: Vec<PendingDelete> = match self.0.lock() {
+            Ok(mut state) => task_ids.iter().filter_map(|id| state.tasks.remove(id)).collect(),
+            Err(_) => return,
+        };
+        for pending in removed {
+            let _ = app.emit("task-deleted", pending);
+        }
+    }
+
+    pub fn list(&self) -> Vec<PendingDelete> {
+        let mut pending: Vec<PendingDelete> = match self.0.lock() {
+            Ok(state) => state.tasks.values().cloned().collect(),
+            Err(_) => Vec::new(),
+        };
+        pending.sort_by(|a, b| a.delete_at.cmp(&b.delete_at));
+        pending
+    }
+
+    // On exit the countdowns can't finish, so whatever is still pending is deleted right away
+    pub fn commit_all(&self, storage: &mut Storage) {
+        let pending = match self.0.lock() {
+            Ok(mut state) => std::mem::take(&mut state.tasks),
+            Err(_) => return,
+        };
+        for (task_id, pending) in pending {
+            if !is_pending_task(storage, &pending) {
+                continue;
+            }
+            if let Err(e) = storage.delete_task(task_id) {
+                eprintln!("Failed to delete task {} on exit: {}", task_id, e);
+            }
+        }
+    }
+
+    // Only the timer holding the current token may take the entry
+    fn take(&self, task_id: u32, token: u64) -> Option<PendingDelete> {
+        let mut state = self.0.lock().ok()?;
+        if state.tasks.get(&task_id)?.token != token {
+            return None;
+        }
+        state.tasks.remove(&task_id)
+    }
+}
+
+fn finish(app: &AppHandle, task_id: u32, token: u64) {
+    // Cancelled or rescheduled in the meantime
+    let Some(pending) = app.state::<PendingDeletes>().take(task_id, token) else {
+        return;
+    };
+
+    let state = app.state::<AppState>();
+    let deleted = match state.0.write() {
+        // Removed another way (a restore, an import, a sync) and maybe replaced by a new task with its id
+        Ok(storage) if !is_pending_task(&storage, &pending) => Err(anyhow!("The task was already removed")),
+        Ok(mut storage) => storage.delete_task(task_id),
+        Err(e) => Err(anyhow!("Failed to acquire lock: {}", e)),
+    };
+    let _ = match deleted {
+        Ok(()) => app.emit("task-deleted", pending),
+        Err(e) => app.emit("task-delete-failed", DeleteFailure { task_id, error: e.to_string() }),
+    };
+}
+
+fn is_pending_task(storage: &Storage, pending: &PendingDelete) -> bool {
+    storage.task(pending.task_id).is_some_and(|t| t.created_at == pending.created_at)
+}
@@ -0,0 +1,259 @@
+use crate::calendar;
+use crate::commands::AppState;
+use crate::models::{LocaleSettings, Project, ProjectNotificationSettings, RoadmapData, Task, TaskStatus, WebhookSettings, WorkingDaysSettings};
+use crate::storage::Storage;
+use crate::workdays;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use serde_json::json;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+// Slack/Discord notifications for completed tasks, overdue tasks and finished projects, and the
+// reminders set on tasks
+
+const OVERDUE_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+// Call after a task's status was changed; announces completion and a project reaching 100%
+pub fn notify_status_change(storage: &Storage, previous: &TaskStatus, task: &Task) {
+    if *previous == TaskStatus::Done || task.status != TaskStatus::Done {
+        return;
+    }
+
+    let data = storage.data();
+    let settings = &data.settings.webhooks;
+    if !has_targets(settings) {
+        return;
+    }
+
+    let project = data.projects.iter().find(|p| p.id == task.project_id);
+    let overrides = notification_overrides(project);
+    let mut messages = Vec::new();
+
+    if overrides.task_completed(settings) {
+        messages.push(render(&settings.templates.task_completed, Some(task), project, &data.settings.locale));
+    }
+
+    if overrides.project_completed(settings) {
+        let mut project_tasks = data.tasks.iter().filter(|t| t.project_id == task.project_id);
+        if project_tasks.all(|t| t.status == TaskStatus::Done) {
+            messages.push(render(&settings.templates.project_completed, None, project, &data.settings.locale));
+        }
+    }
+
+    for message in messages {
+        dispatch(settings, message);
+    }
+}
+
+pub fn spawn_overdue_watcher(app: AppHandle) {
+    thread::spawn(move || loop {
+        if let Err(e) = check_overdue(&app) {
+            eprintln!("Overdue webhook check failed: {}", e);
+        }
+        if let Err(e) = check_reminders(&app) {
+            eprintln!("Reminder check failed: {}", e);
+        }
+        thread::sleep(OVERDUE_CHECK_INTERVAL);
+    });
+}
+
+fn check_overdue(app: &AppHandle) -> Result<()> {
+    let state = app.state::<AppState>();
+    let mut storage = state.0.write().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    // Notified tasks couldn't be recorded, so they would be announced again on every check
+    if storage.is_read_only() {
+        return Ok(());
+    }
+    let mut data = storage.load_data()?;
+
+    // Tasks in muted projects are left out, so unmuting announces them on the next check. Due soon
+    // and overdue are tracked apart, so a task announced as due soon is announced again once overdue.
+    let working_days = &data.settings.working_days;
+    let mut overdue: Vec<u32> = Vec::new();
+    let mut due_soon: Vec<u32> = Vec::new();
+    for task in &data.tasks {
+        let overrides = notification_overrides(data.projects.iter().find(|p| p.id == task.project_id));
+        if !overrides.task_overdue(&data.settings.webhooks) {
+            continue;
+        }
+        if is_due(task, 0, working_days) {
+            overdue.push(task.id);
+        } else if overrides.due_lead_minutes > 0 && is_due(task, overrides.due_lead_minutes, working_days) {
+            due_soon.push(task.id);
+        }
+    }
+
+    let settings = &data.settings.webhooks;
+    let newly_overdue = overdue.iter().filter(|id| !settings.notified_overdue.contains(id));
+    let newly_due_soon = due_soon.iter().filter(|id| !settings.notified_due_soon.contains(id));
+    let announcements: Vec<(&String, u32)> = newly_overdue.map(|id| (&settings.templates.task_overdue, *id))
+        .chain(newly_due_soon.map(|id| (&settings.templates.task_due_soon, *id)))
+        .collect();
+
+    // Forget tasks that are no longer due so a new due date can trigger again
+    let notified_changed = settings.notified_overdue.iter().any(|id| !overdue.contains(id))
+        || settings.notified_due_soon.iter().any(|id| !due_soon.contains(id));
+    if announcements.is_empty() && !notified_changed {
+        return Ok(());
+    }
+
+    let messages: Vec<String> = if has_targets(settings) {
+        announcements.iter()
+            .filter_map(|(template, id)| Some((template, data.tasks.iter().find(|t| t.id == *id)?)))
+            .map(|(template, task)| {
+                let project = data.projects.iter().find(|p| p.id == task.project_id);
+                render(template, Some(task), project, &data.settings.locale)
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    data.settings.webhooks.notified_overdue = overdue;
+    data.settings.webhooks.notified_due_soon = due_soon;
+    storage.save_data(&data)?;
+
+    for message in messages {
+        dispatch(&data.settings.webhooks, message);
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize, Clone)]
+pub struct TaskReminder {
+    pub task_id: u32,
+    pub title: String,
+    pub due_date: String,
+    pub minutes_before: u32,
+}
+
+// Reminders that came due since the last check are given once per due date, in the app and on the
+// webhooks. Several at once (the app was closed) become a single reminder for the nearest offset.
+fn check_reminders(app: &AppHandle) -> Result<()> {
+    let state = app.state::<AppState>();
+    let mut storage = state.0.write().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    if storage.is_read_only() {
+        return Ok(());
+    }
+    let mut data = storage.load_data()?;
+
+    let now = Utc::now();
+    let mut reminders = Vec::new();
+    for task in data.tasks.iter_mut().filter(|t| t.status != TaskStatus::Done) {
+        // Like overdue announcements, muted projects get theirs once unmuted
+        if notification_overrides(data.projects.iter().find(|p| p.id == task.project_id)).muted {
+            continue;
+        }
+        let Some(due_date) = task.due_date.clone() else {
+            continue;
+        };
+        let Ok(due) = DateTime::parse_from_rfc3339(&due_date) else {
+            continue;
+        };
+        let due = workdays::effective_due(due.with_timezone(&Utc), &data.settings.working_days);
+        let came_due: Vec<u32> = task.reminders.iter()
+            .filter(|m| !task.reminders_sent.contains(m) && due - ChronoDuration::minutes(**m as i64) <= now)
+            .copied()
+            .collect();
+        if let Some(minutes_before) = came_due.iter().min().copied() {
+            task.reminders_sent.extend(came_due);
+            reminders.push(TaskReminder { task_id: task.id, title: task.title.clone(), due_date, minutes_before });
+        }
+    }
+    if reminders.is_empty() {
+        return Ok(());
+    }
+    storage.save_data(&data)?;
+
+    let settings = &data.settings.webhooks;
+    for reminder in &reminders {
+        let _ = app.emit("task-reminder", reminder);
+        if has_targets(settings) {
+            let task = data.tasks.iter().find(|t| t.id == reminder.task_id);
+            let project = task.and_then(|t| data.projects.iter().find(|p| p.id == t.project_id));
+            dispatch(settings, render(&settings.templates.task_due_soon, task, project, &data.settings.locale));
+        }
+    }
+
+    Ok(())
+}
+
+// Overdue, or within `lead_minutes` of the due date. Deadlines on days off count from the next working day.
+fn is_due(task: &Task, lead_minutes: u32, working_days: &WorkingDaysSettings) -> bool {
+    task.status != TaskStatus::Done && task.due_date.as_deref()
+        .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+        .map(|d| workdays::effective_due(d.with_timezone(&Utc), working_days) - ChronoDuration::minutes(lead_minutes as i64) < Utc::now())
+        .unwrap_or(false)
+}
+
+fn notification_overrides(project: Option<&Project>) -> ProjectNotificationSettings {
+    project.map(|p| p.settings.notifications.clone()).unwrap_or_default()
+}
+
+fn has_targets(settings: &WebhookSettings) -> bool {
+    settings.slack_url.is_some() || settings.discord_url.is_some()
+}
+
+pub fn render(template: &str, task: Option<&Task>, project: Option<&Project>, locale: &LocaleSettings) -> String {
+    let mut message = template.to_string();
+    if let Some(task) = task {
+        let due_date = task.due_date.as_deref()
+            .map(|d| calendar::format_timestamp(d, locale))
+            .unwrap_or_else(|| "-".to_string());
+        message = message
+            .replace("{task}", &task.title)
+            .replace("{task_id}", &task.id.to_string())
+            .replace("{due_date}", &due_date)
+            .replace("{priority}", &task.priority.to_string());
+    }
+    message.replace("{project}", project.map(|p| p.name.as_str()).unwrap_or("Unknown project"))
+}
+
+// Posts in the background so commands never wait on the network
+pub fn dispatch(settings: &WebhookSettings, message: String) {
+    let slack_url = settings.slack_url.clone();
+    let discord_url = settings.discord_url.clone();
+
+    thread::spawn(move || {
+        if let Some(url) = slack_url {
+            if let Err(e) = post(&url, json!({ "text": message })) {
+                eprintln!("Slack webhook failed: {}", e);
+            }
+        }
+        if let Some(url) = discord_url {
+            if let Err(e) = post(&url, json!({ "content": message })) {
+                eprintln!("Discord webhook failed: {}", e);
+            }
+        }
+    });
+}
+
+pub fn post(url: &str, body: serde_json::Value) -> Result<()> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(10))
+        .build();
+    agent.post(url)
+        .send_json(body)
+        .map_err(|e| anyhow!("{}", e))?;
+    Ok(())
+}
+
+pub fn send_test_message(data: &RoadmapData) -> Result<()> {
+    let settings = &data.settings.webhooks;
+    if !has_targets(settings) {
+        return Err(anyhow!("No webhook URL configured"));
+    }
+
+    let message = "🔔 RuidMap webhook test";
+    if let Some(url) = &settings.slack_url {
+        post(url, json!({ "text": message })).map_err(|e| anyhow!("Slack: {}", e))?;
+    }
+    if let Some(url) = &settings.discord_url {
+        post(url, json!({ "content": message })).map_err(|e| anyhow!("Discord: {}", e))?;
+    }
+    Ok(())
+}
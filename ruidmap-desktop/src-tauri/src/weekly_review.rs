@@ -0,0 +1,172 @@
+use crate::commands::AppState;
+use crate::models::{RoadmapData, WeeklyReviewSettings, Task, TaskPriority, TaskStatus};
+use crate::webhooks;
+use crate::workdays;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveTime, TimeZone, Utc};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+// Weekly review: a reminder at a set weekday and time, and a packet of the open tasks worth
+// walking through (overdue, stale, unestimated). A reminder missed while the app was closed is
+// delivered once on the next check.
+
+const REVIEW_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReviewPacket {
+    pub generated_at: String,
+    pub project_id: Option<u32>, // None covers every project
+    pub open_tasks: usize,
+    pub stale_days: u32,
+    pub overdue: Vec<ReviewItem>, // Longest overdue first
+    pub stale: Vec<ReviewItem>, // Most neglected first
+    pub unestimated: Vec<ReviewItem>, // Highest priority first
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReviewItem {
+    pub task_id: u32,
+    pub title: String,
+    pub project_id: u32,
+    pub project_name: String,
+    pub status: TaskStatus,
+    pub priority: TaskPriority,
+    pub due_date: Option<String>,
+    pub days_since_update: i64,
+}
+
+pub fn validate(settings: &WeeklyReviewSettings) -> Result<()> {
+    parse_time(&settings.time)?;
+    Ok(())
+}
+
+// The latest scheduled review at or before `now`
+pub fn last_occurrence(settings: &WeeklyReviewSettings, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let time = parse_time(&settings.time)?;
+    let local = now.with_timezone(&Local);
+    let days_back = (local.weekday().num_days_from_monday() + 7 - settings.weekday.to_chrono().num_days_from_monday()) % 7;
+    let date = local.date_naive() - ChronoDuration::days(days_back as i64);
+    let at = |date: chrono::NaiveDate| Local.from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+        .ok_or_else(|| anyhow!("{} {} does not exist in the local time zone", date, settings.time));
+
+    let occurrence = at(date)?;
+    if occurrence <= now {
+        return Ok(occurrence);
+    }
+    at(date - ChronoDuration::days(7))
+}
+
+pub fn is_due(settings: &WeeklyReviewSettings, now: DateTime<Utc>) -> Result<bool> {
+    if !settings.enabled {
+        return Ok(false);
+    }
+    let occurrence = last_occurrence(settings, now)?;
+    let reminded = settings.last_reminded_at.as_deref()
+        .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+        .map(|d| d.with_timezone(&Utc));
+    Ok(reminded.is_none_or(|reminded| reminded < occurrence))
+}
+
+pub fn packet(data: &RoadmapData, project_id: Option<u32>, now: DateTime<Utc>) -> ReviewPacket {
+    let stale_days = data.settings.weekly_review.stale_days;
+    let open: Vec<&Task> = data.tasks.iter()
+        .filter(|t| t.status != TaskStatus::Done)
+        .filter(|t| project_id.is_none_or(|id| t.project_id == id))
+        .collect();
+    let item = |task: &Task| ReviewItem {
+        task_id: task.id,
+        title: task.title.clone(),
+        project_id: task.project_id,
+        project_name: data.projects.iter()
+            .find(|p| p.id == task.project_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_default(),
+        status: task.status.clone(),
+        priority: task.priority.clone(),
+        due_date: task.due_date.clone(),
+        days_since_update: task.staleness(now).days_since_update,
+    };
+
+    let mut overdue: Vec<&Task> = open.iter()
+        .copied()
+        .filter(|t| workdays::is_overdue(t, now, &data.settings.working_days))
+        .collect();
+    overdue.sort_by(|a, b| a.due_date.cmp(&b.due_date));
+
+    let mut stale: Vec<&Task> = open.iter()
+        .copied()
+        .filter(|t| t.staleness(now).days_since_update >= stale_days as i64)
+        .collect();
+    stale.sort_by_key(|t| (Reverse(t.staleness(now).days_since_update), t.id));
+
+    let mut unestimated: Vec<&Task> = open.iter()
+        .copied()
+        // A size counts as an estimate for teams that plan in points
+        .filter(|t| t.estimated_time.is_none() && t.size.is_none())
+        .collect();
+    unestimated.sort_by_key(|t| (priority_rank(&t.priority), t.id));
+
+    ReviewPacket {
+        generated_at: now.to_rfc3339(),
+        project_id,
+        open_tasks: open.len(),
+        stale_days,
+        overdue: overdue.into_iter().map(item).collect(),
+        stale: stale.into_iter().map(item).collect(),
+        unestimated: unestimated.into_iter().map(item).collect(),
+    }
+}
+
+pub fn spawn_review_scheduler(app: AppHandle) {
+    thread::spawn(move || loop {
+        if let Err(e) = check(&app) {
+            eprintln!("Weekly review check failed: {}", e);
+        }
+        thread::sleep(REVIEW_CHECK_INTERVAL);
+    });
+}
+
+// Emits weekly-review-due with the packet so the frontend can open the review right away
+fn check(app: &AppHandle) -> Result<()> {
+    let state = app.state::<AppState>();
+    let mut storage = state.0.write().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    let now = Utc::now();
+    if storage.is_read_only() || !is_due(&storage.data().settings.weekly_review, now)? {
+        return Ok(());
+    }
+
+    let mut data = storage.load_data()?;
+    data.settings.weekly_review.last_reminded_at = Some(now.to_rfc3339());
+    storage.save_data(&data)?;
+    drop(storage);
+
+    let packet = packet(&data, None, now);
+    if data.settings.weekly_review.notify_webhooks {
+        let message = format!(
+            "Time for the weekly review: {} overdue, {} stale and {} unestimated tasks",
+            packet.overdue.len(), packet.stale.len(), packet.unestimated.len()
+        );
+        webhooks::dispatch(&data.settings.webhooks, message);
+    }
+    app.emit("weekly-review-due", packet).map_err(|e| anyhow!("Failed to send the reminder: {}", e))?;
+    Ok(())
+}
+
+fn parse_time(time: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .map_err(|_| anyhow!("Invalid review time \"{}\", expected HH:MM", time))
+}
+
+fn priority_rank(priority: &TaskPriority) -> u8 {
+    match priority {
+        TaskPriority::High => 0,
+        TaskPriority::Medium => 1,
+        TaskPriority::Low => 2,
+    }
+}
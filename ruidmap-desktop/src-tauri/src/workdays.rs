@@ -0,0 +1,232 @@
+use crate::calendar;
+use crate::models::{Holiday, RoadmapData, Task, TaskStatus, WorkingDaysSettings};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::Serialize;
+
+// Working-days calendar: weekends and holidays don't count towards deadlines. A due date that
+// falls on a day off moves to the next working day, and time spent overdue skips days off.
+
+// Far enough to get past any sensible run of days off without looping forever on bad settings
+const MAX_SEARCH_DAYS: i64 = 3660;
+// Used when the weekly capacity is zero
+const DEFAULT_DAILY_MINUTES: f64 = 8.0 * 60.0;
+
+#[derive(Debug, Serialize)]
+pub struct DueDateSuggestion {
+    pub due_date: String, // End of the last working day, RFC 3339
+    pub label: String,
+    pub working_days: u32,
+    pub calendar_days: u32, // Including today
+}
+
+pub fn validate(settings: &WorkingDaysSettings) -> Result<()> {
+    let weekend = &settings.weekend;
+    if weekend.iter().enumerate().any(|(i, day)| weekend[..i].contains(day)) {
+        return Err(anyhow!("Weekend days must not repeat"));
+    }
+    if settings.weekend.len() >= 7 {
+        return Err(anyhow!("At least one day of the week must be a working day"));
+    }
+    for holiday in &settings.holidays {
+        NaiveDate::parse_from_str(&holiday.date, "%Y-%m-%d")
+            .map_err(|e| anyhow!("Invalid holiday date {}: {}", holiday.date, e))?;
+    }
+    Ok(())
+}
+
+pub fn is_working_day(date: NaiveDate, settings: &WorkingDaysSettings) -> bool {
+    let weekday = date.weekday();
+    if settings.weekend.iter().any(|d| d.to_chrono() == weekday) {
+        return false;
+    }
+    let date = date.format("%Y-%m-%d").to_string();
+    !settings.holidays.iter().any(|h| h.date == date)
+}
+
+// The first working day on or after `date`
+pub fn next_working_day(date: NaiveDate, settings: &WorkingDaysSettings) -> NaiveDate {
+    (0..MAX_SEARCH_DAYS)
+        .map(|offset| date + Duration::days(offset))
+        .find(|d| is_working_day(*d, settings))
+        .unwrap_or(date)
+}
+
+// A deadline on a day off is kept at the same time on the next working day
+pub fn effective_due(due: DateTime<Utc>, settings: &WorkingDaysSettings) -> DateTime<Utc> {
+    let local = due.with_timezone(&Local);
+    let date = local.date_naive();
+    let working = next_working_day(date, settings);
+    if working == date {
+        return due;
+    }
+    Local.from_local_datetime(&working.and_time(local.time()))
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or(due)
+}
+
+// Working time since the deadline passed, or None if it hasn't
+pub fn overdue_by(due: DateTime<Utc>, now: DateTime<Utc>, settings: &WorkingDaysSettings) -> Option<Duration> {
+    let due = effective_due(due, settings);
+    if due >= now {
+        return None;
+    }
+
+    let due_date = due.with_timezone(&Local).date_naive();
+    let now_local = now.with_timezone(&Local);
+    let today = now_local.date_naive();
+    let mut days_off = Duration::zero();
+    let mut date = due_date + Duration::days(1);
+    while date < today {
+        if !is_working_day(date, settings) {
+            days_off += Duration::days(1);
+        }
+        date += Duration::days(1);
+    }
+    // Only the part of today that has already gone by
+    if today > due_date && !is_working_day(today, settings) {
+        days_off += now_local.time() - NaiveTime::MIN;
+    }
+
+    Some((now - due - days_off).max(Duration::zero()))
+}
+
+pub fn is_overdue(task: &Task, now: DateTime<Utc>, settings: &WorkingDaysSettings) -> bool {
+    task.status != TaskStatus::Done && task.due_date.as_deref()
+        .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+        .map(|d| effective_due(d.with_timezone(&Utc), settings) < now)
+        .unwrap_or(false)
+}
+
+// Spreads the estimate over working days at the daily share of the weekly capacity, starting today
+pub fn suggest_due_date(data: &RoadmapData, estimate_minutes: u32, today: NaiveDate) -> Result<DueDateSuggestion> {
+    let settings = &data.settings.working_days;
+    let working_days_per_week = 7 - settings.weekend.len().min(6);
+    let daily_minutes = data.settings.capacity.weekly_hours * 60.0 / working_days_per_week as f64;
+    let daily_minutes = if daily_minutes > 0.0 { daily_minutes } else { DEFAULT_DAILY_MINUTES };
+    let needed = ((estimate_minutes as f64 / daily_minutes).ceil() as u32).max(1);
+
+    let mut counted = 0;
+    let mut date = today;
+    loop {
+        if is_working_day(date, settings) {
+            counted += 1;
+            if counted == needed {
+                break;
+            }
+        }
+        date += Duration::days(1);
+        if (date - today).num_days() > MAX_SEARCH_DAYS {
+            return Err(anyhow!("No working days found, check the weekend and holiday settings"));
+        }
+    }
+
+    let end_of_day = date.and_time(NaiveTime::from_hms_opt(23, 59, 0).unwrap_or_default());
+    let due = Local.from_local_datetime(&end_of_day)
+        .earliest()
+        .ok_or_else(|| anyhow!("{} does not exist in the local time zone", end_of_day))?;
+
+    Ok(DueDateSuggestion {
+        due_date: due.to_rfc3339(),
+        label: calendar::format_date(date, &data.settings.locale),
+        working_days: needed,
+        calendar_days: (date - today).num_days() as u32 + 1,
+    })
+}
+
+// Reads an iCalendar file (as published by most holiday calendars) or lines of "YYYY-MM-DD,Name"
+pub fn parse_holidays(content: &str) -> Result<Vec<Holiday>> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut holidays = if content.trim_start().starts_with("BEGIN:VCALENDAR") {
+        parse_ical(content)?
+    } else {
+        parse_lines(content)?
+    };
+    holidays.sort_by(|a, b| a.date.cmp(&b.date));
+    holidays.dedup_by(|a, b| a.date == b.date);
+    Ok(holidays)
+}
+
+// Imported holidays replace existing ones on the same date
+pub fn merge_holidays(settings: &mut WorkingDaysSettings, imported: Vec<Holiday>) -> usize {
+    let count = imported.len();
+    settings.holidays.retain(|h| !imported.iter().any(|i| i.date == h.date));
+    settings.holidays.extend(imported);
+    settings.holidays.sort_by(|a, b| a.date.cmp(&b.date));
+    count
+}
+
+fn parse_lines(content: &str) -> Result<Vec<Holiday>> {
+    let mut holidays = Vec::new();
+    for (line, number) in content.lines().zip(1..) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (date, name) = line.split_once([',', ';', '\t']).unwrap_or((line, ""));
+        let date = match NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d") {
+            Ok(date) => date,
+            // A header row
+            Err(_) if number == 1 => continue,
+            Err(_) => return Err(anyhow!("Line {}: expected a YYYY-MM-DD date, found \"{}\"", number, date.trim())),
+        };
+        holidays.push(Holiday {
+            date: date.format("%Y-%m-%d").to_string(),
+            name: name.trim().trim_matches('"').to_string(),
+        });
+    }
+    Ok(holidays)
+}
+
+fn parse_ical(content: &str) -> Result<Vec<Holiday>> {
+    // Long lines are folded onto continuation lines starting with a space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut holidays = Vec::new();
+    let mut event: Option<(Option<NaiveDate>, Option<NaiveDate>, String)> = None;
+    for line in lines {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = key.split(';').next().unwrap_or(key);
+        match (name, event.as_mut()) {
+            ("BEGIN", _) if value == "VEVENT" => event = Some((None, None, String::new())),
+            ("DTSTART", Some(event)) => event.0 = ical_date(value),
+            ("DTEND", Some(event)) => event.1 = ical_date(value),
+            ("SUMMARY", Some(event)) => event.2 = unescape(value),
+            ("END", Some(_)) if value == "VEVENT" => {
+                let Some((Some(start), end, summary)) = event.take() else {
+                    return Err(anyhow!("Holiday calendar has an event without a start date"));
+                };
+                // DTEND is exclusive; a missing one means a single day
+                let end = end.filter(|e| *e > start).unwrap_or(start + Duration::days(1));
+                let mut date = start;
+                while date < end && (date - start).num_days() < 31 {
+                    holidays.push(Holiday { date: date.format("%Y-%m-%d").to_string(), name: summary.clone() });
+                    date += Duration::days(1);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(holidays)
+}
+
+fn ical_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
@@ -1,4 +1,5 @@
 import { useState, useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { motion, AnimatePresence } from "framer-motion";
 import "./App.css";
 
@@ -25,7 +26,7 @@ import { useNotifications } from "./providers/NotificationProvider";
 import { useAccessibility } from "./providers/AccessibilityProvider";
 
 // Types
-import { Task, Project } from "./types";
+import { Task, Project, LastSelection } from "./types";
 
 function App() {
   const [selectedTask, setSelectedTask] = useState<Task | null>(null);
@@ -36,6 +37,8 @@ function App() {
   const [isThemeDropdownOpen, setIsThemeDropdownOpen] = useState(false);
   const [availableTags, setAvailableTags] = useState<string[]>([]);
   const themeDropdownRef = useRef<HTMLDivElement>(null);
+  const selectionRestored = useRef(false);
+  const pendingTaskId = useRef<number | null>(null);
   
   // New UI/UX hooks
   const { isMobile } = useResponsive();
@@ -123,6 +126,43 @@ function App() {
     }
   }, [currentProject, refreshTasks]);
 
+  // Reopen the task that was selected when the app was last used
+  useEffect(() => {
+    if (selectionRestored.current || !currentProject || loading) {
+      return;
+    }
+    selectionRestored.current = true;
+
+    invoke<LastSelection>('get_last_selection')
+      .then((selection) => {
+        if (selection.task_id == null) {
+          return;
+        }
+        pendingTaskId.current = selection.task_id;
+        if (selection.project_id != null && selection.project_id !== currentProject.id) {
+          switchProject(selection.project_id).catch((err) => console.error('Failed to restore project:', err));
+        }
+      })
+      .catch((err) => console.error('Failed to load last selection:', err));
+  }, [currentProject, loading, switchProject]);
+
+  useEffect(() => {
+    if (pendingTaskId.current == null) {
+      return;
+    }
+    const task = tasks.find(t => t.id === pendingTaskId.current);
+    if (task) {
+      pendingTaskId.current = null;
+      setSelectedTask(task);
+      setIsTaskDetailOpen(true);
+    }
+  }, [tasks]);
+
+  const rememberSelection = (projectId: number | null, taskId: number | null) => {
+    invoke('set_last_selection', { projectId, taskId })
+      .catch((err) => console.error('Failed to save last selection:', err));
+  };
+
   // Load available tags when tasks change
   useEffect(() => {
     const loadTags = async () => {
@@ -144,6 +184,7 @@ function App() {
   const handleTaskClick = (task: Task) => {
     setSelectedTask(task);
     setIsTaskDetailOpen(true);
+    rememberSelection(task.project_id, task.id);
   };
 
   const handleAddTask = () => {
@@ -229,6 +270,7 @@ function App() {
   const handleCloseTaskDetail = () => {
     setIsTaskDetailOpen(false);
     setSelectedTask(null);
+    rememberSelection(currentProject?.id ?? null, null);
   };
 
     const handleThemeChange = (theme: string) => {
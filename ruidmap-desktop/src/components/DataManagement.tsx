@@ -7,6 +7,8 @@ interface ImportResult {
   success: boolean;
   imported_tasks: number;
   imported_projects: number;
+  skipped_duplicates: number;
+  updated_duplicates: number;
   message: string;
   export_version: string;
   export_date?: string;
@@ -25,7 +25,8 @@ export const ProjectManagementDialog: React.FC<ProjectManagementDialogProps> = (
     updateProject,
     deleteProject,
     switchProject,
-    getProjectStats,
+    getAllProjectStats,
+    getColorPalette,
     clearError,
   } = useProjectManagement();
 
@@ -42,6 +43,12 @@ export const ProjectManagementDialog: React.FC<ProjectManagementDialogProps> = (
     icon: '📁',
   });
 
+  const [colorOptions, setColorOptions] = useState<string[]>([
+    '#6366f1', '#8b5cf6', '#ec4899', '#ef4444', '#f97316',
+    '#eab308', '#22c55e', '#14b8a6', '#06b6d4', '#3b82f6'
+  ]);
+  const [iconOptions, setIconOptions] = useState<string[]>(['📁', '💼', '🎯', '⚡', '🔥', '🌟', '🚀', '💡', '🎨', '🔧']);
+
   useEffect(() => {
     if (isOpen) {
       clearError();
@@ -49,17 +56,27 @@ export const ProjectManagementDialog: React.FC<ProjectManagementDialogProps> = (
     }
   }, [isOpen, projects, clearError]);
 
+  // The backend owns the palette and suggests a color not used by other projects yet
+  useEffect(() => {
+    if (!isOpen) return;
+    getColorPalette()
+      .then((palette) => {
+        setColorOptions(palette.colors.map((c) => c.hex));
+        setIconOptions(palette.icons);
+        if (activeTab !== 'edit') {
+          setFormData((prev) => ({ ...prev, color: palette.suggested_color }));
+        }
+      })
+      .catch((err) => console.error('Failed to load color palette:', err));
+  }, [isOpen, projects, getColorPalette]);
+
   const loadProjectStats = async () => {
-    const statsMap = new Map<number, ProjectStats>();
-    for (const project of projects) {
-      try {
-        const stats = await getProjectStats(project.id);
-        statsMap.set(project.id, stats);
-      } catch (err) {
-        console.error(`Failed to load stats for project ${project.id}:`, err);
-      }
+    try {
+      const allStats = await getAllProjectStats();
+      setProjectStats(new Map(allStats.map((stats) => [stats.project_id, stats])));
+    } catch (err) {
+      console.error('Failed to load project stats:', err);
     }
-    setProjectStats(statsMap);
   };
 
   const handleCreateProject = async () => {
@@ -143,13 +160,6 @@ export const ProjectManagementDialog: React.FC<ProjectManagementDialogProps> = (
   const getProjectColor = (project: Project) => project.color || '#6366f1';
   const getProjectIcon = (project: Project) => project.icon || '📁';
 
-  const colorOptions = [
-    '#6366f1', '#8b5cf6', '#ec4899', '#ef4444', '#f97316',
-    '#eab308', '#22c55e', '#06b6d4', '#3b82f6', '#6366f1'
-  ];
-
-  const iconOptions = ['📁', '💼', '🎯', '⚡', '🔥', '🌟', '🚀', '💡', '🎨', '🔧'];
-
   if (!isOpen) return null;
 
   return (
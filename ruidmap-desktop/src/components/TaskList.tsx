@@ -3,6 +3,12 @@ import { Task, TaskStatus } from '../types';
 import { SearchFilter } from './ui/SearchFilter';
 import { useTaskSearch } from '../hooks/useSearch';
 
+// Open tasks untouched for this many days are faded on the board
+const STALE_DAYS = 14;
+
+const isStale = (task: Task) =>
+  task.status !== 'done' && (task.staleness?.days_since_update ?? 0) >= STALE_DAYS;
+
 interface TaskListProps {
   tasks: Task[];
   onTaskClick: (task: Task) => void;
@@ -98,12 +104,13 @@ export const TaskList: React.FC<TaskListProps> = ({
                 key={task.id}
                 layout
                 initial={{ opacity: 0, y: 20 }}
-                animate={{ opacity: 1, y: 0 }}
+                animate={{ opacity: isStale(task) ? 0.6 : 1, y: 0 }}
                 exit={{ opacity: 0, y: -20 }}
                 transition={{ 
                   duration: 0.3,
                   delay: index * 0.1
                 }}
+                title={isStale(task) ? `No updates for ${task.staleness?.days_since_update} days` : undefined}
                 className="bg-gray-50 dark:bg-gray-700 border border-gray-200 dark:border-gray-600 rounded-lg p-3 cursor-pointer hover:shadow-md transition-all duration-200 hover:bg-gray-100 dark:hover:bg-gray-600"
                 onClick={() => onTaskClick(task)}
                 whileHover={{ scale: 1.02 }}
@@ -1,6 +1,6 @@
 import { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { Project, ProjectCreateRequest, ProjectUpdateRequest, ProjectStats } from '../types';
+import { Project, ProjectCreateRequest, ProjectUpdateRequest, ProjectStats, ColorPalette } from '../types';
 
 export const useProjectManagement = (onProjectChange?: (project: Project | null) => void) => {
   const [projects, setProjects] = useState<Project[]>([]);
@@ -129,6 +129,22 @@ export const useProjectManagement = (onProjectChange?: (project: Project | null)
     }
   }, []);
 
+  // Get statistics for every project in a single call
+  const getAllProjectStats = useCallback(async (): Promise<ProjectStats[]> => {
+    try {
+      return await invoke<ProjectStats[]>('get_all_project_stats');
+    } catch (err) {
+      const errorMsg = err instanceof Error ? err.message : 'Failed to get project stats';
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, []);
+
+  // Get the project colors and icons to offer, plus a suggested color for a new project
+  const getColorPalette = useCallback(async (): Promise<ColorPalette> => {
+    return await invoke<ColorPalette>('get_color_palette');
+  }, []);
+
   // Initialize data on mount
   useEffect(() => {
     const initializeData = async () => {
@@ -161,6 +177,8 @@ export const useProjectManagement = (onProjectChange?: (project: Project | null)
     deleteProject,
     switchProject,
     getProjectStats,
+    getAllProjectStats,
+    getColorPalette,
 
     // Utilities
     clearError: () => setError(null),
@@ -1,5 +1,6 @@
-import { useState, useMemo, useCallback } from 'react';
-import { Task, Project } from '../types';
+import { useState, useMemo, useCallback, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Task, Project, GlobalSearchResults } from '../types';
 import { SearchFilterState } from '../components/ui/SearchFilter';
 
 export interface UseSearchResult<T> {
@@ -288,4 +289,41 @@ export function useGenericSearch<T>(
     hasActiveFilters,
     resultCount: filteredItems.length
   };
-}
\ No newline at end of file
+}
+
+// Hook for the cross-project omnibox; queries the backend as the user types
+export function useGlobalSearch(query: string, limit?: number, debounceMs: number = 150) {
+  const [results, setResults] = useState<GlobalSearchResults | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    if (!query.trim()) {
+      setResults(null);
+      return;
+    }
+
+    let cancelled = false;
+    const timer = setTimeout(async () => {
+      setLoading(true);
+      try {
+        const found = await invoke<GlobalSearchResults>('global_search', { query, limit });
+        if (!cancelled) {
+          setResults(found);
+        }
+      } catch (err) {
+        console.error('Global search failed:', err);
+      } finally {
+        if (!cancelled) {
+          setLoading(false);
+        }
+      }
+    }, debounceMs);
+
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [query, limit, debounceMs]);
+
+  return { results, loading };
+}
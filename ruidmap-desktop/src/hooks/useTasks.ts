@@ -1,6 +1,6 @@
 import { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { Task, TaskCreateRequest, TaskUpdateRequest, TaskStats, TaskStatus, ProjectStats } from '../types';
+import { Task, TaskCreateRequest, TaskUpdateRequest, TaskStats, TaskStatus, ProjectStats, DurationInput } from '../types';
 
 export const useTasks = (projectId?: number) => {
   const [tasks, setTasks] = useState<Task[]>([]);
@@ -238,7 +238,7 @@ export const useTasks = (projectId?: number) => {
     }
   }, []);
 
-  const addTaskTime = useCallback(async (taskId: number, minutes: number) => {
+  const addTaskTime = useCallback(async (taskId: number, minutes: DurationInput) => {
     try {
       setLoading(true);
       setError(null);
@@ -255,7 +255,7 @@ export const useTasks = (projectId?: number) => {
     }
   }, []);
 
-  const setTaskEstimatedTime = useCallback(async (taskId: number, estimatedMinutes?: number) => {
+  const setTaskEstimatedTime = useCallback(async (taskId: number, estimatedMinutes?: DurationInput) => {
     try {
       setLoading(true);
       setError(null);
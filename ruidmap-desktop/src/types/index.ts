@@ -13,13 +13,56 @@ export interface Task {
   comments: Comment[];
   time_spent: number; // minutes
   estimated_time?: number; // minutes
+  size?: number | null; // points, labelled by the project's size_scale
   attachments: Attachment[];
+  dependencies: number[]; // IDs of tasks that must be finished first
+  description_format?: DescriptionFormat;
+  requires_review?: boolean;
+  review_state?: ReviewState;
+  reviewer?: string | null;
+  status_changed_at?: string | null;
+  assignee?: number | null; // Member id
+  watchers?: number[]; // Member ids
+  custom_fields?: Record<string, string>;
+  external_id?: string | null; // Set on imported tasks
+  reminders?: number[]; // minutes before the due date
+  reminders_sent?: number[];
+  sync_id?: string; // the same on every synced device
+  staleness?: TaskStaleness; // Included in task lists, not stored
+}
+
+export interface TaskStaleness {
+  days_since_update: number;
+  days_in_status: number;
 }
 
 export type TaskStatus = 'todo' | 'in-progress' | 'done';
 
 export type TaskPriority = 'low' | 'medium' | 'high';
 
+export type DescriptionFormat = 'plaintext' | 'markdown';
+
+export type ReviewState = 'not-requested' | 'requested' | 'approved' | 'rejected';
+
+export type CommentKind = 'note' | 'review-requested' | 'approved' | 'rejected';
+
+export interface Member {
+  id: number;
+  name: string;
+  handle: string;
+}
+
+export interface Notification {
+  id: number;
+  member_id: number;
+  task_id: number;
+  comment_id: number;
+  author: string;
+  excerpt: string;
+  created_at: string;
+  read: boolean;
+}
+
 export interface TaskCreateRequest {
   title: string;
   description: string;
@@ -54,6 +97,7 @@ export interface Comment {
   text: string;
   author: string;
   created_at: string;
+  kind?: CommentKind;
 }
 
 export interface Attachment {
@@ -63,8 +107,12 @@ export interface Attachment {
   file_size: number;
   mime_type: string;
   created_at: string;
+  kind?: AttachmentKind;
+  url?: string | null;
 }
 
+export type AttachmentKind = 'file' | 'link';
+
 export interface TaskStats {
   total: number;
   todo: number;
@@ -100,6 +148,7 @@ export interface Project {
   is_active: boolean;
   task_count: number;
   settings: ProjectSettings;
+  sync_id?: string; // the same on every synced device
 }
 
 export interface ProjectSettings {
@@ -109,6 +158,23 @@ export interface ProjectSettings {
   default_description?: string;
   default_tags: string[];
   default_estimated_time?: number; // minutes
+  notifications?: ProjectNotificationSettings;
+  size_scale?: SizeOption[]; // empty allows any number of points
+  status_mapping?: Record<string, TaskStatus>; // other trackers' workflow states, for imports
+}
+
+export interface SizeOption {
+  label: string;
+  points: number;
+}
+
+export interface ProjectNotificationSettings {
+  muted: boolean;
+  on_task_completed?: boolean | null;
+  on_task_overdue?: boolean | null;
+  on_project_completed?: boolean | null;
+  due_lead_minutes: number;
+  reminder_offsets: number[]; // minutes before the due date, copied to tasks when they get one
 }
 
 export interface TaskTemplate {
@@ -136,6 +202,404 @@ export interface ProjectUpdateRequest {
   icon?: string;
 }
 
+export interface LastSelection {
+  project_id: number | null;
+  task_id: number | null;
+  updated_at: string | null;
+}
+
+export interface SearchProjectContext {
+  project_id: number | null;
+  project_name: string | null;
+  project_color: string | null;
+  project_icon: string | null;
+}
+
+export interface TaskSearchHit extends SearchProjectContext {
+  task_id: number;
+  title: string;
+  status: TaskStatus;
+  priority: TaskPriority;
+  tags: string[];
+  score: number;
+}
+
+export interface ProjectSearchHit {
+  project_id: number;
+  name: string;
+  description: string | null;
+  color: string | null;
+  icon: string | null;
+  score: number;
+}
+
+export interface CommentSearchHit extends SearchProjectContext {
+  task_id: number;
+  task_title: string;
+  comment_id: number;
+  author: string;
+  excerpt: string;
+  created_at: string;
+  score: number;
+}
+
+export interface GlobalSearchResults {
+  query: string;
+  tasks: TaskSearchHit[];
+  projects: ProjectSearchHit[];
+  comments: CommentSearchHit[];
+}
+
+export type WeekStart = 'monday' | 'tuesday' | 'wednesday' | 'thursday' | 'friday' | 'saturday' | 'sunday';
+
+export interface LocaleSettings {
+  week_start: WeekStart;
+  date_format: string;
+  fiscal_year_start_month: number;
+}
+
+export type ReportPeriod = 'week' | 'month' | 'quarter' | 'fiscal_year';
+
+export interface PeriodReport {
+  period: ReportPeriod;
+  start: string;
+  end: string;
+  label: string;
+  created_tasks: number;
+  completed_tasks: number;
+  logged_minutes: number;
+  due_tasks: Task[];
+}
+
+export interface CapacitySettings {
+  weekly_hours: number;
+  weekly_points: number; // 0 plans hours only
+  overrides: { week_start: string; hours: number }[];
+}
+
+export interface PlannedTask {
+  task_id: number;
+  title: string;
+  due_date: string;
+  minutes: number;
+  points: number;
+}
+
+export interface CapacityWeek {
+  week_start: string;
+  week_end: string;
+  label: string;
+  capacity_minutes: number;
+  planned_minutes: number;
+  capacity_points: number;
+  planned_points: number;
+  over_committed: boolean;
+  tasks: PlannedTask[];
+}
+
+export interface CapacityPlan {
+  weeks: CapacityWeek[];
+  over_committed_weeks: number;
+  unscheduled_minutes: number;
+  unscheduled_points: number;
+  unestimated_tasks: number;
+}
+
+export interface ColorPalette {
+  colors: { hex: string; name: string }[];
+  icons: string[];
+  suggested_color: string;
+}
+
+export interface CsvMapping {
+  title?: string | null;
+  description?: string | null;
+  status?: string | null;
+  priority?: string | null;
+  due_date?: string | null;
+  tags?: string | null;
+  estimated_time?: string | null;
+  project?: string | null;
+  project_id?: number | null;
+}
+
+export interface CsvPreview {
+  headers: string[];
+  sample_rows: string[][];
+  total_rows: number;
+  delimiter: string;
+  suggested_mapping: CsvMapping;
+}
+
+export interface CsvImportResult {
+  imported_tasks: number;
+  created_projects: number;
+  errors: { row: number; message: string }[];
+}
+
+export type ExportScope =
+  | { type: 'workspace' }
+  | { type: 'project'; project_id: number }
+  | { type: 'tasks'; task_ids: number[] };
+
+export interface TemplateInfo {
+  name: string;
+  builtin: boolean;
+  overridden: boolean;
+}
+
+export interface OnboardingState {
+  completed: boolean;
+  completed_at?: string | null;
+  sample_project_id?: number | null;
+}
+
+export interface SampleWorkspace {
+  project: Project;
+  tasks_created: number;
+}
+
+export interface WorkspaceOverview {
+  total_tasks: number;
+  total_projects: number;
+  by_status: { todo: number; in_progress: number; done: number };
+  by_priority: { low: number; medium: number; high: number }; // Open tasks only
+  overdue_tasks: number;
+  minutes_logged_this_week: number;
+  week_start: string;
+  upcoming_deadlines: UpcomingDeadline[];
+  most_active_projects: ProjectActivity[];
+}
+
+export interface UpcomingDeadline {
+  task_id: number;
+  title: string;
+  project_id: number;
+  project_name: string;
+  due_date: string;
+  priority: TaskPriority;
+}
+
+export interface ProjectActivity {
+  project_id: number;
+  name: string;
+  color?: string | null;
+  icon?: string | null;
+  open_tasks: number;
+  recently_updated_tasks: number;
+  minutes_logged_this_week: number;
+}
+
+export type MyWorkReason = 'assigned' | 'watching' | 'commented';
+
+export interface MyWorkItem {
+  task: Task;
+  reasons: MyWorkReason[];
+}
+
+export interface DataLocation {
+  data_file: string;
+  portable: boolean;
+  relocated: boolean;
+}
+
+export interface RelocationResult {
+  location: DataLocation;
+  moved_dirs: string[];
+  updated_attachments: number;
+}
+
+export interface WeeklyReviewSettings {
+  enabled: boolean;
+  weekday: WeekStart;
+  time: string; // HH:MM
+  stale_days: number;
+  notify_webhooks: boolean;
+  last_reminded_at?: string;
+}
+
+export interface ReviewItem {
+  task_id: number;
+  title: string;
+  project_id: number;
+  project_name: string;
+  status: TaskStatus;
+  priority: TaskPriority;
+  due_date?: string;
+  days_since_update: number;
+}
+
+// Returned by get_review_packet and sent with the weekly-review-due event
+export interface ReviewPacket {
+  generated_at: string;
+  project_id?: number;
+  open_tasks: number;
+  stale_days: number;
+  overdue: ReviewItem[];
+  stale: ReviewItem[];
+  unestimated: ReviewItem[];
+}
+
+export type BoardGrouping = 'status' | 'priority' | 'assignee' | 'tag';
+
+export interface BoardGroup {
+  key: string;
+  title: string;
+}
+
+export interface BoardLane {
+  key: string;
+  title: string;
+  cells: Task[][]; // One per column, in column order
+  task_count: number;
+}
+
+export interface Board {
+  project_id: number;
+  group_by: BoardGrouping;
+  lane_by?: BoardGrouping;
+  columns: BoardGroup[];
+  lanes: BoardLane[];
+}
+
+export interface BoardImportResult {
+  project_id: number;
+  project_created: boolean;
+  imported_tasks: number;
+  updated_tasks: number;
+  skipped: string[];
+  unmapped_columns: string[]; // Imported as To Do
+}
+
+export interface GithubImportOptions {
+  owner: string;
+  number: number;
+  token: string; // Needs the read:project scope; not stored
+  project_id?: number; // Defaults to a project named after the board
+  status_field?: string; // "Status" by default
+  status_map?: Record<string, TaskStatus>;
+  tag_fields?: string[];
+}
+
+// A CSV exported from a query's results, or a query run through the REST API
+export type AzureDevopsSource =
+  | { type: 'csv'; content: string }
+  | {
+      type: 'query';
+      organization: string; // Name, or the organization/collection URL
+      project: string;
+      token: string; // Personal access token with Work Items (Read); not stored
+      query_id?: string; // A saved query; otherwise wiql runs
+      wiql?: string;
+    };
+
+export interface OpmlImportResult {
+  created_projects: number;
+  imported_tasks: number;
+  imported_subtasks: number;
+}
+
+export interface InboxInfo {
+  running: boolean;
+  port: number;
+  token?: string | null;
+  project_id?: number | null; // None uses the "Inbox" project
+  url?: string | null; // POST here with Authorization: Bearer <token>
+}
+
+// Body of POST /inbox
+export interface InboxItem {
+  title: string;
+  notes?: string;
+  tags?: string[] | string; // A string is split on commas
+}
+
+export interface EmailInboxSettings {
+  enabled: boolean;
+  host: string;
+  port: number; // IMAP over TLS, 993
+  username: string;
+  password?: string | null;
+  folder: string;
+  project_id?: number | null; // None uses the "Inbox" project
+  poll_minutes: number;
+  uid_validity?: number | null; // Maintained by the poller
+  last_uid?: number | null;
+  last_checked_at?: string | null;
+  last_error?: string | null;
+}
+
+// Payload of the email-tasks-imported event
+export interface EmailPollResult {
+  imported_tasks: number;
+  attachments: number;
+  skipped: string[];
+}
+
+export type RemoteBackupTarget =
+  | {
+      type: 'webdav';
+      url: string; // The folder to upload to
+      username: string;
+      password?: string | null;
+    }
+  | {
+      type: 's3';
+      endpoint: string; // e.g. https://s3.eu-central-1.amazonaws.com
+      region: string;
+      bucket: string;
+      prefix?: string; // Folder inside the bucket
+      access_key_id: string;
+      secret_access_key?: string | null;
+      path_style?: boolean; // endpoint/bucket/key, as MinIO expects
+    };
+
+export interface RemoteBackupSettings {
+  enabled: boolean;
+  target?: RemoteBackupTarget | null;
+  interval_hours: number;
+  keep: number; // Older uploads are deleted; 0 keeps them all
+  passphrase?: string | null; // Uploads are encrypted with it; without it they can't be restored
+  last_backup_at?: string | null; // Maintained by the scheduler
+  last_error?: string | null;
+}
+
+// Result of sync_now
+export interface SyncResult {
+  pushed: number; // changes uploaded
+  applied: number; // changes from other devices that were applied
+  skipped: string[]; // tasks whose project isn't on this device
+}
+
+// Payload of the remote-backup-completed event
+export interface RemoteBackup {
+  id: string; // File name, as restore_from_remote takes it
+  size: number;
+  created_at?: string | null;
+}
+
+// Minutes, or text such as "2h 30m" or "1d" that the backend parses
+export type DurationInput = number | string;
+
+// Payload of the task-delete-scheduled, task-delete-cancelled and task-deleted events
+export interface PendingDelete {
+  task_id: number;
+  title: string;
+  delete_at: string;
+}
+
+export interface DeleteFailure {
+  task_id: number;
+  error: string;
+}
+
+export interface BulkTagResult {
+  task_id: number;
+  changed: boolean;
+  tags: string[]; // the task's tags afterwards
+  error?: string | null;
+}
+
 export interface ProjectStats {
   project_id: number;
   total_tasks: number;
@@ -143,4 +607,7 @@ export interface ProjectStats {
   in_progress_tasks: number;
   done_tasks: number;
   progress_percentage: number;
+  total_points: number;
+  done_points: number;
+  unsized_tasks: number;
 }
\ No newline at end of file